@@ -0,0 +1,652 @@
+// Авторизация запроса: Basic, Digest, Bearer, API key, OAuth 2.0, AWS SigV4, HMAC, JWT.
+// Применяется при отправке, поверх таблиц заголовков и параметров
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use iced::widget::{button, column, container, pick_list, row, space, text, text_input};
+use iced::{Center, Element, Fill, Font, Shrink};
+
+use crate::digest_auth;
+use crate::environment;
+use crate::oauth::{GrantType, OAuth2Config, Token};
+use crate::signing::{
+    self, HmacAlgorithm, HmacConfig, JwtAlgorithm, JwtConfig, SignatureEncoding,
+};
+use crate::sigv4::{self, SigV4Config};
+use crate::{HeaderParam, Message, QueryParam};
+
+// Тип авторизации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AuthKind {
+    #[default]
+    None,
+    Basic,
+    Digest, // Логин/пароль те же, что у Basic
+    Bearer,
+    ApiKey,
+    OAuth2,
+    AwsSigV4,
+    Hmac,
+    Jwt,
+}
+
+impl AuthKind {
+    pub const ALL: &'static [AuthKind] = &[
+        AuthKind::None,
+        AuthKind::Basic,
+        AuthKind::Digest,
+        AuthKind::Bearer,
+        AuthKind::ApiKey,
+        AuthKind::OAuth2,
+        AuthKind::AwsSigV4,
+        AuthKind::Hmac,
+        AuthKind::Jwt,
+    ];
+}
+
+impl std::fmt::Display for AuthKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthKind::None => write!(f, "No auth"),
+            AuthKind::Basic => write!(f, "Basic"),
+            AuthKind::Digest => write!(f, "Digest"),
+            AuthKind::Bearer => write!(f, "Bearer token"),
+            AuthKind::ApiKey => write!(f, "API key"),
+            AuthKind::OAuth2 => write!(f, "OAuth 2.0"),
+            AuthKind::AwsSigV4 => write!(f, "AWS Signature v4"),
+            AuthKind::Hmac => write!(f, "HMAC signature"),
+            AuthKind::Jwt => write!(f, "JWT"),
+        }
+    }
+}
+
+// Куда кладём API ключ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ApiKeyPlacement {
+    #[default]
+    Header,
+    Query,
+}
+
+impl ApiKeyPlacement {
+    pub const ALL: &'static [ApiKeyPlacement] = &[ApiKeyPlacement::Header, ApiKeyPlacement::Query];
+}
+
+impl std::fmt::Display for ApiKeyPlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyPlacement::Header => write!(f, "Header"),
+            ApiKeyPlacement::Query => write!(f, "Query param"),
+        }
+    }
+}
+
+// Данные всех типов храним сразу, чтобы переключение типа их не теряло
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Auth {
+    #[serde(default)]
+    pub kind: AuthKind,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub token: String,
+    #[serde(default)]
+    pub api_key_name: String,
+    #[serde(default)]
+    pub api_key_value: String,
+    #[serde(default)]
+    pub api_key_placement: ApiKeyPlacement,
+    #[serde(default)]
+    pub oauth2: OAuth2Config,
+    #[serde(default)]
+    pub aws: SigV4Config,
+    #[serde(default)]
+    pub hmac: HmacConfig,
+    #[serde(default)]
+    pub jwt: JwtConfig,
+}
+
+impl Auth {
+    pub fn is_none(&self) -> bool {
+        self.kind == AuthKind::None
+    }
+
+    // Подставляем переменные окружения во все поля
+    pub fn resolve(&self, resolve: impl Fn(&str) -> String) -> Self {
+        Self {
+            kind: self.kind,
+            username: resolve(&self.username),
+            password: resolve(&self.password),
+            token: resolve(&self.token),
+            api_key_name: resolve(&self.api_key_name),
+            api_key_value: resolve(&self.api_key_value),
+            api_key_placement: self.api_key_placement,
+            oauth2: self.oauth2.resolve(&resolve),
+            aws: self.aws.resolve(&resolve),
+            hmac: self.hmac.resolve(&resolve),
+            jwt: self.jwt.resolve(&resolve),
+        }
+    }
+
+    // Копия для файлов на диске: секреты стираем, ссылки {{name}} оставляем -
+    // сами значения живут в окружении
+    pub fn without_secrets(&self) -> Self {
+        let secret = |value: &str| {
+            if environment::is_reference(value) {
+                value.to_string()
+            } else {
+                String::new()
+            }
+        };
+        Self {
+            password: secret(&self.password),
+            token: secret(&self.token),
+            api_key_value: secret(&self.api_key_value),
+            oauth2: OAuth2Config {
+                client_secret: secret(&self.oauth2.client_secret),
+                password: secret(&self.oauth2.password),
+                refresh_token: secret(&self.oauth2.refresh_token),
+                ..self.oauth2.clone()
+            },
+            aws: SigV4Config {
+                secret_key: secret(&self.aws.secret_key),
+                session_token: secret(&self.aws.session_token),
+                ..self.aws.clone()
+            },
+            hmac: HmacConfig {
+                secret: secret(&self.hmac.secret),
+                ..self.hmac.clone()
+            },
+            jwt: JwtConfig {
+                secret: secret(&self.jwt.secret),
+                ..self.jwt.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    // Обратно к without_secrets: стёртые секреты берём из live, если тип тот же.
+    // None - взять неоткуда
+    pub fn with_secrets_from(&self, live: &Auth) -> Option<Self> {
+        if self.kind != live.kind {
+            return None;
+        }
+        let secret = |value: &str, live: &str| {
+            if value.is_empty() { live } else { value }.to_string()
+        };
+        Some(Self {
+            password: secret(&self.password, &live.password),
+            token: secret(&self.token, &live.token),
+            api_key_value: secret(&self.api_key_value, &live.api_key_value),
+            oauth2: OAuth2Config {
+                client_secret: secret(&self.oauth2.client_secret, &live.oauth2.client_secret),
+                password: secret(&self.oauth2.password, &live.oauth2.password),
+                refresh_token: secret(&self.oauth2.refresh_token, &live.oauth2.refresh_token),
+                ..self.oauth2.clone()
+            },
+            aws: SigV4Config {
+                secret_key: secret(&self.aws.secret_key, &live.aws.secret_key),
+                session_token: secret(&self.aws.session_token, &live.aws.session_token),
+                ..self.aws.clone()
+            },
+            hmac: HmacConfig {
+                secret: secret(&self.hmac.secret, &live.hmac.secret),
+                ..self.hmac.clone()
+            },
+            jwt: JwtConfig {
+                secret: secret(&self.jwt.secret, &live.jwt.secret),
+                ..self.jwt.clone()
+            },
+            ..self.clone()
+        })
+    }
+
+    // Добавляем авторизацию к заголовкам/параметрам.
+    // Одноимённый заголовок из таблицы заменяется.
+    // Для OAuth2 нужен уже полученный токен, без него ничего не добавляем
+    pub fn apply(
+        &self,
+        headers: &mut Vec<HeaderParam>,
+        query_params: &mut Vec<QueryParam>,
+        oauth_token: Option<&Token>,
+    ) {
+        let mut set_header = |key: &str, value: String| {
+            headers.retain(|h| !h.key.eq_ignore_ascii_case(key));
+            headers.push(HeaderParam::new(key.to_string(), value));
+        };
+
+        match self.kind {
+            AuthKind::None => {}
+            AuthKind::Basic => {
+                let credentials = base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", self.username, self.password));
+                set_header("Authorization", format!("Basic {}", credentials));
+            }
+            AuthKind::Bearer => set_header("Authorization", format!("Bearer {}", self.token)),
+            AuthKind::ApiKey if self.api_key_name.is_empty() => {}
+            AuthKind::ApiKey => match self.api_key_placement {
+                ApiKeyPlacement::Header => {
+                    set_header(&self.api_key_name, self.api_key_value.clone())
+                }
+                ApiKeyPlacement::Query => query_params.extend(self.query_param()),
+            },
+            AuthKind::OAuth2 => {
+                if let Some(token) = oauth_token {
+                    set_header("Authorization", token.header_value());
+                }
+            }
+            // Подпись зависит от итогового запроса, ставится в sign()
+            AuthKind::AwsSigV4 | AuthKind::Hmac | AuthKind::Jwt => {}
+            // Digest нужен nonce из ответа сервера, см. answer_challenge()
+            AuthKind::Digest => {}
+        }
+    }
+
+    // Что авторизация добавляет в query: API ключ с размещением Query
+    pub fn query_param(&self) -> Option<QueryParam> {
+        let in_query = self.kind == AuthKind::ApiKey
+            && !self.api_key_name.is_empty()
+            && self.api_key_placement == ApiKeyPlacement::Query;
+        in_query.then(|| QueryParam::new(self.api_key_name.clone(), self.api_key_value.clone()))
+    }
+
+    // Подписываем уже собранный запрос (последний шаг перед отправкой)
+    pub fn sign(&self, request: &mut reqwest::Request) -> Result<(), String> {
+        let now = std::time::SystemTime::now();
+        match self.kind {
+            AuthKind::AwsSigV4 => sigv4::sign(&self.aws, request, now),
+            AuthKind::Hmac => signing::sign_hmac(&self.hmac, request, now),
+            AuthKind::Jwt => signing::sign_jwt(&self.jwt, request, now),
+            _ => Ok(()),
+        }
+    }
+
+    // Сервер ответил вызовом (401 + WWW-Authenticate) - готовим повторный запрос.
+    // true - запрос подписан и его нужно отправить ещё раз
+    pub fn answer_challenge(
+        &self,
+        request: &mut reqwest::Request,
+        response: &reqwest::Response,
+    ) -> Result<bool, String> {
+        match self.kind {
+            AuthKind::Digest if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                digest_auth::authorize(&self.username, &self.password, request, response.headers())
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // Ответит ли авторизация на вызов в этом ответе (нужен повторный запрос)
+    pub fn has_challenge(&self, response: &reqwest::Response) -> bool {
+        self.kind == AuthKind::Digest
+            && response.status() == reqwest::StatusCode::UNAUTHORIZED
+            && digest_auth::has_challenge(response.headers())
+    }
+}
+
+// Поле с секретом: скрыто, пока не нажали 👁
+fn secret_input<'a>(
+    placeholder: &'a str,
+    value: &'a str,
+    reveal: bool,
+    on_input: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    row![
+        text_input(placeholder, value)
+            .on_input(on_input)
+            .secure(!reveal)
+            .width(Fill)
+            .padding(5),
+        button(text(if reveal { "🙈" } else { "👁" }).size(14))
+            .on_press(Message::ToggleAuthSecretVisible)
+            .padding(5)
+            .style(button::secondary),
+    ]
+    .spacing(5)
+    .align_y(Center)
+    .into()
+}
+
+// Поля OAuth2: какие нужны, зависит от гранта
+fn oauth2_view<'a>(
+    auth: &'a Auth,
+    reveal: bool,
+    token: Option<&'a Token>,
+    fetching: bool,
+    error: Option<&'a String>,
+) -> Element<'a, Message> {
+    let config = &auth.oauth2;
+    let changed = move |oauth2: OAuth2Config| {
+        Message::AuthChanged(Box::new(Auth { oauth2, ..auth.clone() }))
+    };
+
+    let mut fields = column![
+        pick_list(GrantType::ALL, Some(config.grant), move |grant| {
+            changed(OAuth2Config { grant, ..config.clone() })
+        })
+        .padding(5),
+        text_input("Token URL", &config.token_url)
+            .on_input(move |token_url| changed(OAuth2Config { token_url, ..config.clone() }))
+            .padding(5),
+    ]
+    .spacing(5);
+
+    if config.grant == GrantType::AuthorizationCode {
+        let port = config.redirect_port.to_string();
+        fields = fields.push(
+            row![
+                text_input("Authorization URL", &config.auth_url)
+                    .on_input(move |auth_url| changed(OAuth2Config { auth_url, ..config.clone() }))
+                    .width(Fill)
+                    .padding(5),
+                text("Redirect port:").size(12),
+                text_input("8765", &port)
+                    .on_input(move |input| {
+                        let redirect_port = input.trim().parse().unwrap_or(config.redirect_port);
+                        changed(OAuth2Config { redirect_port, ..config.clone() })
+                    })
+                    .width(70)
+                    .padding(5),
+            ]
+            .spacing(5)
+            .align_y(Center),
+        );
+    }
+
+    fields = fields.push(
+        row![
+            text_input("Client ID", &config.client_id)
+                .on_input(move |client_id| changed(OAuth2Config { client_id, ..config.clone() }))
+                .width(Fill)
+                .padding(5),
+            text_input("Scope", &config.scope)
+                .on_input(move |scope| changed(OAuth2Config { scope, ..config.clone() }))
+                .width(Fill)
+                .padding(5),
+        ]
+        .spacing(5),
+    );
+    fields = fields.push(secret_input(
+        "Client secret (optional with PKCE)",
+        &config.client_secret,
+        reveal,
+        move |client_secret| changed(OAuth2Config { client_secret, ..config.clone() }),
+    ));
+
+    match config.grant {
+        GrantType::Password => {
+            fields = fields
+                .push(
+                    text_input("Username", &config.username)
+                        .on_input(move |username| changed(OAuth2Config { username, ..config.clone() }))
+                        .padding(5),
+                )
+                .push(secret_input("Password", &config.password, reveal, move |password| {
+                    changed(OAuth2Config { password, ..config.clone() })
+                }));
+        }
+        GrantType::RefreshToken => {
+            fields = fields.push(secret_input(
+                "Refresh token",
+                &config.refresh_token,
+                reveal,
+                move |refresh_token| changed(OAuth2Config { refresh_token, ..config.clone() }),
+            ));
+        }
+        GrantType::ClientCredentials | GrantType::AuthorizationCode => {}
+    }
+
+    let status: Element<Message> = match (fetching, error, token) {
+        (true, _, _) => text("Requesting token...").size(12).into(),
+        (false, Some(error), _) => text(error).size(12).style(text::danger).into(),
+        (false, None, Some(token)) => text(token.status()).size(12).style(text::success).into(),
+        (false, None, None) => text("No token yet, it will be requested on send")
+            .size(12)
+            .style(text::secondary)
+            .into(),
+    };
+
+    let mut get_token = button(text("Get new token").size(12))
+        .padding(5)
+        .style(button::primary);
+    if !fetching {
+        get_token = get_token.on_press(Message::FetchOAuthToken);
+    }
+
+    fields
+        .push(
+            row![
+                status,
+                space().width(Fill),
+                get_token,
+                button(text("Clear token").size(12))
+                    .on_press(Message::ClearOAuthToken)
+                    .padding(5)
+                    .style(button::secondary),
+            ]
+            .spacing(5)
+            .align_y(Center),
+        )
+        .into()
+}
+
+// Ключи AWS и область подписи
+fn aws_view(auth: &Auth, reveal: bool) -> Element<'_, Message> {
+    let config = &auth.aws;
+    let changed = move |aws: SigV4Config| {
+        Message::AuthChanged(Box::new(Auth { aws, ..auth.clone() }))
+    };
+
+    column![
+        text_input("Access key ID", &config.access_key)
+            .on_input(move |access_key| changed(SigV4Config { access_key, ..config.clone() }))
+            .padding(5),
+        secret_input("Secret access key", &config.secret_key, reveal, move |secret_key| {
+            changed(SigV4Config { secret_key, ..config.clone() })
+        }),
+        secret_input(
+            "Session token (optional)",
+            &config.session_token,
+            reveal,
+            move |session_token| changed(SigV4Config { session_token, ..config.clone() }),
+        ),
+        row![
+            text_input("Region (us-east-1)", &config.region)
+                .on_input(move |region| changed(SigV4Config { region, ..config.clone() }))
+                .width(Fill)
+                .padding(5),
+            text_input("Service (s3, execute-api)", &config.service)
+                .on_input(move |service| changed(SigV4Config { service, ..config.clone() }))
+                .width(Fill)
+                .padding(5),
+        ]
+        .spacing(5),
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Секрет, шаблон строки для подписи и куда писать подпись
+fn hmac_view(auth: &Auth, reveal: bool) -> Element<'_, Message> {
+    let config = &auth.hmac;
+    let changed = move |hmac: HmacConfig| {
+        Message::AuthChanged(Box::new(Auth { hmac, ..auth.clone() }))
+    };
+
+    column![
+        row![
+            pick_list(HmacAlgorithm::ALL, Some(config.algorithm), move |algorithm| {
+                changed(HmacConfig { algorithm, ..config.clone() })
+            })
+            .padding(5),
+            pick_list(SignatureEncoding::ALL, Some(config.encoding), move |encoding| {
+                changed(HmacConfig { encoding, ..config.clone() })
+            })
+            .padding(5),
+        ]
+        .spacing(5),
+        secret_input("Secret", &config.secret, reveal, move |secret| {
+            changed(HmacConfig { secret, ..config.clone() })
+        }),
+        text_input("String to sign", &config.template)
+            .on_input(move |template| changed(HmacConfig { template, ..config.clone() }))
+            .font(Font::MONOSPACE)
+            .padding(5),
+        text(signing::TEMPLATE_HINT).size(12).style(text::secondary),
+        row![
+            text_input("Signature header", &config.header)
+                .on_input(move |header| changed(HmacConfig { header, ..config.clone() }))
+                .width(Fill)
+                .padding(5),
+            text_input("Prefix (v1=)", &config.prefix)
+                .on_input(move |prefix| changed(HmacConfig { prefix, ..config.clone() }))
+                .width(100)
+                .padding(5),
+            text_input("Timestamp header", &config.timestamp_header)
+                .on_input(move |timestamp_header| {
+                    changed(HmacConfig { timestamp_header, ..config.clone() })
+                })
+                .width(Fill)
+                .padding(5),
+        ]
+        .spacing(5),
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Алгоритм, ключ и claims для JWT
+fn jwt_view(auth: &Auth, reveal: bool) -> Element<'_, Message> {
+    let config = &auth.jwt;
+    let changed = move |jwt: JwtConfig| {
+        Message::AuthChanged(Box::new(Auth { jwt, ..auth.clone() }))
+    };
+
+    let key: Element<Message> = if config.algorithm.uses_key_file() {
+        text_input("Private key file (PEM)", &config.key_file)
+            .on_input(move |key_file| changed(JwtConfig { key_file, ..config.clone() }))
+            .padding(5)
+            .into()
+    } else {
+        secret_input("Secret", &config.secret, reveal, move |secret| {
+            changed(JwtConfig { secret, ..config.clone() })
+        })
+    };
+    let lifetime = config.lifetime_secs.to_string();
+
+    column![
+        row![
+            pick_list(JwtAlgorithm::ALL, Some(config.algorithm), move |algorithm| {
+                changed(JwtConfig { algorithm, ..config.clone() })
+            })
+            .padding(5),
+            text_input("Key ID (kid)", &config.key_id)
+                .on_input(move |key_id| changed(JwtConfig { key_id, ..config.clone() }))
+                .width(Fill)
+                .padding(5),
+            text("Lifetime, s:").size(12),
+            text_input("300", &lifetime)
+                .on_input(move |input| {
+                    let lifetime_secs = input.trim().parse().unwrap_or(config.lifetime_secs);
+                    changed(JwtConfig { lifetime_secs, ..config.clone() })
+                })
+                .width(70)
+                .padding(5),
+        ]
+        .spacing(5)
+        .align_y(Center),
+        key,
+        text_input("Claims JSON {\"iss\": \"...\", \"sub\": \"...\"}", &config.claims)
+            .on_input(move |claims| changed(JwtConfig { claims, ..config.clone() }))
+            .font(Font::MONOSPACE)
+            .padding(5),
+        text("iat and exp are added unless set in claims; sent as Authorization: Bearer")
+            .size(12)
+            .style(text::secondary),
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Секция Auth рядом с таблицами Query/Headers
+pub fn view<'a>(
+    auth: &'a Auth,
+    reveal: bool,
+    oauth_token: Option<&'a Token>,
+    oauth_fetching: bool,
+    oauth_error: Option<&'a String>,
+) -> Element<'a, Message> {
+    let changed = |auth: Auth| Message::AuthChanged(Box::new(auth));
+
+    let kind = row![
+        text("Auth:").size(16),
+        pick_list(AuthKind::ALL, Some(auth.kind), move |kind| {
+            changed(Auth { kind, ..auth.clone() })
+        })
+        .padding(5),
+    ]
+    .spacing(10)
+    .align_y(Center);
+
+    let fields: Element<Message> = match auth.kind {
+        AuthKind::None => container(text("Request is sent without authorization").style(text::secondary))
+            .padding(10)
+            .center_x(Shrink)
+            .into(),
+        AuthKind::Basic | AuthKind::Digest => column![
+            text_input("Username", &auth.username)
+                .on_input(move |username| changed(Auth { username, ..auth.clone() }))
+                .padding(5),
+            secret_input("Password", &auth.password, reveal, move |password| {
+                changed(Auth { password, ..auth.clone() })
+            }),
+        ]
+        .spacing(5)
+        .into(),
+        AuthKind::Bearer => secret_input("Token", &auth.token, reveal, move |token| {
+            changed(Auth { token, ..auth.clone() })
+        }),
+        AuthKind::ApiKey => column![
+            row![
+                text_input("Key name (X-API-Key)", &auth.api_key_name)
+                    .on_input(move |api_key_name| changed(Auth { api_key_name, ..auth.clone() }))
+                    .width(Fill)
+                    .padding(5),
+                pick_list(
+                    ApiKeyPlacement::ALL,
+                    Some(auth.api_key_placement),
+                    move |api_key_placement| changed(Auth { api_key_placement, ..auth.clone() })
+                )
+                .padding(5),
+            ]
+            .spacing(5)
+            .align_y(Center),
+            secret_input("Key value", &auth.api_key_value, reveal, move |api_key_value| {
+                changed(Auth { api_key_value, ..auth.clone() })
+            }),
+        ]
+        .spacing(5)
+        .into(),
+        AuthKind::OAuth2 => oauth2_view(auth, reveal, oauth_token, oauth_fetching, oauth_error),
+        AuthKind::AwsSigV4 => aws_view(auth, reveal),
+        AuthKind::Hmac => hmac_view(auth, reveal),
+        AuthKind::Jwt => jwt_view(auth, reveal),
+    };
+
+    container(
+        column![
+            kind,
+            fields,
+            text("Values may use {{variables}} from the environment")
+                .size(12)
+                .style(text::secondary),
+        ]
+        .spacing(10)
+        .padding(10),
+    )
+    .width(Fill)
+    .style(container::bordered_box)
+    .into()
+}
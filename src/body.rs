@@ -0,0 +1,604 @@
+// Тело запроса: JSON или текст с выбранным типом из редактора,
+// форма x-www-form-urlencoded, multipart/form-data с файлами или файл целиком
+use serde::{Deserialize, Serialize};
+
+use iced::widget::{button, column, container, pick_list, row, text, text_input};
+use iced::{Center, Element, Fill, Shrink};
+
+use crate::{graphql, jsonrpc};
+use crate::syntax::Syntax;
+use crate::{Message, QueryParam};
+
+// Что отправляем в теле
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BodyType {
+    #[default]
+    Json, // Текст редактора: валидный JSON уходит как JSON, остальное как есть
+    Raw, // Текст редактора с явным Content-Type
+    GraphQl, // Query из редактора и переменные - конвертом в JSON
+    JsonRpc, // Метод и params из редактора - конвертом JSON-RPC 2.0
+    FormUrlEncoded,
+    Multipart,
+    Binary, // Файл с диска как есть
+}
+
+impl BodyType {
+    pub const ALL: &'static [BodyType] = &[
+        BodyType::Json,
+        BodyType::Raw,
+        BodyType::GraphQl,
+        BodyType::JsonRpc,
+        BodyType::FormUrlEncoded,
+        BodyType::Multipart,
+        BodyType::Binary,
+    ];
+
+    // Тело берётся из текстового редактора
+    pub fn uses_editor(self) -> bool {
+        matches!(
+            self,
+            BodyType::Json | BodyType::Raw | BodyType::GraphQl | BodyType::JsonRpc
+        )
+    }
+}
+
+impl std::fmt::Display for BodyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodyType::Json => write!(f, "JSON"),
+            BodyType::Raw => write!(f, "Raw text"),
+            BodyType::GraphQl => write!(f, "GraphQL"),
+            BodyType::JsonRpc => write!(f, "JSON-RPC"),
+            BodyType::FormUrlEncoded => write!(f, "x-www-form-urlencoded"),
+            BodyType::Multipart => write!(f, "multipart/form-data"),
+            BodyType::Binary => write!(f, "Binary file"),
+        }
+    }
+}
+
+// Content-Type для сырого текста
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RawContentType {
+    #[default]
+    TextPlain,
+    Xml,
+    Csv,
+    Yaml,
+    Html,
+    GraphQl,
+}
+
+impl RawContentType {
+    pub const ALL: &'static [RawContentType] = &[
+        RawContentType::TextPlain,
+        RawContentType::Xml,
+        RawContentType::Csv,
+        RawContentType::Yaml,
+        RawContentType::Html,
+        RawContentType::GraphQl,
+    ];
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            RawContentType::TextPlain => "text/plain",
+            RawContentType::Xml => "application/xml",
+            RawContentType::Csv => "text/csv",
+            RawContentType::Yaml => "application/yaml",
+            RawContentType::Html => "text/html",
+            RawContentType::GraphQl => "application/graphql",
+        }
+    }
+
+    pub fn syntax(self) -> Syntax {
+        match self {
+            RawContentType::TextPlain | RawContentType::Csv => Syntax::Plain,
+            RawContentType::Xml => Syntax::Xml,
+            RawContentType::Yaml => Syntax::Yaml,
+            RawContentType::Html => Syntax::Html,
+            RawContentType::GraphQl => Syntax::GraphQl,
+        }
+    }
+}
+
+impl std::fmt::Display for RawContentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mime())
+    }
+}
+
+// Для файла без своего типа
+const OCTET_STREAM: &str = "application/octet-stream";
+
+// Часть multipart: текстовое поле или файл с диска
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PartKind {
+    #[default]
+    Text,
+    File,
+}
+
+impl PartKind {
+    pub const ALL: &'static [PartKind] = &[PartKind::Text, PartKind::File];
+}
+
+impl std::fmt::Display for PartKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartKind::Text => write!(f, "Text"),
+            PartKind::File => write!(f, "File"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    #[serde(default)]
+    pub kind: PartKind,
+    #[serde(default)]
+    pub value: String, // Текст или путь к файлу
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub filename: String, // Пусто - имя файла из пути
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub content_type: String, // Пусто - по расширению файла
+}
+
+impl MultipartPart {
+    pub fn resolve(&self, resolve: impl Fn(&str) -> String) -> Self {
+        Self {
+            name: resolve(&self.name),
+            kind: self.kind,
+            value: resolve(&self.value),
+            filename: resolve(&self.filename),
+            content_type: resolve(&self.content_type),
+        }
+    }
+
+    // Часть для reqwest: файл читается потоком при отправке
+    async fn to_reqwest(&self) -> Result<reqwest::multipart::Part, String> {
+        let mut part = match self.kind {
+            PartKind::Text => reqwest::multipart::Part::text(self.value.clone()),
+            PartKind::File => reqwest::multipart::Part::file(&self.value)
+                .await
+                .map_err(|e| format!("Failed to open {}: {}", self.value, e))?,
+        };
+        if !self.filename.is_empty() {
+            part = part.file_name(self.filename.clone());
+        }
+        if !self.content_type.is_empty() {
+            part = part
+                .mime_str(&self.content_type)
+                .map_err(|_| format!("Invalid content type: {}", self.content_type))?;
+        }
+        Ok(part)
+    }
+}
+
+// Диалог выбора файла
+pub async fn pick_file() -> Option<String> {
+    rfd::AsyncFileDialog::new()
+        .pick_file()
+        .await
+        .map(|file| file.path().to_string_lossy().into_owned())
+}
+
+// Данные всех режимов храним сразу, чтобы переключение их не теряло.
+// Текст редактора лежит отдельно (поле body у запроса)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BodyData {
+    #[serde(default)]
+    pub kind: BodyType,
+    #[serde(default)]
+    pub form_fields: Vec<QueryParam>,
+    #[serde(default)]
+    pub parts: Vec<MultipartPart>,
+    #[serde(default)]
+    pub raw_content_type: RawContentType,
+    #[serde(default)]
+    pub file: String, // Путь к файлу для Binary
+    #[serde(default)]
+    pub file_content_type: String, // Пусто - application/octet-stream
+    #[serde(default)]
+    pub graphql_variables: String, // JSON объект
+    #[serde(default)]
+    pub graphql_operation: String, // Пусто - первая операция документа
+    #[serde(default)]
+    pub jsonrpc_method: String,
+    #[serde(default)]
+    pub jsonrpc_notification: bool, // Вызов без id
+    #[serde(default)]
+    pub jsonrpc_batch: bool, // В редакторе массив вызовов вместо params
+}
+
+impl BodyData {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // Синтаксис текста в редакторе: подсветка, проверка, форматирование
+    pub fn syntax(&self) -> Syntax {
+        match self.kind {
+            BodyType::Json | BodyType::JsonRpc => Syntax::Json,
+            BodyType::Raw => self.raw_content_type.syntax(),
+            BodyType::GraphQl => Syntax::GraphQl,
+            _ => Syntax::Plain,
+        }
+    }
+
+    // Вызовы JSON-RPC с подставленными переменными окружения
+    pub fn jsonrpc_calls(
+        &self,
+        text: &str,
+        resolve: impl Fn(&str) -> String,
+    ) -> Result<Vec<jsonrpc::Call>, String> {
+        jsonrpc::calls(
+            &resolve(&self.jsonrpc_method),
+            &resolve(text),
+            self.jsonrpc_notification,
+            self.jsonrpc_batch,
+        )
+    }
+
+    // Тело для отправки: переменные окружения подставлены, пустые ключи формы пропущены.
+    // jsonrpc_id - id первого вызова JSON-RPC
+    pub fn prepare(
+        &self,
+        text: &str,
+        jsonrpc_id: u64,
+        resolve: impl Fn(&str) -> String,
+    ) -> RequestBody {
+        match self.kind {
+            BodyType::Json => RequestBody::Text(resolve(text)),
+            BodyType::Raw => {
+                RequestBody::Raw(resolve(text), self.raw_content_type.mime().to_string())
+            }
+            BodyType::GraphQl => RequestBody::Text(graphql::envelope(
+                &resolve(text),
+                &resolve(&self.graphql_variables),
+                &self.graphql_operation,
+            )),
+            // Кривые вызовы до отправки не доходят (проверка в SendRequest), здесь - как есть
+            BodyType::JsonRpc => RequestBody::Text(match self.jsonrpc_calls(text, &resolve) {
+                Ok(calls) => jsonrpc::envelope(&calls, jsonrpc_id, self.jsonrpc_batch),
+                Err(_) => resolve(text),
+            }),
+            BodyType::Binary => RequestBody::File(
+                resolve(&self.file),
+                match resolve(&self.file_content_type) {
+                    content_type if content_type.trim().is_empty() => OCTET_STREAM.to_string(),
+                    content_type => content_type,
+                },
+            ),
+            BodyType::FormUrlEncoded => RequestBody::Form(
+                self.form_fields
+                    .iter()
+                    .filter(|field| !field.key.is_empty())
+                    .map(|field| (resolve(&field.key), resolve(&field.value)))
+                    .collect(),
+            ),
+            BodyType::Multipart => RequestBody::Multipart(
+                self.parts
+                    .iter()
+                    .filter(|part| !part.name.is_empty())
+                    .map(|part| part.resolve(&resolve))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// Тело в том виде, в каком уйдёт
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    Text(String),
+    Form(Vec<(String, String)>),
+    Multipart(Vec<MultipartPart>),
+    Raw(String, String),  // Текст и Content-Type
+    File(String, String), // Путь и Content-Type
+}
+
+impl RequestBody {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            RequestBody::Text(text) => text.trim().is_empty(),
+            RequestBody::Form(fields) => fields.is_empty(),
+            RequestBody::Multipart(parts) => parts.is_empty(),
+            RequestBody::Raw(text, _) => text.trim().is_empty(),
+            RequestBody::File(path, _) => path.trim().is_empty(),
+        }
+    }
+
+    // Кладём тело в запрос. Content-Type ставим сами,
+    // только если его нет в заголовках (кроме multipart - там нужен boundary)
+    pub async fn apply(
+        self,
+        mut request: reqwest::RequestBuilder,
+        has_content_type: bool,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        Ok(match self {
+            // Попытка to JSON, если не получается - отправляем как текст
+            RequestBody::Text(text) => match serde_json::from_str::<serde_json::Value>(&text) {
+                Ok(json_value) => {
+                    // Это валидный JSON - отправляем как JSON
+                    request = request.json(&json_value);
+
+                    // Автоматически добавляем Content-Type если его нет
+                    if !has_content_type {
+                        request = request.header("Content-Type", "application/json");
+                    }
+                    request
+                }
+                // Не JSON - отправляем как обычный текст
+                Err(_) => {
+                    if !has_content_type {
+                        request = request.header("Content-Type", "text/plain; charset=utf-8");
+                    }
+                    request.body(text)
+                }
+            },
+            RequestBody::Raw(text, content_type) => {
+                if !has_content_type {
+                    request = request.header("Content-Type", content_type);
+                }
+                request.body(text)
+            }
+            // Файл не читаем в память целиком, а отдаём потоком
+            RequestBody::File(path, content_type) => {
+                let file = tokio::fs::File::open(&path)
+                    .await
+                    .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+                let length = file
+                    .metadata()
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", path, e))?
+                    .len();
+                if !has_content_type {
+                    request = request.header("Content-Type", content_type);
+                }
+                request
+                    .header("Content-Length", length)
+                    .body(reqwest::Body::from(file))
+            }
+            // reqwest сам кодирует пары и ставит Content-Type, если его нет
+            RequestBody::Form(fields) => request.form(&fields),
+            RequestBody::Multipart(parts) => {
+                let mut form = reqwest::multipart::Form::new();
+                for part in &parts {
+                    form = form.part(part.name.clone(), part.to_reqwest().await?);
+                }
+                request.multipart(form)
+            }
+        })
+    }
+}
+
+// Таблица полей формы (как таблица Query параметров)
+pub fn form_view(data: &BodyData) -> Element<'_, Message> {
+    let update = move |index: usize, field: QueryParam| {
+        let mut form_fields = data.form_fields.clone();
+        form_fields[index] = field;
+        Message::BodyDataChanged(BodyData { form_fields, ..data.clone() })
+    };
+
+    let items_table: Element<Message> = if data.form_fields.is_empty() {
+        container(text("No form fields added yet").style(text::secondary))
+            .padding(10)
+            .center_x(Shrink)
+            .into()
+    } else {
+        let rows: Vec<Element<Message>> = data
+            .form_fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                row![
+                    text_input("Key", &field.key)
+                        .on_input(move |key| update(index, QueryParam { key, ..field.clone() }))
+                        .width(200)
+                        .padding(5),
+                    text_input("Value", &field.value)
+                        .on_input(move |value| update(index, QueryParam { value, ..field.clone() }))
+                        .width(300)
+                        .padding(5),
+                    button(text("🗑️").size(14))
+                        .on_press({
+                            let mut form_fields = data.form_fields.clone();
+                            form_fields.remove(index);
+                            Message::BodyDataChanged(BodyData { form_fields, ..data.clone() })
+                        })
+                        .padding(5)
+                        .style(button::danger),
+                ]
+                .spacing(8)
+                .align_y(Center)
+                .into()
+            })
+            .collect();
+        column(rows).spacing(5).into()
+    };
+
+    let add = button(text("+ Add field").size(14))
+        .on_press({
+            let mut form_fields = data.form_fields.clone();
+            form_fields.push(QueryParam::new(String::new(), String::new()));
+            Message::BodyDataChanged(BodyData { form_fields, ..data.clone() })
+        })
+        .padding(5)
+        .style(button::success);
+
+    column![
+        items_table,
+        add,
+        text("Sent as application/x-www-form-urlencoded; values may use {{variables}}")
+            .size(12)
+            .style(text::secondary),
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Части multipart: имя, текст или файл, переопределения имени файла и типа
+pub fn multipart_view(data: &BodyData) -> Element<'_, Message> {
+    let update = move |index: usize, part: MultipartPart| {
+        let mut parts = data.parts.clone();
+        parts[index] = part;
+        Message::BodyDataChanged(BodyData { parts, ..data.clone() })
+    };
+
+    let items_table: Element<Message> = if data.parts.is_empty() {
+        container(text("No parts added yet").style(text::secondary))
+            .padding(10)
+            .center_x(Shrink)
+            .into()
+    } else {
+        let rows: Vec<Element<Message>> = data
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let value: Element<Message> = match part.kind {
+                    PartKind::Text => text_input("Value", &part.value)
+                        .on_input(move |value| update(index, MultipartPart { value, ..part.clone() }))
+                        .width(Fill)
+                        .padding(5)
+                        .into(),
+                    PartKind::File => row![
+                        text_input("File path", &part.value)
+                            .on_input(move |value| {
+                                update(index, MultipartPart { value, ..part.clone() })
+                            })
+                            .width(Fill)
+                            .padding(5),
+                        button(text("📂").size(14))
+                            .on_press(Message::PickMultipartFile(index))
+                            .padding(5)
+                            .style(button::secondary),
+                    ]
+                    .spacing(5)
+                    .align_y(Center)
+                    .into(),
+                };
+
+                let overrides: Element<Message> = match part.kind {
+                    PartKind::Text => row![
+                        text_input("Content type (optional)", &part.content_type)
+                            .on_input(move |content_type| {
+                                update(index, MultipartPart { content_type, ..part.clone() })
+                            })
+                            .width(Fill)
+                            .padding(5)
+                            .size(12),
+                    ]
+                    .into(),
+                    PartKind::File => row![
+                        text_input("Filename (from path)", &part.filename)
+                            .on_input(move |filename| {
+                                update(index, MultipartPart { filename, ..part.clone() })
+                            })
+                            .width(Fill)
+                            .padding(5)
+                            .size(12),
+                        text_input("Content type (by extension)", &part.content_type)
+                            .on_input(move |content_type| {
+                                update(index, MultipartPart { content_type, ..part.clone() })
+                            })
+                            .width(Fill)
+                            .padding(5)
+                            .size(12),
+                    ]
+                    .spacing(5)
+                    .into(),
+                };
+
+                column![
+                    row![
+                        text_input("Name", &part.name)
+                            .on_input(move |name| update(index, MultipartPart { name, ..part.clone() }))
+                            .width(140)
+                            .padding(5),
+                        pick_list(PartKind::ALL, Some(part.kind), move |kind| {
+                            update(index, MultipartPart { kind, ..part.clone() })
+                        })
+                        .padding(5),
+                        value,
+                        button(text("🗑️").size(14))
+                            .on_press({
+                                let mut parts = data.parts.clone();
+                                parts.remove(index);
+                                Message::BodyDataChanged(BodyData { parts, ..data.clone() })
+                            })
+                            .padding(5)
+                            .style(button::danger),
+                    ]
+                    .spacing(8)
+                    .align_y(Center),
+                    overrides,
+                ]
+                .spacing(3)
+                .into()
+            })
+            .collect();
+        column(rows).spacing(10).into()
+    };
+
+    let add = button(text("+ Add part").size(14))
+        .on_press({
+            let mut parts = data.parts.clone();
+            parts.push(MultipartPart::default());
+            Message::BodyDataChanged(BodyData { parts, ..data.clone() })
+        })
+        .padding(5)
+        .style(button::success);
+
+    column![
+        items_table,
+        add,
+        text("Files are streamed from disk when the request is sent")
+            .size(12)
+            .style(text::secondary),
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Тип для сырого текста (над редактором)
+pub fn raw_type_view(data: &BodyData) -> Element<'_, Message> {
+    row![
+        text("Content-Type:").size(14),
+        pick_list(RawContentType::ALL, Some(data.raw_content_type), move |raw_content_type| {
+            Message::BodyDataChanged(BodyData { raw_content_type, ..data.clone() })
+        })
+        .padding(5),
+    ]
+    .spacing(10)
+    .align_y(Center)
+    .into()
+}
+
+// Файл, который уйдёт телом целиком
+pub fn binary_view(data: &BodyData) -> Element<'_, Message> {
+    column![
+        row![
+            text_input("File path", &data.file)
+                .on_input(move |file| Message::BodyDataChanged(BodyData { file, ..data.clone() }))
+                .width(Fill)
+                .padding(5),
+            button(text("📂").size(14))
+                .on_press(Message::PickBinaryFile)
+                .padding(5)
+                .style(button::secondary),
+        ]
+        .spacing(5)
+        .align_y(Center),
+        text_input("Content type (application/octet-stream)", &data.file_content_type)
+            .on_input(move |file_content_type| {
+                Message::BodyDataChanged(BodyData { file_content_type, ..data.clone() })
+            })
+            .padding(5),
+        text("The file is streamed from disk as-is when the request is sent")
+            .size(12)
+            .style(text::secondary),
+    ]
+    .spacing(10)
+    .into()
+}
@@ -0,0 +1,327 @@
+// Коллекции сохранённых запросов: папки с именованными запросами,
+// хранятся в читаемом JSON рядом с проектом, чтобы их можно было держать в git
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::Path;
+
+use iced::widget::{button, column, container, pick_list, row, scrollable, space, text, text_input};
+use iced::{Center, Element, Fill};
+
+use crate::auth::Auth;
+use crate::body::BodyData;
+use crate::query::ArrayStyle;
+use crate::settings::ClientSettings;
+use crate::storage;
+use crate::{ConnectionType, HeaderParam, HttpMethod, Message, QueryParam};
+
+// Файл коллекции в рабочей директории
+pub const COLLECTION_FILE: &str = "rastman.collection.json";
+
+// Сохранённый запрос
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedRequest {
+    pub name: String,
+    pub method: HttpMethod,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub custom_method: String,
+    #[serde(default)]
+    pub force_body: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<ClientSettings>, // Свои настройки клиента
+    #[serde(default, skip_serializing_if = "Auth::is_none")]
+    pub auth: Auth,
+    pub url: String,
+    #[serde(default)]
+    pub query_params: Vec<QueryParam>,
+    #[serde(default)]
+    pub array_style: ArrayStyle,
+    #[serde(default)]
+    pub headers: Vec<HeaderParam>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default, skip_serializing_if = "BodyData::is_default")]
+    pub body_data: BodyData, // Тип тела и поля формы
+    #[serde(default, skip_serializing_if = "ConnectionType::is_http")]
+    pub connection: ConnectionType,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub grpc_method: String, // "package.Service/Method"
+}
+
+// Папка с запросами
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Folder {
+    pub name: String,
+    #[serde(default)]
+    pub requests: Vec<SavedRequest>,
+}
+
+// Вся коллекция
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Collection {
+    #[serde(default)]
+    pub folders: Vec<Folder>,
+}
+
+impl SavedRequest {
+    // Имя метода для списков (для CUSTOM - введённое)
+    pub fn method_name(&self) -> String {
+        self.method.name(&self.custom_method)
+    }
+}
+
+impl Collection {
+    // Читаем коллекцию с диска, отсутствующий файл - пустая коллекция
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    // Пишем коллекцию в красивом JSON (удобно смотреть диффы в git).
+    // Секреты авторизации в файл не попадают, ссылки {{name}} остаются.
+    // Снимок берём сейчас, пишем в фоне
+    pub fn save(&self, path: impl AsRef<Path>) -> impl Future<Output = Result<(), String>> {
+        let mut snapshot = self.clone();
+        storage::write(path.as_ref().to_path_buf(), move || {
+            for request in snapshot.folders.iter_mut().flat_map(|folder| &mut folder.requests) {
+                request.auth = request.auth.without_secrets();
+            }
+            let mut data = serde_json::to_string_pretty(&snapshot)
+                .map_err(|e| format!("Failed to serialize collection: {}", e))?;
+            data.push('\n');
+            Ok(data)
+        })
+    }
+
+    pub fn folder_names(&self) -> Vec<String> {
+        self.folders.iter().map(|folder| folder.name.clone()).collect()
+    }
+
+    // Добавляем папку, если такой ещё нет
+    pub fn add_folder(&mut self, name: &str) {
+        if !self.folders.iter().any(|folder| folder.name == name) {
+            self.folders.push(Folder {
+                name: name.to_string(),
+                requests: Vec::new(),
+            });
+        }
+    }
+
+    pub fn remove_folder(&mut self, folder: usize) {
+        if folder < self.folders.len() {
+            self.folders.remove(folder);
+        }
+    }
+
+    // Сохраняем запрос в папку: запрос с тем же именем перезаписывается.
+    // Возвращает позицию сохранённого запроса
+    pub fn upsert(&mut self, folder_name: &str, request: SavedRequest) -> (usize, usize) {
+        self.add_folder(folder_name);
+        let folder_index = self
+            .folders
+            .iter()
+            .position(|folder| folder.name == folder_name)
+            .expect("folder was just added");
+        let folder = &mut self.folders[folder_index];
+
+        match folder.requests.iter().position(|r| r.name == request.name) {
+            Some(index) => {
+                folder.requests[index] = request;
+                (folder_index, index)
+            }
+            None => {
+                folder.requests.push(request);
+                (folder_index, folder.requests.len() - 1)
+            }
+        }
+    }
+
+    pub fn get(&self, folder: usize, index: usize) -> Option<&SavedRequest> {
+        self.folders.get(folder)?.requests.get(index)
+    }
+
+    pub fn rename(&mut self, folder: usize, index: usize, name: String) {
+        if let Some(request) = self
+            .folders
+            .get_mut(folder)
+            .and_then(|folder| folder.requests.get_mut(index))
+        {
+            request.name = name;
+        }
+    }
+
+    // Копия кладётся сразу под оригиналом
+    pub fn duplicate(&mut self, folder: usize, index: usize) {
+        if let Some(folder) = self.folders.get_mut(folder) {
+            if let Some(original) = folder.requests.get(index) {
+                let mut copy = original.clone();
+                copy.name = format!("{} (copy)", copy.name);
+                folder.requests.insert(index + 1, copy);
+            }
+        }
+    }
+
+    pub fn delete(&mut self, folder: usize, index: usize) {
+        if let Some(folder) = self.folders.get_mut(folder) {
+            if index < folder.requests.len() {
+                folder.requests.remove(index);
+            }
+        }
+    }
+}
+
+// Состояние боковой панели коллекции
+#[derive(Debug, Clone, Default)]
+pub struct Sidebar {
+    pub new_folder_name: String,        // Поле для новой папки
+    pub save_name: String,              // Имя сохраняемого запроса
+    pub save_folder: Option<String>,    // Папка для сохранения
+    pub renaming: Option<(usize, usize, String)>, // Переименовываемый запрос
+    pub active: Option<(usize, usize)>, // Открытый сейчас запрос
+    pub error: Option<String>,          // Ошибка чтения/записи файла
+    pub notice: Option<String>,         // Секреты не сохранены в файл
+}
+
+// Боковая панель: папки, запросы и форма сохранения
+pub fn view<'a>(collection: &'a Collection, sidebar: &'a Sidebar) -> Element<'a, Message> {
+    let title = text("Collection:").size(16);
+
+    let folders: Vec<Element<Message>> = collection
+        .folders
+        .iter()
+        .enumerate()
+        .map(|(folder_index, folder)| {
+            let header = row![
+                text(format!("📁 {}", folder.name)).size(14).width(Fill),
+                button(text("🗑️").size(12))
+                    .on_press(Message::DeleteCollectionFolder(folder_index))
+                    .padding(3)
+                    .style(button::danger),
+            ]
+            .spacing(5)
+            .align_y(Center);
+
+            let requests: Vec<Element<Message>> = folder
+                .requests
+                .iter()
+                .enumerate()
+                .map(|(index, request)| {
+                    match &sidebar.renaming {
+                        Some((f, i, name)) if *f == folder_index && *i == index => row![
+                            text_input("Name...", name)
+                                .on_input(Message::RenameSavedRequestInput)
+                                .on_submit(Message::ConfirmRenameSavedRequest)
+                                .padding(3)
+                                .size(12),
+                            button(text("✔").size(12))
+                                .on_press(Message::ConfirmRenameSavedRequest)
+                                .padding(3)
+                                .style(button::success),
+                        ]
+                        .spacing(3)
+                        .align_y(Center)
+                        .into(),
+                        _ => {
+                            let is_active = sidebar.active == Some((folder_index, index));
+                            row![
+                                button(
+                                    text(format!("{} {}", request.method_name(), request.name)).size(12)
+                                )
+                                .on_press(Message::OpenSavedRequest(folder_index, index))
+                                .padding(3)
+                                .width(Fill)
+                                .style(if is_active { button::primary } else { button::text }),
+                                button(text("✏").size(12))
+                                    .on_press(Message::StartRenameSavedRequest(folder_index, index))
+                                    .padding(3)
+                                    .style(button::secondary),
+                                button(text("⧉").size(12))
+                                    .on_press(Message::DuplicateSavedRequest(folder_index, index))
+                                    .padding(3)
+                                    .style(button::secondary),
+                                button(text("🗑️").size(12))
+                                    .on_press(Message::DeleteSavedRequest(folder_index, index))
+                                    .padding(3)
+                                    .style(button::danger),
+                            ]
+                            .spacing(3)
+                            .align_y(Center)
+                            .into()
+                        }
+                    }
+                })
+                .collect();
+
+            column![header, column(requests).spacing(3).padding([0, 10])]
+                .spacing(5)
+                .into()
+        })
+        .collect();
+
+    let folders_list: Element<Message> = if folders.is_empty() {
+        text("No saved requests yet").style(text::secondary).into()
+    } else {
+        scrollable(column(folders).spacing(10)).height(300).into()
+    };
+
+    let new_folder = row![
+        text_input("New folder...", &sidebar.new_folder_name)
+            .on_input(Message::NewCollectionFolderChanged)
+            .on_submit(Message::AddCollectionFolder)
+            .padding(5)
+            .size(12),
+        button(text("+").size(12))
+            .on_press(Message::AddCollectionFolder)
+            .padding(5)
+            .style(button::success),
+    ]
+    .spacing(5)
+    .align_y(Center);
+
+    let save_form = column![
+        text("Save current request:").size(14),
+        pick_list(
+            collection.folder_names(),
+            sidebar.save_folder.clone(),
+            Message::SaveFolderSelected
+        )
+        .placeholder("Folder")
+        .width(Fill)
+        .padding(5),
+        row![
+            text_input("Request name...", &sidebar.save_name)
+                .on_input(Message::SaveNameChanged)
+                .on_submit(Message::SaveCurrentRequest)
+                .padding(5)
+                .size(12),
+            button(text("💾 Save").size(12))
+                .on_press(Message::SaveCurrentRequest)
+                .padding(5)
+                .style(button::primary),
+        ]
+        .spacing(5)
+        .align_y(Center),
+    ]
+    .spacing(5);
+
+    let error: Element<Message> = match (&sidebar.error, &sidebar.notice) {
+        (Some(error), _) => text(error).size(12).style(text::danger).into(),
+        (None, Some(notice)) => text(notice).size(12).style(text::warning).into(),
+        (None, None) => space().height(0).into(),
+    };
+
+    container(
+        column![title, folders_list, new_folder, save_form, error]
+            .spacing(10)
+            .padding(10),
+    )
+    .width(260)
+    .style(container::bordered_box)
+    .into()
+}
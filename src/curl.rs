@@ -0,0 +1,930 @@
+// Импорт и экспорт запросов в виде команды curl
+use std::collections::VecDeque;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::auth::{Auth, AuthKind};
+use crate::body::{BodyData, BodyType, MultipartPart, PartKind, RequestBody};
+use crate::collection::SavedRequest;
+use crate::{graphql, jsonrpc};
+use crate::query;
+use crate::settings::{ClientSettings, HttpVersion, RedirectPolicy};
+use crate::{HeaderParam, HttpMethod};
+
+// Число из escape-последовательности $'...': до max цифр в системе radix
+fn escape_number(chars: &mut Peekable<Chars>, radix: u32, max: usize, first: Option<char>) -> Option<char> {
+    let mut digits: String = first.into_iter().collect();
+    while digits.len() < max {
+        match chars.peek() {
+            Some(c) if c.is_digit(radix) => digits.push(*c),
+            _ => break,
+        }
+        chars.next();
+    }
+    u32::from_str_radix(&digits, radix).ok().and_then(char::from_u32)
+}
+
+// $'...' из bash: экранирование в стиле C (\n, \t, \xHH, \uHHHH, \0NNN ...)
+fn ansi_c_quoted(chars: &mut Peekable<Chars>, current: &mut String) -> Result<(), String> {
+    loop {
+        let c = match chars.next() {
+            Some('\'') => return Ok(()),
+            Some('\\') => match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some('a') => '\x07',
+                Some('b') => '\x08',
+                Some('e' | 'E') => '\x1b',
+                Some('f') => '\x0c',
+                Some('v') => '\x0b',
+                Some(c @ ('\\' | '\'' | '"' | '?')) => c,
+                Some('x') => escape_number(chars, 16, 2, None).unwrap_or_default(),
+                Some('u') => escape_number(chars, 16, 4, None).unwrap_or_default(),
+                Some('U') => escape_number(chars, 16, 8, None).unwrap_or_default(),
+                Some(c @ '0'..='7') => escape_number(chars, 8, 3, Some(c)).unwrap_or_default(),
+                Some(c) => {
+                    current.push('\\');
+                    c
+                }
+                None => break,
+            },
+            Some(c) => c,
+            None => break,
+        };
+        current.push(c);
+    }
+    Err("Unterminated $' quote".to_string())
+}
+
+// Разбиваем командную строку на аргументы как это делает sh:
+// одинарные и двойные кавычки, $'...', экранирование, перенос строки через "\"
+fn tokenize(command: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_token = true;
+                ansi_c_quoted(&mut chars, &mut current)?;
+            }
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            // Внутри двойных кавычек экранируются только эти символы
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // Перенос строки в многострочной команде
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(c) => {
+                    in_token = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+// --data-urlencode: "name=content", "=content" или просто "content"
+fn data_urlencode(value: &str) -> String {
+    match value.split_once('=') {
+        Some((name, content)) if !name.is_empty() => {
+            format!("{}={}", name, query::encode_component(content))
+        }
+        Some((_, content)) => query::encode_component(content),
+        None => query::encode_component(value),
+    }
+}
+
+// Файл из -d @file / -F name=<file читаем в фоне, а не в update()
+async fn read_file(path: &str) -> Result<String, String> {
+    tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+// -F name=value / -F name=@file;type=...;filename=... / -F name=<file.
+// Для --form-string значение берётся как есть (literal)
+async fn multipart_parts(fields: &[(String, bool)]) -> Result<Vec<MultipartPart>, String> {
+    let mut parts = Vec::new();
+
+    for (field, literal) in fields {
+        let (name, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid form field: {}", field))?;
+        if *literal {
+            parts.push(MultipartPart {
+                name: name.to_string(),
+                value: value.to_string(),
+                ..MultipartPart::default()
+            });
+            continue;
+        }
+
+        let mut options = value.split(';');
+        let content = options.next().unwrap_or_default();
+        let mut part = MultipartPart {
+            name: name.to_string(),
+            ..MultipartPart::default()
+        };
+        for option in options {
+            match option.split_once('=') {
+                Some(("type", t)) => part.content_type = t.to_string(),
+                Some(("filename", f)) => part.filename = f.to_string(),
+                _ => {}
+            }
+        }
+
+        if let Some(path) = content.strip_prefix('@') {
+            // Файл уходит с диска при отправке
+            part.kind = PartKind::File;
+            part.value = path.to_string();
+        } else if let Some(path) = content.strip_prefix('<') {
+            // Содержимое файла как текстовое поле
+            part.value = read_file(path).await?;
+        } else {
+            part.value = content.to_string();
+        }
+        parts.push(part);
+    }
+
+    Ok(parts)
+}
+
+// Короткие флаги curl, которые ждут значение
+const SHORT_WITH_VALUE: &str = "XHdurFAbeowxcmETKUyYzCtQP";
+
+// Флаги с аргументом, которые нам не важны: аргумент пропускаем,
+// иначе он разберётся как URL
+const IGNORED_WITH_VALUE: &[&str] = &[
+    "-o", "--output", "--output-dir", "-w", "--write-out", "-x", "--proxy", "-U",
+    "--proxy-user", "--proxy-header", "--noproxy", "--socks4", "--socks4a", "--socks5",
+    "--socks5-hostname", "--preproxy", "--cacert", "--capath", "-E", "--cert", "--cert-type",
+    "--key", "--key-type", "--pass", "--ciphers", "--tls-max", "--pinnedpubkey", "-c",
+    "--cookie-jar", "-r", "--range", "-T", "--upload-file", "-K", "--config", "--retry",
+    "--retry-delay", "--retry-max-time", "--resolve", "--connect-to", "--interface",
+    "--dns-servers", "--local-port", "--unix-socket", "--abstract-unix-socket",
+    "--limit-rate", "-y", "--speed-time", "-Y", "--speed-limit", "--max-filesize",
+    "--keepalive-time", "--expect100-timeout", "--happy-eyeballs-timeout-ms", "-z",
+    "--time-cond", "-C", "--continue-at", "--stderr", "--trace", "--trace-ascii",
+    "--netrc-file", "--proto", "--proto-redir", "--request-target", "-t", "--telnet-option",
+    "-Q", "--quote", "-P", "--ftp-port", "--mail-from", "--mail-rcpt", "--variable",
+];
+
+// Склеенные короткие флаги: "-sSL" -> "-s -S -L", "-XPOST" -> "-X POST",
+// "-sXPOST" -> "-s -X POST". Значение - всё, что после флага со значением
+fn split_short_flags(arg: &str) -> Option<Vec<String>> {
+    let cluster = arg.strip_prefix('-').filter(|rest| !rest.starts_with('-'))?;
+    if cluster.chars().count() < 2 {
+        return None;
+    }
+
+    let mut flags = Vec::new();
+    for (index, c) in cluster.char_indices() {
+        flags.push(format!("-{}", c));
+        if SHORT_WITH_VALUE.contains(c) {
+            let value = &cluster[index + c.len_utf8()..];
+            if !value.is_empty() {
+                flags.push(value.to_string());
+            }
+            break;
+        }
+    }
+    Some(flags)
+}
+
+// Разбираем команду curl в запрос редактора.
+// Асинхронно: данные из файлов (-d @file, -F name=<file) читаются с диска
+pub async fn parse(command: String) -> Result<SavedRequest, String> {
+    let mut args: VecDeque<String> = tokenize(command.trim())?.into();
+
+    match args.pop_front() {
+        Some(first) if first == "curl" || first.ends_with("/curl") => {}
+        _ => return Err("Command must start with 'curl'".to_string()),
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut form: Vec<(String, bool)> = Vec::new();
+    let mut binary_file: Option<String> = None;
+    let mut get = false;
+    let mut auth = Auth::default();
+    // Флаги curl, влияющие на клиент: без них у запроса нет своих настроек.
+    // curl по умолчанию не ходит по редиректам и не распаковывает ответ
+    let mut settings = ClientSettings {
+        redirect: RedirectPolicy::DontFollow,
+        gzip: false,
+        brotli: false,
+        ..ClientSettings::default()
+    };
+    let mut has_settings = false;
+    let seconds = |value: String| -> Result<Option<u64>, String> {
+        let seconds: f64 = value
+            .trim()
+            .parse()
+            .ok()
+            .filter(|seconds: &f64| seconds.is_finite() && *seconds >= 0.0)
+            .ok_or_else(|| format!("Invalid number of seconds: {}", value))?;
+        Ok(Some((seconds * 1000.0) as u64))
+    };
+
+    while let Some(arg) = args.pop_front() {
+        // Склеенные короткие флаги раскладываем и разбираем по одному
+        if let Some(flags) = split_short_flags(&arg) {
+            for flag in flags.into_iter().rev() {
+                args.push_front(flag);
+            }
+            continue;
+        }
+
+        // Поддерживаем и "--header=X"
+        let (flag, inline) = match arg.strip_prefix("--").and_then(|long| long.split_once('=')) {
+            Some((flag, value)) => (format!("--{}", flag), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.pop_front())
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value()?),
+            "-H" | "--header" => {
+                let header = value()?;
+                let (key, val) = header
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid header: {}", header))?;
+                headers.push(HeaderParam::new(key.trim().to_string(), val.trim().to_string()));
+            }
+            "--data-raw" => data.push(value()?),
+            "--data-binary" if binary_file.is_none() && data.is_empty() => {
+                // "@file" - файл уходит телом как есть
+                let value = value()?;
+                match value.strip_prefix('@') {
+                    Some(path) => binary_file = Some(path.to_string()),
+                    None => data.push(value),
+                }
+            }
+            "-d" | "--data" | "--data-binary" | "--data-ascii" => {
+                // "@file" - данные из файла
+                let value = value()?;
+                match value.strip_prefix('@') {
+                    Some(path) => data.push(read_file(path).await?),
+                    None => data.push(value),
+                }
+            }
+            "--data-urlencode" => data.push(data_urlencode(&value()?)),
+            "-F" | "--form" => form.push((value()?, false)),
+            "--form-string" => form.push((value()?, true)),
+            "-u" | "--user" => {
+                // user:password уходит во вкладку Auth
+                let credentials = value()?;
+                let (username, password) =
+                    credentials.split_once(':').unwrap_or((credentials.as_str(), ""));
+                auth = Auth {
+                    kind: AuthKind::Basic,
+                    username: username.to_string(),
+                    password: password.to_string(),
+                    ..Auth::default()
+                };
+            }
+            "-A" | "--user-agent" => {
+                headers.push(HeaderParam::new("User-Agent".to_string(), value()?))
+            }
+            "-e" | "--referer" => headers.push(HeaderParam::new("Referer".to_string(), value()?)),
+            "-b" | "--cookie" => headers.push(HeaderParam::new("Cookie".to_string(), value()?)),
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            "-L" | "--location" => {
+                settings.redirect = RedirectPolicy::Follow;
+                has_settings = true;
+            }
+            "--max-redirs" => {
+                let value = value()?;
+                settings.max_redirects = value
+                    .parse()
+                    .map_err(|_| format!("Invalid --max-redirs: {}", value))?;
+                has_settings = true;
+            }
+            "-m" | "--max-time" => {
+                settings.total_timeout_ms = seconds(value()?)?;
+                has_settings = true;
+            }
+            "--connect-timeout" => {
+                settings.connect_timeout_ms = seconds(value()?)?;
+                has_settings = true;
+            }
+            "--http1.1" => {
+                settings.http_version = HttpVersion::Http1;
+                has_settings = true;
+            }
+            "--http2-prior-knowledge" => {
+                settings.http_version = HttpVersion::Http2PriorKnowledge;
+                has_settings = true;
+            }
+            "--compressed" => {
+                settings.gzip = true;
+                settings.brotli = true;
+                has_settings = true;
+            }
+            "--url" => url = Some(value()?),
+            flag if IGNORED_WITH_VALUE.contains(&flag) => {
+                value()?;
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                // Остальные флаги без аргумента (-s, -k, -v, -i ...) пропускаем
+            }
+            _ => url = Some(arg),
+        }
+    }
+
+    let url = url.ok_or("No URL found in curl command")?;
+    let (url, mut query_params) = query::split_url(&url);
+
+    let mut body = String::new();
+    let mut body_data = BodyData::default();
+    if let Some(file) = binary_file.filter(|_| data.is_empty() && form.is_empty()) {
+        body_data = BodyData {
+            kind: BodyType::Binary,
+            file,
+            ..BodyData::default()
+        };
+    } else if !form.is_empty() {
+        body_data = BodyData {
+            kind: BodyType::Multipart,
+            parts: multipart_parts(&form).await?,
+            ..BodyData::default()
+        };
+    } else if get {
+        // -G переносит данные в query строку
+        for data in data.drain(..) {
+            query_params.extend(query::decode(&data));
+        }
+    } else {
+        body = data.join("&");
+    }
+
+    // curl -d без Content-Type отправляет форму - так её и открываем
+    let has_content_type = headers.iter().any(|h| h.key.eq_ignore_ascii_case("content-type"));
+    if !body.is_empty()
+        && !has_content_type
+        && body.split('&').all(|pair| pair.contains('='))
+        && serde_json::from_str::<serde_json::Value>(&body).is_err()
+    {
+        body_data = BodyData {
+            kind: BodyType::FormUrlEncoded,
+            form_fields: query::decode(&body),
+            ..BodyData::default()
+        };
+    }
+
+    // JSON вида {"query": ...} открываем в режиме GraphQL
+    if let Some((query, graphql_variables, graphql_operation)) = graphql::from_envelope(&body) {
+        body = query;
+        body_data = BodyData {
+            kind: BodyType::GraphQl,
+            graphql_variables,
+            graphql_operation,
+            ..BodyData::default()
+        };
+    }
+
+    // Конверт JSON-RPC 2.0 (или пакет) открываем в режиме JSON-RPC
+    if let Some((jsonrpc_method, params, jsonrpc_notification, jsonrpc_batch)) =
+        jsonrpc::from_envelope(&body)
+    {
+        body = params;
+        body_data = BodyData {
+            kind: BodyType::JsonRpc,
+            jsonrpc_method,
+            jsonrpc_notification,
+            jsonrpc_batch,
+            ..BodyData::default()
+        };
+    }
+
+    // Как и curl: с данными по умолчанию POST
+    let method = match method {
+        Some(method) => method,
+        None if !body.is_empty() || !body_data.is_default() => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+    let (method, custom_method) = HttpMethod::parse(&method);
+    // curl -X GET -d ... отправляет тело - повторяем это
+    let force_body = (!body.is_empty() || !body_data.is_default()) && !method.has_body();
+
+    Ok(SavedRequest {
+        name: String::new(),
+        method,
+        custom_method,
+        force_body,
+        settings: has_settings.then_some(settings),
+        auth,
+        url,
+        query_params,
+        headers,
+        body: if body_data.kind.uses_editor() { body } else { String::new() },
+        body_data,
+        ..SavedRequest::default()
+    })
+}
+
+// Экранируем для sh одинарными кавычками
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// Тело формы в том виде, в каком его соберёт curl из --data-urlencode
+fn form_body(fields: &[(String, String)]) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| {
+            format!("{}={}", query::encode_component(key), query::encode_component(value))
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Подпись HMAC/JWT зависит от времени и тела: считаем её сейчас по тем же байтам,
+// что отправит curl, и возвращаем заголовки, которые добавила подпись
+fn signed_headers(
+    method: &str,
+    url: &str,
+    headers: &[HeaderParam],
+    body: Option<&RequestBody>,
+    auth: &Auth,
+) -> Result<Vec<HeaderParam>, String> {
+    let method = reqwest::Method::from_bytes(method.as_bytes())
+        .map_err(|_| format!("Invalid method: {}", method))?;
+    let url = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let mut request = reqwest::Request::new(method, url);
+    for header in headers {
+        let name = reqwest::header::HeaderName::from_bytes(header.key.trim().as_bytes());
+        let value = reqwest::header::HeaderValue::from_str(&header.value);
+        if let (Ok(name), Ok(value)) = (name, value) {
+            request.headers_mut().append(name, value);
+        }
+    }
+    *request.body_mut() = body.map(|body| match body {
+        RequestBody::Text(text) | RequestBody::Raw(text, _) => text.clone().into(),
+        RequestBody::Form(fields) => form_body(fields).into(),
+        // Файл и multipart уходят потоком - подпись, которой нужно тело, не посчитать
+        RequestBody::File(..) | RequestBody::Multipart(_) => reqwest::Body::wrap_stream(
+            iced::futures::stream::empty::<Result<Vec<u8>, std::io::Error>>(),
+        ),
+    });
+
+    let unsigned = request.headers().clone();
+    auth.sign(&mut request)?;
+    Ok(request
+        .headers()
+        .iter()
+        .filter(|(name, value)| unsigned.get_all(*name).iter().all(|old| old != *value))
+        .map(|(name, value)| {
+            HeaderParam::new(name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned())
+        })
+        .collect())
+}
+
+// Авторизация, которой нет в заголовках: Digest и SigV4 curl умеет сам,
+// для HMAC/JWT - заголовки подписи. Возвращаем флаги curl и заголовки
+fn auth_args(
+    method: &str,
+    url: &str,
+    headers: &[HeaderParam],
+    body: Option<&RequestBody>,
+    auth: &Auth,
+) -> Result<(Vec<String>, Vec<HeaderParam>), String> {
+    let user = |user: &str, password: &str| format!("--user {}", quote(&format!("{}:{}", user, password)));
+    match auth.kind {
+        AuthKind::Digest => Ok((
+            vec!["--digest".to_string(), user(&auth.username, &auth.password)],
+            Vec::new(),
+        )),
+        AuthKind::AwsSigV4 => {
+            let scope = format!("aws:amz:{}:{}", auth.aws.region.trim(), auth.aws.service.trim());
+            let args = vec![
+                format!("--aws-sigv4 {}", quote(&scope)),
+                user(&auth.aws.access_key, &auth.aws.secret_key),
+            ];
+            let headers = match auth.aws.session_token.trim() {
+                "" => Vec::new(),
+                token => vec![HeaderParam::new("X-Amz-Security-Token".to_string(), token.to_string())],
+            };
+            Ok((args, headers))
+        }
+        AuthKind::Hmac | AuthKind::Jwt => {
+            Ok((Vec::new(), signed_headers(method, url, headers, body, auth)?))
+        }
+        _ => Ok((Vec::new(), Vec::new())),
+    }
+}
+
+// Собираем команду curl из запроса так, как его отправил бы send_http_request.
+// auth - с подставленными переменными, заголовки Basic/Bearer/API key уже в headers
+pub fn to_curl(
+    method: &str,
+    url: &str,
+    headers: &[HeaderParam],
+    body: RequestBody,
+    send_body: bool,
+    settings: &ClientSettings,
+    auth: &Auth,
+) -> String {
+    let mut parts = vec![format!("curl {}", quote(url))];
+    let send_body = send_body && !body.is_empty();
+
+    // Подпись не посчитать - честно пишем об этом над командой
+    let (auth_flags, signed, warning) =
+        match auth_args(method, url, headers, send_body.then_some(&body), auth) {
+            Ok((flags, signed)) => (flags, signed, None),
+            Err(error) => {
+                let warning = format!("# Warning: {} is not included: {}", auth.kind, error);
+                (Vec::new(), Vec::new(), Some(warning))
+            }
+        };
+    let headers: Vec<&HeaderParam> = headers
+        .iter()
+        .filter(|header| !signed.iter().any(|s| s.key.eq_ignore_ascii_case(&header.key)))
+        .chain(&signed)
+        .collect();
+
+    // Повторяем поведение клиента: редиректы, таймауты, версия, распаковка
+    if settings.redirect == RedirectPolicy::Follow {
+        parts.push("-L".to_string());
+        if settings.max_redirects != ClientSettings::default().max_redirects {
+            parts.push(format!("--max-redirs {}", settings.max_redirects));
+        }
+    }
+    if let Some(ms) = settings.connect_timeout_ms {
+        parts.push(format!("--connect-timeout {}", ms as f64 / 1000.0));
+    }
+    if let Some(ms) = settings.total_timeout_ms {
+        parts.push(format!("--max-time {}", ms as f64 / 1000.0));
+    }
+    match settings.http_version {
+        HttpVersion::Auto => {}
+        HttpVersion::Http1 => parts.push("--http1.1".to_string()),
+        HttpVersion::Http2PriorKnowledge => parts.push("--http2-prior-knowledge".to_string()),
+    }
+    if settings.gzip || settings.brotli {
+        parts.push("--compressed".to_string());
+    }
+
+    // Для HEAD у curl есть -I (с -X HEAD он ждёт тело ответа)
+    if method == "HEAD" && !send_body {
+        parts.push("-I".to_string());
+    } else if method != "GET" || send_body {
+        parts.push(format!("-X {}", method));
+    }
+    parts.extend(auth_flags);
+    for header in &headers {
+        parts.push(format!("-H {}", quote(&format!("{}: {}", header.key, header.value))));
+    }
+
+    let has_content_type = headers.iter().any(|h| h.key.eq_ignore_ascii_case("content-type"));
+    match body {
+        _ if !send_body => {}
+        RequestBody::Text(body) => {
+            // JSON тело уходит с Content-Type: application/json, остальное - text/plain
+            if !has_content_type {
+                let content_type = if serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+                    "application/json"
+                } else {
+                    "text/plain; charset=utf-8"
+                };
+                parts.push(format!("-H {}", quote(&format!("Content-Type: {}", content_type))));
+            }
+            parts.push(format!("--data-raw {}", quote(&body)));
+        }
+        // curl сам кодирует значение и ставит Content-Type формы
+        RequestBody::Form(fields) => {
+            for (key, value) in fields {
+                let field = format!("{}={}", query::encode_component(&key), value);
+                parts.push(format!("--data-urlencode {}", quote(&field)));
+            }
+        }
+        RequestBody::Raw(body, content_type) => {
+            if !has_content_type {
+                parts.push(format!("-H {}", quote(&format!("Content-Type: {}", content_type))));
+            }
+            parts.push(format!("--data-raw {}", quote(&body)));
+        }
+        RequestBody::File(path, content_type) => {
+            if !has_content_type {
+                parts.push(format!("-H {}", quote(&format!("Content-Type: {}", content_type))));
+            }
+            parts.push(format!("--data-binary {}", quote(&format!("@{}", path))));
+        }
+        RequestBody::Multipart(form) => {
+            for part in form {
+                let mut field = match part.kind {
+                    // --form-string не трактует @ и < в значении
+                    PartKind::Text if part.content_type.is_empty() => {
+                        parts.push(format!(
+                            "--form-string {}",
+                            quote(&format!("{}={}", part.name, part.value))
+                        ));
+                        continue;
+                    }
+                    PartKind::Text => format!("{}={}", part.name, part.value),
+                    PartKind::File => format!("{}=@{}", part.name, part.value),
+                };
+                if !part.filename.is_empty() {
+                    field.push_str(&format!(";filename={}", part.filename));
+                }
+                if !part.content_type.is_empty() {
+                    field.push_str(&format!(";type={}", part.content_type));
+                }
+                parts.push(format!("-F {}", quote(&field)));
+            }
+        }
+    }
+
+    let command = parts.join(" \\\n  ");
+    match warning {
+        Some(warning) => format!("{}\n{}", warning, command),
+        None => command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    fn parse_now(command: &str) -> Result<SavedRequest, String> {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(parse(command.to_string()))
+    }
+
+    fn header(request: &SavedRequest, key: &str) -> Option<String> {
+        request
+            .headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case(key))
+            .map(|h| h.value.clone())
+    }
+
+    #[test]
+    fn tokenize_quotes_and_escapes() {
+        let tokens = tokenize(r#"curl 'a b' "c \"d\" \$e \x" f\ g 'it'\''s'"#).unwrap();
+        assert_eq!(tokens, ["curl", "a b", r#"c "d" $e \x"#, "f g", "it's"]);
+
+        assert!(tokenize("curl 'open").is_err());
+        assert!(tokenize("curl \"open").is_err());
+    }
+
+    #[test]
+    fn tokenize_ansi_c_quotes() {
+        let tokens = tokenize(r"curl $'a\nb\t\'c\' \x41é\101 \q'").unwrap();
+        assert_eq!(tokens, ["curl", "a\nb\t'c' Aé\u{41} \\q"]);
+        assert!(tokenize("curl $'open").is_err());
+    }
+
+    #[test]
+    fn tokenize_line_continuations() {
+        let tokens = tokenize("curl \\\n  -X POST \\\r\n  'https://h/x'").unwrap();
+        assert_eq!(tokens, ["curl", "-X", "POST", "https://h/x"]);
+    }
+
+    #[test]
+    fn parse_headers_and_user() {
+        let request = parse_now(
+            "curl -H 'Accept: application/json' --header=X-Id:7 -HX-Two:2 -u bob:s3:cret https://h/x?a=1",
+        )
+        .unwrap();
+        assert_eq!(request.method, HttpMethod::GET);
+        assert_eq!(request.url, "https://h/x");
+        assert_eq!(request.query_params[0].key, "a");
+        assert_eq!(header(&request, "accept").as_deref(), Some("application/json"));
+        assert_eq!(header(&request, "x-id").as_deref(), Some("7"));
+        assert_eq!(header(&request, "x-two").as_deref(), Some("2"));
+        assert_eq!(request.auth.kind, AuthKind::Basic);
+        assert_eq!(request.auth.username, "bob");
+        assert_eq!(request.auth.password, "s3:cret");
+    }
+
+    #[test]
+    fn parse_clustered_short_flags() {
+        let request = parse_now("curl -sSL -XPUT -sH 'X-A: 1' https://h/x").unwrap();
+        assert_eq!(request.method, HttpMethod::PUT);
+        assert_eq!(header(&request, "x-a").as_deref(), Some("1"));
+        let settings = request.settings.unwrap();
+        assert_eq!(settings.redirect, RedirectPolicy::Follow);
+
+        // Значение флага склеено с другими: -sXPOST
+        let request = parse_now("curl -sXPOST https://h/x").unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.url, "https://h/x");
+    }
+
+    #[test]
+    fn parse_data_flags() {
+        let request = parse_now("curl https://h/x -d a=1 --data-raw b=@2 --data-urlencode 'c=x y'").unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.body_data.kind, BodyType::FormUrlEncoded);
+        let fields: Vec<(&str, &str)> = request
+            .body_data
+            .form_fields
+            .iter()
+            .map(|f| (f.key.as_str(), f.value.as_str()))
+            .collect();
+        assert_eq!(fields, [("a", "1"), ("b", "@2"), ("c", "x y")]);
+
+        let request = parse_now(r#"curl -X PUT https://h/x -H 'Content-Type: application/json' --data '{"a":1}'"#)
+            .unwrap();
+        assert_eq!(request.method, HttpMethod::PUT);
+        assert_eq!(request.body, r#"{"a":1}"#);
+
+        // -G переносит данные в query
+        let request = parse_now("curl -G https://h/x -d q=1").unwrap();
+        assert_eq!(request.method, HttpMethod::GET);
+        assert_eq!(request.query_params[0].key, "q");
+
+        let request = parse_now("curl https://h/x --data-binary @upload.bin").unwrap();
+        assert_eq!(request.body_data.kind, BodyType::Binary);
+        assert_eq!(request.body_data.file, "upload.bin");
+    }
+
+    #[test]
+    fn parse_data_from_file() {
+        let path = std::env::temp_dir().join("rastman-curl-test-data.txt");
+        std::fs::write(&path, "from=file").unwrap();
+        let request = parse_now(&format!("curl https://h/x -d @{}", path.display())).unwrap();
+        assert_eq!(request.body_data.form_fields[0].value, "file");
+
+        let request = parse_now(&format!("curl https://h/x -F 'note=<{}'", path.display())).unwrap();
+        assert_eq!(request.body_data.parts[0].value, "from=file");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(parse_now("curl https://h/x -d @/no/such/file").is_err());
+    }
+
+    #[test]
+    fn parse_form_fields() {
+        let request = parse_now(
+            "curl https://h/x -F name=bob -F 'doc=@a.pdf;type=application/pdf;filename=b.pdf' --form-string 'raw=@x'",
+        )
+        .unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.body_data.kind, BodyType::Multipart);
+        let parts = &request.body_data.parts;
+        assert_eq!((parts[0].name.as_str(), parts[0].value.as_str()), ("name", "bob"));
+        assert_eq!(parts[1].kind, PartKind::File);
+        assert_eq!(parts[1].value, "a.pdf");
+        assert_eq!(parts[1].content_type, "application/pdf");
+        assert_eq!(parts[1].filename, "b.pdf");
+        assert_eq!(parts[2].kind, PartKind::Text);
+        assert_eq!(parts[2].value, "@x");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_now("wget https://h/x").is_err());
+        assert!(parse_now("curl -X").is_err());
+        assert!(parse_now("curl -s").is_err());
+    }
+
+    #[test]
+    fn parse_compressed() {
+        let settings = parse_now("curl --compressed https://h/x").unwrap().settings.unwrap();
+        assert!(settings.gzip && settings.brotli);
+        assert_eq!(settings.redirect, RedirectPolicy::DontFollow);
+
+        // Без --compressed curl ответ не распаковывает
+        let settings = parse_now("curl -L https://h/x").unwrap().settings.unwrap();
+        assert!(!settings.gzip && !settings.brotli);
+        assert!(parse_now("curl https://h/x").unwrap().settings.is_none());
+    }
+
+    #[test]
+    fn parse_skips_values_of_ignored_flags() {
+        let request = parse_now(
+            "curl -o out.json --retry 3 -sKcurl.rc --limit-rate=10k --proxy-user u:p -A ua --connect-timeout 5 https://h/x",
+        )
+        .unwrap();
+        assert_eq!(request.url, "https://h/x");
+        assert_eq!(header(&request, "user-agent").as_deref(), Some("ua"));
+        assert_eq!(request.settings.unwrap().connect_timeout_ms, Some(5000));
+
+        assert!(parse_now("curl https://h/x -o").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_negative_timeouts() {
+        assert_eq!(
+            parse_now("curl --max-time -1 https://h/x").unwrap_err(),
+            "Invalid number of seconds: -1"
+        );
+        assert!(parse_now("curl --connect-timeout=-0.5 https://h/x").is_err());
+        assert!(parse_now("curl -m NaN https://h/x").is_err());
+        let settings = parse_now("curl -m 1.5 https://h/x").unwrap().settings.unwrap();
+        assert_eq!(settings.total_timeout_ms, Some(1500));
+    }
+
+    #[test]
+    fn to_curl_digest_and_sigv4() {
+        let settings = ClientSettings::default();
+        let auth = Auth {
+            kind: AuthKind::Digest,
+            username: "bob".to_string(),
+            password: "pw".to_string(),
+            ..Auth::default()
+        };
+        let command = to_curl("GET", "https://h/x", &[], RequestBody::Text(String::new()), false, &settings, &auth);
+        assert!(command.contains("--digest"));
+        assert!(command.contains("--user 'bob:pw'"));
+
+        let mut auth = Auth { kind: AuthKind::AwsSigV4, ..Auth::default() };
+        auth.aws.access_key = "AKID".to_string();
+        auth.aws.secret_key = "SECRET".to_string();
+        auth.aws.region = "us-east-1".to_string();
+        auth.aws.service = "s3".to_string();
+        auth.aws.session_token = "TOKEN".to_string();
+        let command = to_curl("GET", "https://h/x", &[], RequestBody::Text(String::new()), false, &settings, &auth);
+        assert!(command.contains("--aws-sigv4 'aws:amz:us-east-1:s3'"));
+        assert!(command.contains("--user 'AKID:SECRET'"));
+        assert!(command.contains("-H 'X-Amz-Security-Token: TOKEN'"));
+    }
+
+    #[test]
+    fn to_curl_signs_hmac_or_warns() {
+        let settings = ClientSettings::default();
+        let mut auth = Auth { kind: AuthKind::Hmac, ..Auth::default() };
+        auth.hmac.secret = "key".to_string();
+        auth.hmac.template = "{method} {path} {body}".to_string();
+        auth.hmac.header = "X-Signature".to_string();
+        auth.hmac.timestamp_header = String::new();
+
+        let body = RequestBody::Text("hello".to_string());
+        let command = to_curl("POST", "https://h/x", &[], body, true, &settings, &auth);
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(b"POST /x hello");
+        let expected = crate::sigv4::hex(&mac.finalize().into_bytes());
+        assert!(command.contains(&format!("-H 'x-signature: {}'", expected)), "{}", command);
+
+        // Тело-файл уходит потоком - подписи нет, но есть предупреждение
+        let body = RequestBody::File("a.bin".to_string(), "application/octet-stream".to_string());
+        let command = to_curl("POST", "https://h/x", &[], body, true, &settings, &auth);
+        assert!(command.starts_with("# Warning: HMAC signature is not included:"), "{}", command);
+        assert!(command.contains("\ncurl 'https://h/x'"));
+    }
+}
@@ -0,0 +1,301 @@
+// HTTP Digest (RFC 7616): ответ на вызов 401 WWW-Authenticate: Digest.
+// Поддерживаются MD5, SHA-256 (и их -sess варианты) и qop=auth
+use md5::Md5;
+use rand::distr::{Alphanumeric, SampleString};
+use sha2::{Digest, Sha256};
+
+use crate::sigv4::hex;
+
+// Разобранный вызов сервера
+#[derive(Debug, Default)]
+struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: String, // Как прислал сервер: MD5, SHA-256, MD5-sess ...
+    qop: Option<String>,
+}
+
+impl Challenge {
+    fn is_sha256(&self) -> bool {
+        self.algorithm.to_ascii_uppercase().starts_with("SHA-256")
+    }
+
+    fn is_session(&self) -> bool {
+        self.algorithm.to_ascii_uppercase().ends_with("-SESS")
+    }
+
+    fn supported(&self) -> bool {
+        matches!(
+            self.algorithm.to_ascii_uppercase().as_str(),
+            "MD5" | "MD5-SESS" | "SHA-256" | "SHA-256-SESS"
+        )
+    }
+
+    fn hash(&self, data: &str) -> String {
+        if self.is_sha256() {
+            hex(&Sha256::digest(data.as_bytes()))
+        } else {
+            hex(&Md5::digest(data.as_bytes()))
+        }
+    }
+}
+
+// Символы token из RFC 9110 (и "/" для token68 вроде Bearer)
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~/".contains(c)
+}
+
+// Значение параметра: token или "quoted, \"string\"". Возвращаем его и остаток
+fn parse_value(input: &str) -> (String, &str) {
+    let Some(quoted) = input.strip_prefix('"') else {
+        let end = input.find(|c: char| !is_token_char(c)).unwrap_or(input.len());
+        return (input[..end].to_string(), &input[end..]);
+    };
+
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    value.push(escaped);
+                }
+            }
+            '"' => return (value, &quoted[index + 1..]),
+            c => value.push(c),
+        }
+    }
+    (value, "")
+}
+
+// WWW-Authenticate: один или несколько вызовов "Scheme k=v, k2="v2", Scheme2 ..." через запятую.
+// Схема - token без "=" после него, всё остальное - параметры последней схемы.
+// Кавычки разбираем честно, так что "digest" внутри realm другой схемы не сработает
+fn parse_challenges(input: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut challenges: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    let mut rest = input;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let Some(first) = rest.chars().next() else {
+            break;
+        };
+        let end = rest.find(|c: char| !is_token_char(c)).unwrap_or(rest.len());
+        if end == 0 {
+            // Мусор вне token и кавычек - пропускаем символ
+            rest = &rest[first.len_utf8()..];
+            continue;
+        }
+        let (token, after) = rest.split_at(end);
+
+        match after.trim_start().strip_prefix('=') {
+            // Хвост token68 ("abc==") - не параметр
+            Some(value) if value.trim_start().starts_with('=') || value.trim_start().is_empty() => {
+                rest = value.trim_start_matches('=');
+            }
+            Some(value) => {
+                let (value, remaining) = parse_value(value.trim_start());
+                if let Some((_, params)) = challenges.last_mut() {
+                    params.push((token.to_ascii_lowercase(), value));
+                }
+                rest = remaining;
+            }
+            None => {
+                challenges.push((token.to_string(), Vec::new()));
+                rest = after;
+            }
+        }
+    }
+
+    challenges
+}
+
+// Ищем Digest вызовы во всех WWW-Authenticate, лучший - SHA-256
+fn find_challenge(headers: &reqwest::header::HeaderMap) -> Option<Challenge> {
+    let mut challenges: Vec<Challenge> = headers
+        .get_all(reqwest::header::WWW_AUTHENTICATE)
+        .iter()
+        .flat_map(|value| parse_challenges(&String::from_utf8_lossy(value.as_bytes())))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("digest"))
+        .map(|(_, params)| {
+            let mut challenge = Challenge {
+                algorithm: "MD5".to_string(),
+                ..Challenge::default()
+            };
+            for (key, value) in params {
+                match key.as_str() {
+                    "realm" => challenge.realm = value,
+                    "nonce" => challenge.nonce = value,
+                    "opaque" => challenge.opaque = Some(value),
+                    "algorithm" => challenge.algorithm = value,
+                    "qop" => challenge.qop = Some(value),
+                    _ => {}
+                }
+            }
+            challenge
+        })
+        .filter(Challenge::supported)
+        .collect();
+
+    challenges.sort_by_key(|challenge| !challenge.is_sha256());
+    challenges.into_iter().next()
+}
+
+// Есть ли в ответе Digest вызов, на который мы умеем ответить
+pub fn has_challenge(headers: &reqwest::header::HeaderMap) -> bool {
+    find_challenge(headers).is_some()
+}
+
+// Каждый повтор - новый вызов, счётчик nonce всегда первый
+const NC: &str = "00000001";
+
+// response из RFC 7616: хэш от HA1 (пользователь, realm, пароль), nonce и HA2 (метод, uri)
+fn response_hash(
+    challenge: &Challenge,
+    username: &str,
+    password: &str,
+    method: &str,
+    uri: &str,
+    qop: Option<&str>,
+    cnonce: &str,
+) -> String {
+    let mut ha1 = challenge.hash(&format!("{}:{}:{}", username, challenge.realm, password));
+    if challenge.is_session() {
+        ha1 = challenge.hash(&format!("{}:{}:{}", ha1, challenge.nonce, cnonce));
+    }
+    let ha2 = challenge.hash(&format!("{}:{}", method, uri));
+    match qop {
+        Some(qop) => challenge.hash(&format!(
+            "{}:{}:{}:{}:{}:{}",
+            ha1, challenge.nonce, NC, cnonce, qop, ha2
+        )),
+        None => challenge.hash(&format!("{}:{}:{}", ha1, challenge.nonce, ha2)),
+    }
+}
+
+// Отвечаем на вызов: ставим Authorization в повторный запрос.
+// false - в ответе нет Digest вызова, повторять нечего
+pub fn authorize(
+    username: &str,
+    password: &str,
+    request: &mut reqwest::Request,
+    response_headers: &reqwest::header::HeaderMap,
+) -> Result<bool, String> {
+    let Some(challenge) = find_challenge(response_headers) else {
+        return Ok(false);
+    };
+
+    let qop = match &challenge.qop {
+        None => None,
+        Some(offered) if offered.split(',').any(|q| q.trim() == "auth") => Some("auth"),
+        Some(offered) => return Err(format!("Unsupported Digest qop: {}", offered)),
+    };
+
+    let url = request.url();
+    let uri = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let cnonce = Alphanumeric.sample_string(&mut rand::rng(), 16);
+
+    let response = response_hash(
+        &challenge,
+        username,
+        password,
+        request.method().as_str(),
+        &uri,
+        qop,
+        &cnonce,
+    );
+
+    let mut header = format!(
+        "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+        username, challenge.realm, challenge.nonce, uri, challenge.algorithm, response
+    );
+    if let Some(qop) = qop {
+        header.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, NC, cnonce));
+    }
+    if let Some(opaque) = &challenge.opaque {
+        header.push_str(&format!(", opaque=\"{}\"", opaque));
+    }
+
+    let header = reqwest::header::HeaderValue::from_str(&header)
+        .map_err(|e| format!("Invalid Digest header: {}", e))?;
+    request
+        .headers_mut()
+        .insert(reqwest::header::AUTHORIZATION, header);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Пример из RFC 7616, раздел 3.9.1
+    const RFC_CHALLENGES: [&str; 2] = [
+        r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+        r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+    ];
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn headers(values: &[&str]) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for value in values {
+            headers.append(reqwest::header::WWW_AUTHENTICATE, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn rfc_response(challenge: &Challenge) -> String {
+        response_hash(
+            challenge,
+            "Mufasa",
+            "Circle of Life",
+            "GET",
+            "/dir/index.html",
+            Some("auth"),
+            CNONCE,
+        )
+    }
+
+    #[test]
+    fn rfc7616_sha256_response() {
+        let challenge = find_challenge(&headers(&RFC_CHALLENGES)).unwrap();
+        assert_eq!(challenge.algorithm, "SHA-256");
+        assert_eq!(challenge.realm, "http-auth@example.org");
+        assert_eq!(challenge.qop.as_deref(), Some("auth, auth-int"));
+        assert_eq!(
+            rfc_response(&challenge),
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+    }
+
+    #[test]
+    fn rfc7616_md5_response() {
+        let challenge = find_challenge(&headers(&RFC_CHALLENGES[1..])).unwrap();
+        assert_eq!(challenge.algorithm, "MD5");
+        assert_eq!(challenge.opaque.as_deref(), Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS"));
+        assert_eq!(rfc_response(&challenge), "8ca523f5e9506fed4657c9700eebdbec");
+    }
+
+    #[test]
+    fn several_challenges_in_one_header() {
+        let challenges = parse_challenges(
+            r#"Bearer realm="api", error="invalid_token", Digest realm="a b", nonce=abc, Basic dXNlcjpwYXNz=="#,
+        );
+        let schemes: Vec<&str> = challenges.iter().map(|(scheme, _)| scheme.as_str()).collect();
+        assert_eq!(schemes, ["Bearer", "Digest", "Basic"]);
+        assert_eq!(
+            challenges[1].1,
+            [("realm".to_string(), "a b".to_string()), ("nonce".to_string(), "abc".to_string())]
+        );
+    }
+
+    #[test]
+    fn digest_inside_quoted_realm_is_not_a_challenge() {
+        let headers = headers(&[r#"Basic realm="Digest realm=\"x\", nonce=\"y\"""#]);
+        assert!(find_challenge(&headers).is_none());
+        assert!(!has_challenge(&headers));
+    }
+}
@@ -0,0 +1,288 @@
+// Окружения (dev/staging/prod) с переменными для подстановки {{name}}
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+
+use iced::widget::{button, column, container, row, space, text, text_input};
+use iced::{Center, Element, Fill, Shrink};
+
+use crate::storage;
+use crate::Message;
+
+// Прежнее место файла окружений - рабочая директория рядом с коллекцией,
+// которую коммитят. Читаем его, только пока нет файла в папке настроек
+pub const LEGACY_ENVIRONMENTS_FILE: &str = "rastman.environments.json";
+
+// Пункт списка "без окружения"
+pub const NO_ENVIRONMENT: &str = "No environment";
+
+// Переменная окружения
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variable {
+    pub key: String,
+    pub value: String,
+}
+
+// Именованное окружение
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: Vec<Variable>,
+}
+
+impl Environment {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .find(|variable| variable.key == key)
+            .map(|variable| variable.value.as_str())
+    }
+}
+
+// Все окружения и выбранное
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Environments {
+    #[serde(default)]
+    pub environments: Vec<Environment>,
+    #[serde(default)]
+    pub active: Option<String>,
+}
+
+impl Environments {
+    // ~/.config/rastman/environments.json (или аналог на других ОС):
+    // в значениях переменных часто секреты, в проекте им не место
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rastman").join("environments.json"))
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let path = match Self::path() {
+            Some(path) if path.exists() => path,
+            _ => PathBuf::from(LEGACY_ENVIRONMENTS_FILE),
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    // Снимок берём сейчас, пишем в фоне; файл доступен только владельцу
+    pub fn save(&self) -> impl Future<Output = Result<(), String>> {
+        let snapshot = self.clone();
+        let write = Self::path().map(|path| {
+            storage::write_private(path, move || {
+                let mut data = serde_json::to_string_pretty(&snapshot)
+                    .map_err(|e| format!("Failed to serialize environments: {}", e))?;
+                data.push('\n');
+                Ok(data)
+            })
+        });
+
+        async move {
+            match write {
+                Some(write) => write.await,
+                None => Err("No config directory for environments".to_string()),
+            }
+        }
+    }
+
+    // Список для pick_list, первым идёт "без окружения"
+    pub fn names(&self) -> Vec<String> {
+        std::iter::once(NO_ENVIRONMENT.to_string())
+            .chain(self.environments.iter().map(|env| env.name.clone()))
+            .collect()
+    }
+
+    pub fn active(&self) -> Option<&Environment> {
+        let name = self.active.as_ref()?;
+        self.environments.iter().find(|env| &env.name == name)
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut Environment> {
+        let name = self.active.clone()?;
+        self.environments.iter_mut().find(|env| env.name == name)
+    }
+
+    pub fn select(&mut self, name: String) {
+        self.active = if name == NO_ENVIRONMENT { None } else { Some(name) };
+    }
+
+    // Новое окружение сразу становится активным
+    pub fn add(&mut self, name: &str) {
+        if !self.environments.iter().any(|env| env.name == name) {
+            self.environments.push(Environment {
+                name: name.to_string(),
+                variables: Vec::new(),
+            });
+        }
+        self.active = Some(name.to_string());
+    }
+
+    pub fn remove_active(&mut self) {
+        if let Some(name) = self.active.take() {
+            self.environments.retain(|env| env.name != name);
+        }
+    }
+
+    // Подстановка переменных активного окружения
+    pub fn resolve(&self, input: &str) -> String {
+        match self.active() {
+            Some(env) => substitute(input, |key| env.get(key)),
+            None => input.to_string(),
+        }
+    }
+}
+
+// Заменяем {{name}} на значение переменной.
+// Неизвестные переменные оставляем как есть, чтобы их было видно в превью
+pub fn substitute<'a>(input: &str, lookup: impl Fn(&str) -> Option<&'a str>) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        match after.find("}}") {
+            Some(end) => {
+                let placeholder = &rest[start..start + 2 + end + 2];
+                match lookup(after[..end].trim()) {
+                    Some(value) => result.push_str(value),
+                    None => result.push_str(placeholder),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                // Незакрытая скобка - дальше подставлять нечего
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+// Значение - только ссылки {{name}} без самого секрета
+pub fn is_reference(value: &str) -> bool {
+    value.contains("{{") && substitute(value, |_| Some("")).trim().is_empty()
+}
+
+// Состояние редактора окружений
+#[derive(Debug, Clone, Default)]
+pub struct Editor {
+    pub open: bool,
+    pub new_name: String,
+    pub new_key: String,
+    pub new_value: String,
+    pub error: Option<String>,
+}
+
+// Редактор переменных активного окружения
+pub fn view<'a>(environments: &'a Environments, editor: &'a Editor) -> Element<'a, Message> {
+    let new_environment = row![
+        text_input("New environment...", &editor.new_name)
+            .on_input(Message::NewEnvironmentNameChanged)
+            .on_submit(Message::AddEnvironment)
+            .width(200)
+            .padding(5),
+        button(text("+ Add").size(14))
+            .on_press(Message::AddEnvironment)
+            .padding(5)
+            .style(button::success),
+    ]
+    .spacing(8)
+    .align_y(Center);
+
+    let variables: Element<Message> = match environments.active() {
+        None => container(text("Select an environment to edit its variables").style(text::secondary))
+            .padding(10)
+            .center_x(Shrink)
+            .into(),
+        Some(env) => {
+            let rows: Vec<Element<Message>> = env
+                .variables
+                .iter()
+                .enumerate()
+                .map(|(index, variable)| {
+                    row![
+                        text_input("Name", &variable.key)
+                            .on_input(move |key| Message::UpdateVariableKey(index, key))
+                            .width(180)
+                            .padding(5),
+                        text_input("Value", &variable.value)
+                            .on_input(move |value| Message::UpdateVariableValue(index, value))
+                            .width(Fill)
+                            .padding(5),
+                        button(text("🗑️").size(14))
+                            .on_press(Message::RemoveVariable(index))
+                            .padding(5)
+                            .style(button::danger),
+                    ]
+                    .spacing(8)
+                    .align_y(Center)
+                    .into()
+                })
+                .collect();
+
+            let add_form = row![
+                text_input("Name...", &editor.new_key)
+                    .on_input(Message::NewVariableKeyChanged)
+                    .width(180)
+                    .padding(5),
+                text_input("Value...", &editor.new_value)
+                    .on_input(Message::NewVariableValueChanged)
+                    .on_submit(Message::AddVariable)
+                    .width(Fill)
+                    .padding(5),
+                button(text("+ Add").size(14))
+                    .on_press(Message::AddVariable)
+                    .padding(5)
+                    .style(button::success),
+            ]
+            .spacing(8)
+            .align_y(Center);
+
+            column![
+                row![
+                    text(format!("Variables of '{}':", env.name)).size(14).width(Fill),
+                    button(text("Delete environment").size(12))
+                        .on_press(Message::DeleteEnvironment)
+                        .padding(5)
+                        .style(button::danger),
+                ]
+                .align_y(Center),
+                column(rows).spacing(5),
+                add_form,
+            ]
+            .spacing(10)
+            .into()
+        }
+    };
+
+    let error: Element<Message> = match &editor.error {
+        Some(error) => text(error).size(12).style(text::danger).into(),
+        None => space().height(0).into(),
+    };
+
+    container(
+        column![
+            text("Environments:").size(16),
+            text("Use {{name}} in URL, query/header values and body").size(12).style(text::secondary),
+            new_environment,
+            variables,
+            error,
+        ]
+        .spacing(10)
+        .padding(10),
+    )
+    .width(Fill)
+    .style(container::bordered_box)
+    .into()
+}
@@ -0,0 +1,987 @@
+// GraphQL: query и variables уходят конвертом {query, variables, operationName},
+// схема берётся интроспекцией - по ней подсказки полей и справочник типов.
+// errors из ответа показываем отдельно от data
+use serde::Deserialize;
+use serde_json::Value;
+
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_editor};
+use iced::{Center, Element, Fill, Font};
+
+use crate::body::BodyData;
+use crate::syntax::{graphql_tokens, GraphQlKind};
+use crate::Message;
+
+// Запрос интроспекции как в GraphiQL, без директив
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+  }
+  inputFields { ...InputValue }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+  }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType {
+              kind
+              name
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+// Имена операций верхнего уровня: query/mutation/subscription Name
+pub fn operation_names(query: &str) -> Vec<String> {
+    let Ok(tokens) = graphql_tokens(query) else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    let mut tokens = tokens
+        .iter()
+        .filter(|token| token.kind != GraphQlKind::Comment)
+        .peekable();
+    while let Some(token) = tokens.next() {
+        match (token.kind, token.text) {
+            (GraphQlKind::Punct, "{") => depth += 1,
+            (GraphQlKind::Punct, "}") => depth = depth.saturating_sub(1),
+            (GraphQlKind::Name, "query" | "mutation" | "subscription") if depth == 0 => {
+                if let Some(name) = tokens.peek().filter(|next| next.kind == GraphQlKind::Name) {
+                    names.push(name.text.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+// Переменные: пусто - без переменных, иначе JSON объект
+pub fn parse_variables(variables: &str) -> Result<Option<Value>, String> {
+    if variables.trim().is_empty() {
+        return Ok(None);
+    }
+    match serde_json::from_str::<Value>(variables) {
+        Ok(value @ Value::Object(_)) => Ok(Some(value)),
+        Ok(_) => Err("Variables must be a JSON object".to_string()),
+        Err(e) => Err(format!("Invalid variables JSON: {}", e)),
+    }
+}
+
+// Тело запроса. operationName - выбранная операция, если она есть в документе,
+// иначе первая именованная
+pub fn envelope(query: &str, variables: &str, operation: &str) -> String {
+    let names = operation_names(query);
+    let operation_name = names
+        .iter()
+        .find(|name| name.as_str() == operation)
+        .or(names.first());
+
+    let mut envelope = serde_json::json!({ "query": query });
+    if let Ok(Some(variables)) = parse_variables(variables) {
+        envelope["variables"] = variables;
+    }
+    if let Some(name) = operation_name {
+        envelope["operationName"] = name.clone().into();
+    }
+    envelope.to_string()
+}
+
+// Обратно из конверта (curl импорт): (query, variables, operationName)
+pub fn from_envelope(body: &str) -> Option<(String, String, String)> {
+    let Value::Object(object) = serde_json::from_str(body).ok()? else {
+        return None;
+    };
+    if !object
+        .keys()
+        .all(|key| matches!(key.as_str(), "query" | "variables" | "operationName"))
+    {
+        return None;
+    }
+
+    let query = object.get("query")?.as_str()?.to_string();
+    let variables = match object.get("variables") {
+        Some(variables @ Value::Object(_)) => serde_json::to_string_pretty(variables).ok()?,
+        _ => String::new(),
+    };
+    let operation = object
+        .get("operationName")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Some((query, variables, operation))
+}
+
+// ---------- Схема из интроспекции ----------
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    query_type: Option<NamedType>,
+    mutation_type: Option<NamedType>,
+    subscription_type: Option<NamedType>,
+    types: Vec<SchemaType>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct NamedType {
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaType {
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    fields: Option<Vec<Field>>,
+    #[serde(default)]
+    input_fields: Option<Vec<InputValue>>,
+    #[serde(default)]
+    enum_values: Option<Vec<EnumValue>>,
+    #[serde(default)]
+    possible_types: Option<Vec<TypeRef>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Field {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<InputValue>,
+    #[serde(rename = "type")]
+    type_ref: TypeRef,
+    #[serde(default)]
+    is_deprecated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InputValue {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(rename = "type")]
+    type_ref: TypeRef,
+    #[serde(default)]
+    default_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnumValue {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+}
+
+// Ссылка на тип с обёртками NON_NULL и LIST
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    // Имя типа без обёрток: [User!]! -> User
+    fn named(&self) -> &str {
+        match (&self.name, &self.of_type) {
+            (Some(name), _) => name,
+            (None, Some(of_type)) => of_type.named(),
+            (None, None) => "",
+        }
+    }
+}
+
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.kind.as_str(), &self.of_type) {
+            ("NON_NULL", Some(of_type)) => write!(f, "{}!", of_type),
+            ("LIST", Some(of_type)) => write!(f, "[{}]", of_type),
+            _ => write!(f, "{}", self.named()),
+        }
+    }
+}
+
+impl Schema {
+    fn get(&self, name: &str) -> Option<&SchemaType> {
+        self.types.iter().find(|schema_type| schema_type.name == name)
+    }
+
+    // Тип корня операции: query, mutation, subscription
+    fn root(&self, operation: &str) -> Option<String> {
+        let root = match operation {
+            "query" => &self.query_type,
+            "mutation" => &self.mutation_type,
+            "subscription" => &self.subscription_type,
+            _ => &None,
+        };
+        root.as_ref().map(|root| root.name.clone())
+    }
+
+    fn field(&self, type_name: &str, field: &str) -> Option<&Field> {
+        self.get(type_name)?
+            .fields
+            .as_ref()?
+            .iter()
+            .find(|candidate| candidate.name == field)
+    }
+
+    pub fn type_count(&self) -> usize {
+        self.types
+            .iter()
+            .filter(|schema_type| !schema_type.name.starts_with("__"))
+            .count()
+    }
+}
+
+// Ответ на запрос интроспекции -> схема
+pub fn parse_schema(status: u16, body: &str) -> Result<Schema, String> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|_| format!("HTTP {}: response is not JSON", status))?;
+
+    match value.pointer("/data/__schema") {
+        Some(schema) => Schema::deserialize(schema)
+            .map_err(|e| format!("Invalid introspection result: {}", e)),
+        None => Err(match value.pointer("/errors/0/message").and_then(Value::as_str) {
+            Some(message) => format!("Introspection failed: {}", message),
+            None => format!("HTTP {}: no data.__schema in response", status),
+        }),
+    }
+}
+
+// ---------- Подсказки по позиции курсора ----------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub label: String,  // Имя поля, аргумента или типа
+    pub detail: String, // Его тип
+    pub insert: String, // Что допечатать после уже набранной части
+}
+
+// Что можно набрать в позиции offset (байты) по схеме:
+// поля выборки, аргументы поля в ( ) или тип после "on"
+pub fn completions(schema: &Schema, query: &str, offset: usize) -> Vec<Completion> {
+    const LIMIT: usize = 10;
+
+    let Some(before) = query.get(..offset) else {
+        return Vec::new();
+    };
+    let Ok(tokens) = graphql_tokens(before) else {
+        return Vec::new(); // Курсор внутри строки
+    };
+    let mut tokens: Vec<_> = tokens
+        .into_iter()
+        .filter(|token| token.kind != GraphQlKind::Comment)
+        .collect();
+
+    // Недописанное имя прямо перед курсором
+    let prefix = match tokens.last() {
+        Some(token) if token.kind == GraphQlKind::Name && before.ends_with(token.text) => {
+            tokens.pop().map(|token| token.text).unwrap_or_default()
+        }
+        _ => "",
+    };
+
+    // Проходим документ до курсора: стек типов открытых выборок
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut pending: Option<String> = None; // Тип для следующей {
+    let mut last_field: Option<&Field> = None;
+    let mut args_field: Option<&Field> = None;
+    let mut paren = 0usize;
+    let mut after_spread = false;
+    let mut fragment_definition = false;
+    let mut expect_type = false; // После "on"
+
+    for (index, token) in tokens.iter().enumerate() {
+        // Внутри ( ) - аргументы и значения, выборок там нет
+        if paren > 0 {
+            match token.text {
+                "(" => paren += 1,
+                ")" => paren -= 1,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (token.kind, token.text) {
+            (GraphQlKind::Punct, "(") => {
+                paren += 1;
+                args_field = last_field;
+            }
+            (GraphQlKind::Punct, "{") => {
+                let selection_type = match pending.take() {
+                    Some(pending) => Some(pending),
+                    // Сокращённая запись: { ... } - это query
+                    None if stack.is_empty() => schema.root("query"),
+                    None => None,
+                };
+                stack.push(selection_type);
+                last_field = None;
+            }
+            (GraphQlKind::Punct, "}") => {
+                stack.pop();
+                last_field = None;
+            }
+            (GraphQlKind::Directive, _) => last_field = None,
+            (GraphQlKind::Spread, _) => after_spread = true,
+            (GraphQlKind::Name, "on") if after_spread || fragment_definition => {
+                expect_type = true;
+                after_spread = false;
+                fragment_definition = false;
+            }
+            (GraphQlKind::Name, name) if expect_type => {
+                pending = Some(name.to_string());
+                expect_type = false;
+            }
+            // ...FragmentName
+            (GraphQlKind::Name, _) if after_spread => after_spread = false,
+            (GraphQlKind::Name, keyword) if stack.is_empty() => match keyword {
+                "query" | "mutation" | "subscription" => pending = schema.root(keyword),
+                "fragment" => fragment_definition = true,
+                _ => {} // Имя операции или фрагмента
+            },
+            (GraphQlKind::Name, name) => {
+                // alias: field
+                if tokens.get(index + 1).is_some_and(|next| next.text == ":") {
+                    continue;
+                }
+                let field = stack
+                    .last()
+                    .cloned()
+                    .flatten()
+                    .and_then(|parent| schema.field(&parent, name));
+                pending = field.map(|field| field.type_ref.named().to_string());
+                last_field = field;
+            }
+            _ => {}
+        }
+    }
+
+    let complete = |label: &str, detail: String| {
+        (label.starts_with(prefix) && label != prefix).then(|| Completion {
+            label: label.to_string(),
+            detail,
+            insert: label[prefix.len()..].to_string(),
+        })
+    };
+
+    if paren > 0 {
+        // После ":" набирается значение, а не имя аргумента
+        if tokens.last().is_some_and(|token| token.text == ":") {
+            return Vec::new();
+        }
+        let Some(field) = args_field else {
+            return Vec::new();
+        };
+        return field
+            .args
+            .iter()
+            .filter_map(|arg| {
+                complete(&arg.name, arg.type_ref.to_string()).map(|completion| Completion {
+                    insert: format!("{}: ", completion.insert),
+                    ..completion
+                })
+            })
+            .take(LIMIT)
+            .collect();
+    }
+
+    if expect_type {
+        return schema
+            .types
+            .iter()
+            .filter(|schema_type| {
+                matches!(schema_type.kind.as_str(), "OBJECT" | "INTERFACE" | "UNION")
+                    && !schema_type.name.starts_with("__")
+            })
+            .filter_map(|schema_type| complete(&schema_type.name, schema_type.kind.to_lowercase()))
+            .take(LIMIT)
+            .collect();
+    }
+
+    let Some(Some(parent)) = stack.last().filter(|_| !after_spread) else {
+        return Vec::new();
+    };
+    let Some(fields) = schema.get(parent).and_then(|parent| parent.fields.as_ref()) else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .filter(|field| !field.is_deprecated)
+        .filter_map(|field| complete(&field.name, field.type_ref.to_string()))
+        .chain(complete("__typename", "String!".to_string()))
+        .take(LIMIT)
+        .collect()
+}
+
+pub fn completions_view(completions: &[Completion]) -> Element<'_, Message> {
+    row(completions.iter().map(|completion| {
+        button(
+            row![
+                text(&completion.label).size(12).font(Font::MONOSPACE),
+                text(&completion.detail).size(11).style(text::secondary),
+            ]
+            .spacing(6)
+            .align_y(Center),
+        )
+        .on_press(Message::ApplyGraphQlCompletion(completion.insert.clone()))
+        .padding([2, 6])
+        .style(button::secondary)
+        .into()
+    }))
+    .spacing(5)
+    .wrap()
+    .into()
+}
+
+// ---------- Справочник по схеме ----------
+
+fn type_link<'a>(label: String, name: &str) -> Element<'a, Message> {
+    button(text(label).size(12).font(Font::MONOSPACE))
+        .on_press(Message::GraphQlDocsOpen(name.to_string()))
+        .padding(0)
+        .style(button::text)
+        .into()
+}
+
+fn description(description: &Option<String>) -> Element<'_, Message> {
+    match description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(description) => text(description).size(11).style(text::secondary).into(),
+        None => column![].into(),
+    }
+}
+
+fn arguments(args: &[InputValue]) -> String {
+    if args.is_empty() {
+        return String::new();
+    }
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| match &arg.default_value {
+            Some(default) => format!("{}: {} = {}", arg.name, arg.type_ref, default),
+            None => format!("{}: {}", arg.name, arg.type_ref),
+        })
+        .collect();
+    format!("({})", args.join(", "))
+}
+
+// path пуст - корневые типы и все типы схемы, иначе последний открытый тип
+pub fn docs_view<'a>(schema: &'a Schema, path: &'a [String]) -> Element<'a, Message> {
+    let header = row![
+        if path.is_empty() {
+            Element::from(text("Schema").size(14))
+        } else {
+            button(text("← Back").size(12))
+                .on_press(Message::GraphQlDocsBack)
+                .padding([2, 6])
+                .style(button::secondary)
+                .into()
+        },
+        text(path.last().map(String::as_str).unwrap_or_default())
+            .size(14)
+            .font(Font::MONOSPACE),
+    ]
+    .spacing(10)
+    .align_y(Center);
+
+    let body: Element<Message> = match path.last().and_then(|name| schema.get(name)) {
+        None => {
+            let roots = ["query", "mutation", "subscription"]
+                .into_iter()
+                .filter_map(|operation| {
+                    schema.root(operation).map(|name| {
+                        row![
+                            text(format!("{}:", operation)).size(12).width(90),
+                            type_link(name.clone(), &name),
+                        ]
+                        .align_y(Center)
+                        .into()
+                    })
+                });
+            let types = schema
+                .types
+                .iter()
+                .filter(|schema_type| !schema_type.name.starts_with("__"))
+                .map(|schema_type| {
+                    row![
+                        text(schema_type.kind.to_lowercase()).size(11).style(text::secondary).width(90),
+                        type_link(schema_type.name.clone(), &schema_type.name),
+                    ]
+                    .align_y(Center)
+                    .into()
+                });
+            column![
+                column(roots).spacing(2),
+                text(format!("All types ({}):", schema.type_count())).size(12),
+                column(types).spacing(2),
+            ]
+            .spacing(8)
+            .into()
+        }
+        Some(schema_type) => {
+            let mut items = column![
+                text(schema_type.kind.to_lowercase()).size(11).style(text::secondary),
+                description(&schema_type.description),
+            ]
+            .spacing(6);
+
+            for field in schema_type.fields.iter().flatten() {
+                items = items.push(
+                    column![
+                        row![
+                            text(format!(
+                                "{}{}{}:",
+                                field.name,
+                                arguments(&field.args),
+                                if field.is_deprecated { " (deprecated)" } else { "" }
+                            ))
+                            .size(12)
+                            .font(Font::MONOSPACE),
+                            type_link(field.type_ref.to_string(), field.type_ref.named()),
+                        ]
+                        .spacing(5)
+                        .align_y(Center),
+                        description(&field.description),
+                    ]
+                    .spacing(2),
+                );
+            }
+            for input in schema_type.input_fields.iter().flatten() {
+                items = items.push(
+                    column![
+                        row![
+                            text(format!("{}:", input.name)).size(12).font(Font::MONOSPACE),
+                            type_link(input.type_ref.to_string(), input.type_ref.named()),
+                        ]
+                        .spacing(5)
+                        .align_y(Center),
+                        description(&input.description),
+                    ]
+                    .spacing(2),
+                );
+            }
+            for value in schema_type.enum_values.iter().flatten() {
+                items = items.push(
+                    column![
+                        text(if value.is_deprecated {
+                            format!("{} (deprecated)", value.name)
+                        } else {
+                            value.name.clone()
+                        })
+                        .size(12)
+                        .font(Font::MONOSPACE),
+                        description(&value.description),
+                    ]
+                    .spacing(2),
+                );
+            }
+            for possible in schema_type.possible_types.iter().flatten() {
+                items = items.push(type_link(possible.to_string(), possible.named()));
+            }
+            items.into()
+        }
+    };
+
+    container(column![header, scrollable(body).height(250).width(Fill)].spacing(8))
+        .padding(10)
+        .style(container::bordered_box)
+        .into()
+}
+
+// ---------- Ответ ----------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseError {
+    pub message: String,
+    pub details: String, // path, line:column, extensions.code
+}
+
+// Ответ вида {data, errors}: ошибки списком, data отформатированным JSON.
+// None - ошибок нет или это не GraphQL ответ
+pub fn split_response(body: &str) -> Option<(Vec<ResponseError>, String)> {
+    let Value::Object(object) = serde_json::from_str(body).ok()? else {
+        return None;
+    };
+    if !object
+        .keys()
+        .all(|key| matches!(key.as_str(), "data" | "errors" | "extensions"))
+    {
+        return None;
+    }
+    let errors = object.get("errors")?.as_array()?;
+    if errors.is_empty() {
+        return None;
+    }
+
+    let errors = errors
+        .iter()
+        .map(|error| {
+            let message = match error.get("message").and_then(Value::as_str) {
+                Some(message) => message.to_string(),
+                None => error.to_string(),
+            };
+
+            let mut details = Vec::new();
+            if let Some(path) = error.get("path").and_then(Value::as_array) {
+                let path: Vec<String> = path
+                    .iter()
+                    .map(|segment| match segment {
+                        Value::String(segment) => segment.clone(),
+                        segment => segment.to_string(),
+                    })
+                    .collect();
+                details.push(format!("path: {}", path.join(".")));
+            }
+            for location in error.get("locations").and_then(Value::as_array).into_iter().flatten() {
+                if let (Some(line), Some(column)) = (
+                    location.get("line").and_then(Value::as_u64),
+                    location.get("column").and_then(Value::as_u64),
+                ) {
+                    details.push(format!("line {}:{}", line, column));
+                }
+            }
+            if let Some(code) = error.pointer("/extensions/code").and_then(Value::as_str) {
+                details.push(code.to_string());
+            }
+
+            ResponseError {
+                message,
+                details: details.join(", "),
+            }
+        })
+        .collect();
+
+    let data = serde_json::to_string_pretty(object.get("data").unwrap_or(&Value::Null)).ok()?;
+    Some((errors, data))
+}
+
+pub fn errors_view(errors: &[ResponseError]) -> Element<'_, Message> {
+    let items = errors.iter().map(|error| {
+        column![
+            text(format!("• {}", error.message)).size(13),
+            if error.details.is_empty() {
+                Element::from(column![])
+            } else {
+                text(&error.details).size(11).style(text::secondary).into()
+            },
+        ]
+        .spacing(2)
+        .into()
+    });
+
+    container(
+        column![
+            text(format!("GraphQL errors ({}):", errors.len()))
+                .size(14)
+                .style(text::danger),
+            column(items).spacing(6),
+        ]
+        .spacing(8),
+    )
+    .padding(10)
+    .width(Fill)
+    .style(|theme: &iced::Theme| container::Style {
+        border: iced::border::Border {
+            color: theme.palette().danger,
+            width: 1.5,
+            radius: 5.0.into(),
+        },
+        ..container::bordered_box(theme)
+    })
+    .into()
+}
+
+// ---------- Состояние и панель режима GraphQL ----------
+
+#[derive(Debug, Default)]
+pub struct Panel {
+    pub variables: text_editor::Content,
+    pub schema: Option<Schema>,
+    pub fetching: bool,
+    pub error: Option<String>, // Ошибка интроспекции
+    pub completions: Vec<Completion>,
+    pub docs: Option<Vec<String>>, // Открытый справочник: путь по типам
+    pub response_errors: Vec<ResponseError>,
+}
+
+// Загрузка схемы и кнопка справочника
+pub fn schema_view(panel: &Panel) -> Element<'_, Message> {
+    let status: Element<Message> = match (&panel.error, &panel.schema) {
+        (Some(error), _) => text(error).size(12).style(text::danger).into(),
+        (None, Some(schema)) => text(format!("Schema: {} types", schema.type_count()))
+            .size(12)
+            .style(text::success)
+            .into(),
+        (None, None) => text("No schema: autocomplete and docs need introspection")
+            .size(12)
+            .style(text::secondary)
+            .into(),
+    };
+
+    row![
+        button(text(if panel.fetching { "Fetching..." } else { "Fetch schema" }).size(12))
+            .on_press_maybe((!panel.fetching).then_some(Message::FetchGraphQlSchema))
+            .padding([3, 8])
+            .style(button::secondary),
+        button(text(if panel.docs.is_some() { "Hide docs" } else { "Docs" }).size(12))
+            .on_press_maybe(panel.schema.as_ref().map(|_| Message::ToggleGraphQlDocs))
+            .padding([3, 8])
+            .style(button::secondary),
+        status,
+    ]
+    .spacing(10)
+    .align_y(Center)
+    .into()
+}
+
+// Выбор операции, если в документе их несколько
+pub fn operation_view<'a>(data: &'a BodyData, query: &str) -> Element<'a, Message> {
+    let names = operation_names(query);
+    if names.len() < 2 {
+        return column![].into();
+    }
+
+    let selected = names
+        .iter()
+        .find(|name| **name == data.graphql_operation)
+        .or(names.first())
+        .cloned();
+    row![
+        text("Operation:").size(14),
+        pick_list(names, selected, move |graphql_operation| {
+            Message::BodyDataChanged(BodyData { graphql_operation, ..data.clone() })
+        })
+        .padding(5),
+    ]
+    .spacing(10)
+    .align_y(Center)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Маленькая схема: Query { user(id: ID!): User, users: [User!]! },
+    // User { id, name, posts: [Post] }, Post { title }
+    fn schema() -> Schema {
+        let scalar = |name: &str| json!({ "kind": "SCALAR", "name": name });
+        let object = |name: &str| json!({ "kind": "OBJECT", "name": name });
+        let body = json!({ "data": { "__schema": {
+            "queryType": { "name": "Query" },
+            "mutationType": null,
+            "subscriptionType": null,
+            "types": [
+                { "kind": "OBJECT", "name": "Query", "fields": [
+                    { "name": "user", "type": object("User"), "args": [
+                        { "name": "id", "type": { "kind": "NON_NULL", "ofType": scalar("ID") } }
+                    ] },
+                    { "name": "users", "args": [], "type": { "kind": "NON_NULL", "ofType":
+                        { "kind": "LIST", "ofType": { "kind": "NON_NULL", "ofType": object("User") } } } }
+                ] },
+                { "kind": "OBJECT", "name": "User", "fields": [
+                    { "name": "id", "args": [], "type": scalar("ID") },
+                    { "name": "name", "args": [], "type": scalar("String") },
+                    { "name": "nickname", "args": [], "type": scalar("String"), "isDeprecated": true },
+                    { "name": "posts", "args": [], "type": { "kind": "LIST", "ofType": object("Post") } }
+                ] },
+                { "kind": "OBJECT", "name": "Post", "fields": [
+                    { "name": "title", "args": [], "type": scalar("String") }
+                ] },
+                { "kind": "SCALAR", "name": "ID" },
+                { "kind": "SCALAR", "name": "String" },
+                { "kind": "OBJECT", "name": "__Type", "fields": [] }
+            ]
+        } } });
+        parse_schema(200, &body.to_string()).unwrap()
+    }
+
+    // Подсказки для курсора на месте "|"
+    fn labels(query: &str) -> Vec<String> {
+        let offset = query.find('|').unwrap();
+        let query = query.replace('|', "");
+        completions(&schema(), &query, offset)
+            .into_iter()
+            .map(|completion| completion.label)
+            .collect()
+    }
+
+    #[test]
+    fn operation_names_are_top_level_only() {
+        let query = "# query Commented\n\
+            query First { user(id: 1) { name } }\n\
+            mutation Second { query }\n\
+            { anonymous }\n\
+            subscription Third { posts { title } }";
+        assert_eq!(operation_names(query), ["First", "Second", "Third"]);
+        assert!(operation_names("{ user { name } }").is_empty());
+        assert!(operation_names("query Broken { \"unterminated").is_empty());
+    }
+
+    #[test]
+    fn envelope_picks_operation_and_valid_variables() {
+        let query = "query A { users { id } } query B { users { name } }";
+        let body: Value = serde_json::from_str(&envelope(query, r#"{"id": 1}"#, "B")).unwrap();
+        assert_eq!(
+            body,
+            json!({ "query": query, "variables": { "id": 1 }, "operationName": "B" })
+        );
+
+        // Неизвестная операция - первая из документа, кривые переменные не отправляем
+        let body: Value = serde_json::from_str(&envelope(query, "[1]", "C")).unwrap();
+        assert_eq!(body, json!({ "query": query, "operationName": "A" }));
+
+        let body: Value = serde_json::from_str(&envelope("{ users { id } }", "", "")).unwrap();
+        assert_eq!(body, json!({ "query": "{ users { id } }" }));
+
+        let (query, variables, operation) =
+            from_envelope(&envelope(query, r#"{"id": 1}"#, "B")).unwrap();
+        assert_eq!(query, "query A { users { id } } query B { users { name } }");
+        assert_eq!(serde_json::from_str::<Value>(&variables).unwrap(), json!({ "id": 1 }));
+        assert_eq!(operation, "B");
+    }
+
+    #[test]
+    fn split_response_separates_errors_from_data() {
+        let body = json!({
+            "data": { "user": null },
+            "errors": [
+                {
+                    "message": "Not found",
+                    "path": ["user", 0],
+                    "locations": [{ "line": 1, "column": 3 }],
+                    "extensions": { "code": "NOT_FOUND" }
+                },
+                { "code": 7 }
+            ]
+        });
+        let (errors, data) = split_response(&body.to_string()).unwrap();
+        assert_eq!(
+            errors,
+            [
+                ResponseError {
+                    message: "Not found".to_string(),
+                    details: "path: user.0, line 1:3, NOT_FOUND".to_string(),
+                },
+                ResponseError {
+                    message: r#"{"code":7}"#.to_string(),
+                    details: String::new(),
+                },
+            ]
+        );
+        assert_eq!(serde_json::from_str::<Value>(&data).unwrap(), json!({ "user": null }));
+
+        // Без ошибок, с чужими ключами или не JSON - показываем тело как есть
+        assert!(split_response(r#"{"data": {}, "errors": []}"#).is_none());
+        assert!(split_response(r#"{"data": {}}"#).is_none());
+        assert!(split_response(r#"{"errors": [{"message": "x"}], "status": 1}"#).is_none());
+        assert!(split_response("not json").is_none());
+    }
+
+    #[test]
+    fn completions_follow_selection_types() {
+        assert_eq!(labels("{ |"), ["user", "users", "__typename"]);
+        assert_eq!(labels("query Q { users { n| } }"), ["name"]);
+        // Устаревшие поля не предлагаем
+        assert_eq!(labels("{ user(id: 1) { posts { | } } }"), ["title", "__typename"]);
+        // Через alias тип поля тот же
+        assert_eq!(labels("{ me: user { po| } }"), ["posts"]);
+        assert_eq!(labels("{ users { unknown { | } } }"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn completions_for_arguments_and_fragment_types() {
+        let schema = schema();
+        let query = "{ user(";
+        assert_eq!(
+            completions(&schema, query, query.len()),
+            [Completion {
+                label: "id".to_string(),
+                detail: "ID!".to_string(),
+                insert: "id: ".to_string(),
+            }]
+        );
+        // После ":" идёт значение
+        assert!(labels("{ user(id: |) }").is_empty());
+        // Служебные типы __ не предлагаем
+        assert_eq!(labels("fragment F on |"), ["Query", "User", "Post"]);
+        assert_eq!(labels("{ users { ... on P| } }"), ["Post"]);
+        // Внутри строки подсказок нет
+        assert!(labels("{ user(id: \"|").is_empty());
+    }
+
+    #[test]
+    fn type_refs_render_wrappers() {
+        let schema = schema();
+        let users = schema.field("Query", "users").unwrap();
+        assert_eq!(users.type_ref.to_string(), "[User!]!");
+        assert_eq!(users.type_ref.named(), "User");
+        assert_eq!(schema.type_count(), 5);
+        assert!(parse_schema(500, "oops").unwrap_err().starts_with("HTTP 500"));
+        assert_eq!(
+            parse_schema(200, r#"{"errors": [{"message": "disabled"}]}"#).unwrap_err(),
+            "Introspection failed: disabled"
+        );
+    }
+}
@@ -0,0 +1,647 @@
+// gRPC: описания сервисов из .proto файла или reflection сервера, сообщение - JSON
+// в редакторе тела. Любой вызов идёт как server streaming: так у унарного и потокового
+// методов одинаково видны заголовки, сообщения, статус и трейлеры
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream};
+use iced::widget::{button, column, container, pick_list, row, scrollable, space, text};
+use iced::{Center, Element, Fill, Font};
+use prost_reflect::prost::Message as _;
+use prost_reflect::prost_types::FileDescriptorProto;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor, SerializeOptions};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{
+    AsciiMetadataKey, AsciiMetadataValue, BinaryMetadataKey, BinaryMetadataValue, MetadataMap,
+};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic_reflection::pb::v1::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::v1::ServerReflectionRequest;
+
+use crate::settings::ClientSettings;
+use crate::{HeaderParam, Message};
+
+// Сервис reflection в списке методов не нужен
+const REFLECTION_PREFIX: &str = "grpc.reflection.";
+
+// Больше сообщений потока не держим - старые уходят
+const MESSAGE_LIMIT: usize = 1000;
+
+// Диалог выбора .proto файла
+pub async fn pick_proto() -> Option<String> {
+    rfd::AsyncFileDialog::new()
+        .add_filter("Protocol Buffers", &["proto"])
+        .pick_file()
+        .await
+        .map(|file| file.path().to_string_lossy().into_owned())
+}
+
+// Разбираем .proto вместе с импортами; импорты ищутся рядом с файлом.
+// Стандартные google/protobuf/*.proto встроены в парсер
+pub async fn load_proto(path: String) -> Result<DescriptorPool, String> {
+    let file = Path::new(&path);
+    let include = file.parent().unwrap_or(Path::new("."));
+    let parsed = protobuf_parse::Parser::new()
+        .pure()
+        .include(include)
+        .input(file)
+        .parse_and_typecheck()
+        .map_err(|e| format!("Failed to parse {}: {:#}", path, e))?;
+
+    let set = protobuf::descriptor::FileDescriptorSet {
+        file: parsed.file_descriptors,
+        ..Default::default()
+    };
+    let bytes = protobuf::Message::write_to_bytes(&set)
+        .map_err(|e| format!("Failed to encode descriptors: {}", e))?;
+    DescriptorPool::decode(bytes.as_slice()).map_err(|e| format!("Invalid descriptors: {}", e))
+}
+
+// Описания с сервера (grpc.reflection.v1): список сервисов, затем файлы с ними
+pub async fn reflect(
+    url: String,
+    headers: Vec<HeaderParam>,
+    settings: ClientSettings,
+) -> Result<DescriptorPool, String> {
+    let mut client = ServerReflectionClient::new(connect(&url, &settings).await?);
+
+    let list = vec![MessageRequest::ListServices(String::new())];
+    let mut services = Vec::new();
+    for response in reflection_request(&mut client, &headers, list).await? {
+        if let MessageResponse::ListServicesResponse(list) = response {
+            services.extend(
+                list.service
+                    .into_iter()
+                    .map(|service| service.name)
+                    .filter(|name| !name.starts_with(REFLECTION_PREFIX)),
+            );
+        }
+    }
+    if services.is_empty() {
+        return Err("Server reflection returned no services".to_string());
+    }
+
+    // Файлы с сервисами, затем импорты, которых сервер сам не прислал
+    let mut requests: Vec<MessageRequest> =
+        services.into_iter().map(MessageRequest::FileContainingSymbol).collect();
+    let mut names = HashSet::new();
+    let mut requested = HashSet::new(); // Каждый импорт спрашиваем один раз
+    let mut files: Vec<FileDescriptorProto> = Vec::new();
+    while !requests.is_empty() {
+        for response in reflection_request(&mut client, &headers, requests).await? {
+            match response {
+                MessageResponse::FileDescriptorResponse(response) => {
+                    for bytes in response.file_descriptor_proto {
+                        let file = FileDescriptorProto::decode(bytes.as_slice())
+                            .map_err(|e| format!("Invalid descriptor from server: {}", e))?;
+                        if names.insert(file.name().to_string()) {
+                            files.push(file);
+                        }
+                    }
+                }
+                MessageResponse::ErrorResponse(error) => {
+                    return Err(format!("Reflection error {}: {}", error.error_code, error.error_message));
+                }
+                _ => {}
+            }
+        }
+        requests = files
+            .iter()
+            .flat_map(|file| &file.dependency)
+            .filter(|name| !names.contains(*name) && requested.insert(name.to_string()))
+            .map(|name| MessageRequest::FileByFilename(name.clone()))
+            .collect();
+    }
+
+    let mut pool = DescriptorPool::new();
+    pool.add_file_descriptor_protos(files)
+        .map_err(|e| format!("Invalid descriptors from server: {}", e))?;
+    Ok(pool)
+}
+
+async fn reflection_request(
+    client: &mut ServerReflectionClient<Channel>,
+    headers: &[HeaderParam],
+    requests: Vec<MessageRequest>,
+) -> Result<Vec<MessageResponse>, String> {
+    let requests = requests.into_iter().map(|request| ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(request),
+    });
+    let mut request = tonic::Request::new(iced::futures::stream::iter(requests));
+    *request.metadata_mut() = metadata(headers)?;
+
+    let mut stream = client
+        .server_reflection_info(request)
+        .await
+        .map_err(|status| format!("Server reflection failed: {}", status_text(&status)))?
+        .into_inner();
+    let mut responses = Vec::new();
+    while let Some(response) = stream
+        .message()
+        .await
+        .map_err(|status| format!("Server reflection failed: {}", status_text(&status)))?
+    {
+        responses.extend(response.message_response);
+    }
+    Ok(responses)
+}
+
+// Без схемы считаем адрес открытым (http://)
+async fn connect(url: &str, settings: &ClientSettings) -> Result<Channel, String> {
+    let url = if url.contains("://") { url.to_string() } else { format!("http://{}", url) };
+    let mut endpoint =
+        Endpoint::from_shared(url.clone()).map_err(|e| format!("Invalid gRPC URL: {}", e))?;
+    if url.starts_with("https://") {
+        endpoint = endpoint
+            .tls_config(ClientTlsConfig::new().with_native_roots())
+            .map_err(|e| format!("TLS error: {}", error_text(&e)))?;
+    }
+    if let Some(ms) = settings.connect_timeout_ms {
+        endpoint = endpoint.connect_timeout(Duration::from_millis(ms));
+    }
+    endpoint
+        .connect()
+        .await
+        .map_err(|e| format!("Connection failed: {}", error_text(&e)))
+}
+
+// Ошибка транспорта вместе с причинами: сама по себе она малоинформативна
+fn error_text(error: &dyn std::error::Error) -> String {
+    let mut text = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        text.push_str(": ");
+        text.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    text
+}
+
+fn status_text(status: &tonic::Status) -> String {
+    format!("{:?} {}", status.code(), status.message())
+}
+
+// Заголовки таблицы - metadata запроса; ключи *-bin ждут значение в base64
+fn metadata(headers: &[HeaderParam]) -> Result<MetadataMap, String> {
+    let mut metadata = MetadataMap::new();
+    for header in headers.iter().filter(|header| !header.key.trim().is_empty()) {
+        let key = header.key.trim().to_ascii_lowercase();
+        if key.ends_with("-bin") {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(header.value.trim())
+                .map_err(|e| format!("Metadata {} is not valid base64: {}", key, e))?;
+            let key = BinaryMetadataKey::from_bytes(key.as_bytes())
+                .map_err(|_| format!("Invalid metadata key: {}", key))?;
+            metadata.append_bin(key, BinaryMetadataValue::from_bytes(&bytes));
+        } else {
+            let value = AsciiMetadataValue::try_from(header.value.as_str())
+                .map_err(|_| format!("Invalid value for metadata {}", key))?;
+            let key = AsciiMetadataKey::from_bytes(key.as_bytes())
+                .map_err(|_| format!("Invalid metadata key: {}", key))?;
+            metadata.append(key, value);
+        }
+    }
+    Ok(metadata)
+}
+
+fn metadata_pairs(metadata: &MetadataMap) -> Vec<(String, String)> {
+    metadata
+        .clone()
+        .into_headers()
+        .iter()
+        .map(|(key, value)| (key.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect()
+}
+
+// Кодек для сообщений, известных только по описанию
+#[derive(Debug, Clone)]
+struct DynamicCodec(MessageDescriptor); // Тип ответа
+
+#[derive(Debug)]
+struct DynamicEncoder;
+
+#[derive(Debug)]
+struct DynamicDecoder(MessageDescriptor);
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder(self.0.clone())
+    }
+}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = tonic::Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| tonic::Status::internal(format!("Failed to encode message: {}", e)))
+    }
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = tonic::Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.0.clone(), src)
+            .map(Some)
+            .map_err(|e| tonic::Status::internal(format!("Failed to decode message: {}", e)))
+    }
+}
+
+// JSON редактора -> сообщение запроса (пустой текст - пустое сообщение)
+pub fn parse_message(method: &MethodDescriptor, json: &str) -> Result<DynamicMessage, String> {
+    let json = if json.trim().is_empty() { "{}" } else { json };
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let message = DynamicMessage::deserialize(method.input(), &mut deserializer)
+        .and_then(|message| deserializer.end().map(|_| message))
+        .map_err(|e| format!("Invalid {} JSON: {}", method.input().full_name(), e))?;
+    Ok(message)
+}
+
+fn to_json(message: &DynamicMessage, options: &SerializeOptions) -> String {
+    let mut serializer = serde_json::Serializer::pretty(Vec::new());
+    match message.serialize_with_options(&mut serializer, options) {
+        Ok(()) => String::from_utf8_lossy(&serializer.into_inner()).into_owned(),
+        Err(e) => format!("Failed to convert message to JSON: {}", e),
+    }
+}
+
+// Заготовка запроса: все поля со значениями по умолчанию
+pub fn template(method: &MethodDescriptor) -> String {
+    to_json(
+        &DynamicMessage::new(method.input()),
+        &SerializeOptions::new().skip_default_fields(false),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct CallStatus {
+    pub code: tonic::Code,
+    pub message: String,
+    pub trailers: Vec<(String, String)>,
+}
+
+impl From<tonic::Status> for CallStatus {
+    // Для ответа без тела (trailers-only) metadata - это и есть трейлеры
+    fn from(status: tonic::Status) -> Self {
+        Self {
+            code: status.code(),
+            message: status.message().to_string(),
+            trailers: metadata_pairs(status.metadata()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Headers(Vec<(String, String)>),
+    Received(String), // Сообщение ответа в JSON
+    Finished(Result<CallStatus, String>), // Err - до gRPC статуса дело не дошло
+}
+
+// Вызов метода: события по мере прихода, последним - Finished
+pub fn call(
+    url: String,
+    headers: Vec<HeaderParam>,
+    settings: ClientSettings,
+    method: MethodDescriptor,
+    message: DynamicMessage,
+) -> impl Stream<Item = Event> {
+    iced::stream::channel(100, async move |mut output: mpsc::Sender<Event>| {
+        let result = run_call(&mut output, url, headers, settings, method, message).await;
+        let _ = output.send(Event::Finished(result)).await;
+    })
+}
+
+async fn run_call(
+    output: &mut mpsc::Sender<Event>,
+    url: String,
+    headers: Vec<HeaderParam>,
+    settings: ClientSettings,
+    method: MethodDescriptor,
+    message: DynamicMessage,
+) -> Result<CallStatus, String> {
+    let mut request = tonic::Request::new(message);
+    *request.metadata_mut() = metadata(&headers)?;
+    let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+    let path = PathAndQuery::try_from(path).map_err(|e| format!("Invalid method path: {}", e))?;
+
+    let mut grpc = tonic::client::Grpc::new(connect(&url, &settings).await?);
+    grpc.ready()
+        .await
+        .map_err(|e| format!("Connection failed: {}", error_text(&e)))?;
+
+    let response = match grpc.server_streaming(request, path, DynamicCodec(method.output())).await {
+        Ok(response) => response,
+        Err(status) => return Ok(status.into()),
+    };
+    let _ = output.send(Event::Headers(metadata_pairs(response.metadata()))).await;
+
+    let mut stream = response.into_inner();
+    loop {
+        match stream.message().await {
+            Ok(Some(message)) => {
+                let json = to_json(&message, &SerializeOptions::new());
+                let _ = output.send(Event::Received(json)).await;
+            }
+            Ok(None) => break,
+            Err(status) => return Ok(status.into()),
+        }
+    }
+    match stream.trailers().await {
+        Ok(trailers) => Ok(CallStatus {
+            code: tonic::Code::Ok,
+            message: String::new(),
+            trailers: trailers.as_ref().map(metadata_pairs).unwrap_or_default(),
+        }),
+        Err(status) => Ok(status.into()),
+    }
+}
+
+// Ход и итог вызова для панели ответа
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub started: Instant,
+    pub elapsed: Option<Duration>,
+    pub headers: Vec<(String, String)>,
+    pub messages: Vec<String>,
+    pub result: Option<Result<CallStatus, String>>,
+}
+
+// Состояние режима gRPC
+#[derive(Debug, Default)]
+pub struct Panel {
+    pub pool: Option<DescriptorPool>,
+    pub source: String, // Откуда описания: .proto файл или адрес сервера
+    pub loading: bool,
+    pub error: Option<String>,
+    pub service: Option<String>,
+    pub method: Option<String>,
+    pub call: Option<Call>,
+}
+
+impl Panel {
+    pub fn services(&self) -> Vec<String> {
+        self.pool
+            .iter()
+            .flat_map(|pool| pool.services())
+            .map(|service| service.full_name().to_string())
+            .filter(|name| !name.starts_with(REFLECTION_PREFIX))
+            .collect()
+    }
+
+    pub fn methods(&self) -> Vec<String> {
+        self.service_descriptor()
+            .map(|service| service.methods().map(|method| method.name().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    fn service_descriptor(&self) -> Option<prost_reflect::ServiceDescriptor> {
+        self.pool.as_ref()?.get_service_by_name(self.service.as_ref()?)
+    }
+
+    pub fn method_descriptor(&self) -> Result<MethodDescriptor, String> {
+        if self.pool.is_none() {
+            return Err("Load a .proto file or use server reflection first".to_string());
+        }
+        let service = self.service_descriptor().ok_or("Choose a gRPC service")?;
+        let name = self.method.as_ref().ok_or("Choose a gRPC method")?;
+        let method = service.methods().find(|method| method.name() == name);
+        method.ok_or_else(|| format!("Method {} not found in {}", name, service.full_name()))
+    }
+
+    // Выбор метода для сохранения: "package.Service/Method"
+    pub fn selected(&self) -> String {
+        match (&self.service, &self.method) {
+            (Some(service), Some(method)) => format!("{}/{}", service, method),
+            _ => String::new(),
+        }
+    }
+
+    pub fn select(&mut self, selected: &str) {
+        let (service, method) = match selected.split_once('/') {
+            Some((service, method)) => (Some(service.to_string()), Some(method.to_string())),
+            None => (None, None),
+        };
+        self.service = service;
+        self.method = method;
+    }
+
+    // Новые описания; прежний выбор остаётся, если он в них есть
+    pub fn set_pool(&mut self, pool: DescriptorPool, source: String) {
+        self.pool = Some(pool);
+        self.source = source;
+        self.error = None;
+        if self.service_descriptor().is_none() {
+            self.service = self.services().into_iter().next();
+            self.method = None;
+        }
+        if self.method_descriptor().is_err() {
+            self.method = self.methods().into_iter().next();
+        }
+    }
+
+    pub fn start_call(&mut self) {
+        self.call = Some(Call {
+            started: Instant::now(),
+            elapsed: None,
+            headers: Vec::new(),
+            messages: Vec::new(),
+            result: None,
+        });
+    }
+
+    // Вызов идёт: начат и ещё без статуса
+    pub fn is_calling(&self) -> bool {
+        self.call.as_ref().is_some_and(|call| call.result.is_none())
+    }
+
+    // true - вызов закончился
+    pub fn handle(&mut self, event: Event) -> bool {
+        let Some(call) = &mut self.call else {
+            return true;
+        };
+        match event {
+            Event::Headers(headers) => call.headers = headers,
+            Event::Received(json) => {
+                if call.messages.len() >= MESSAGE_LIMIT {
+                    call.messages.remove(0);
+                }
+                call.messages.push(json);
+            }
+            Event::Finished(result) => {
+                call.elapsed = Some(call.started.elapsed());
+                call.result = Some(result);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// Над редактором: откуда описания, сервис и метод
+pub fn method_view(panel: &Panel) -> Element<'_, Message> {
+    let source: Element<Message> = match (&panel.error, panel.loading) {
+        (_, true) => text("Loading descriptors...").size(12).style(text::secondary).into(),
+        (Some(error), _) => text(error).size(12).style(text::danger).into(),
+        (None, _) if panel.pool.is_some() => {
+            text(format!("{} services from {}", panel.services().len(), panel.source))
+                .size(12)
+                .style(text::secondary)
+                .into()
+        }
+        (None, _) => text("No descriptors loaded").size(12).style(text::secondary).into(),
+    };
+
+    let kind = match panel.method_descriptor() {
+        Ok(method) if method.is_client_streaming() => {
+            text("client streaming (not supported)").style(text::danger)
+        }
+        Ok(method) if method.is_server_streaming() => text("server streaming").style(text::secondary),
+        Ok(_) => text("unary").style(text::secondary),
+        Err(_) => text(""),
+    }
+    .size(12);
+
+    column![
+        row![
+            text("Message:").size(16),
+            button(text("📂 Load .proto").size(12))
+                .on_press_maybe((!panel.loading).then_some(Message::GrpcPickProto))
+                .padding([3, 8])
+                .style(button::secondary),
+            button(text("Server reflection").size(12))
+                .on_press_maybe((!panel.loading).then_some(Message::GrpcReflect))
+                .padding([3, 8])
+                .style(button::secondary),
+            source,
+        ]
+        .spacing(10)
+        .align_y(Center),
+        row![
+            pick_list(panel.services(), panel.service.clone(), Message::GrpcServiceSelected)
+                .placeholder("Service")
+                .text_size(12)
+                .padding(3),
+            pick_list(panel.methods(), panel.method.clone(), Message::GrpcMethodSelected)
+                .placeholder("Method")
+                .text_size(12)
+                .padding(3),
+            kind,
+            space().width(Fill),
+            button(text("Template").size(12))
+                .on_press_maybe(panel.method_descriptor().is_ok().then_some(Message::GrpcTemplate))
+                .padding([3, 8])
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(Center),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn metadata_table(pairs: &[(String, String)]) -> Element<'_, Message> {
+    if pairs.is_empty() {
+        return text("None").size(12).style(text::secondary).into();
+    }
+    let rows: Vec<Element<Message>> = pairs
+        .iter()
+        .map(|(key, value)| {
+            row![
+                text(key).size(12).font(Font::MONOSPACE).width(220),
+                text(value).size(12).font(Font::MONOSPACE).width(Fill),
+            ]
+            .spacing(10)
+            .into()
+        })
+        .collect();
+    container(column(rows).spacing(4))
+        .padding(10)
+        .width(Fill)
+        .style(container::bordered_box)
+        .into()
+}
+
+// Панель ответа: статус, заголовки, сообщения и трейлеры
+pub fn response_view(panel: &Panel) -> Element<'_, Message> {
+    let Some(call) = &panel.call else {
+        return text("No response yet. Choose a method and click 'Send Request'.")
+            .style(text::secondary)
+            .into();
+    };
+
+    let status: Element<Message> = match &call.result {
+        None => text("Call in progress...").size(18).style(text::secondary).into(),
+        Some(Err(error)) => column![
+            text("❌ Error").size(18).style(text::danger),
+            text(error).size(14),
+        ]
+        .spacing(5)
+        .into(),
+        Some(Ok(status)) => row![
+            text(format!("Status: {} {:?}", i32::from(status.code), status.code))
+                .size(18)
+                .style(if status.code == tonic::Code::Ok { text::success } else { text::danger }),
+            text(&status.message).size(14),
+        ]
+        .spacing(20)
+        .align_y(Center)
+        .into(),
+    };
+
+    let mut content = column![
+        status,
+        text(format!(
+            "Time: {}",
+            crate::response::format_duration(call.elapsed.unwrap_or_else(|| call.started.elapsed()))
+        ))
+        .size(12),
+        text(format!("Response Headers ({}):", call.headers.len())).size(14),
+        metadata_table(&call.headers),
+        text(format!("Messages ({}):", call.messages.len())).size(14),
+        container(
+            scrollable(
+                column(call.messages.iter().map(|json| {
+                    text(json).size(12).font(Font::MONOSPACE).into()
+                }))
+                .spacing(10)
+            )
+            .height(300)
+            .width(Fill)
+            .anchor_bottom()
+        )
+        .padding(10)
+        .style(container::bordered_box),
+    ]
+    .spacing(10);
+
+    if let Some(Ok(status)) = &call.result {
+        content = content
+            .push(text(format!("Trailers ({}):", status.trailers.len())).size(14))
+            .push(metadata_table(&status.trailers));
+    }
+
+    container(content)
+        .padding(15)
+        .style(container::bordered_box)
+        .into()
+}
@@ -0,0 +1,800 @@
+// История отправленных запросов: каждый SendRequest попадает сюда
+// и сохраняется в файл пользователя, чтобы можно было повторить вызов
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use iced::widget::{button, column, container, row, scrollable, space, text, text_input};
+use iced::{Center, Element, Fill};
+
+use crate::collection::SavedRequest;
+use crate::auth::{Auth, AuthKind};
+use crate::body::{BodyData, MultipartPart, PartKind};
+use crate::environment;
+use crate::query::{self, ArrayStyle};
+use crate::settings::ClientSettings;
+use crate::response::HttpResponse;
+use crate::storage;
+use crate::syntax::Syntax;
+use crate::{ConnectionType, HeaderParam, HttpMethod, Message, QueryParam};
+
+// Сколько записей держим в истории
+const MAX_ENTRIES: usize = 500;
+// Большие ответы обрезаем, чтобы файл истории не разрастался
+const MAX_STORED_BODY: usize = 64 * 1024;
+// Чем заменяем значения секретных заголовков и параметров
+const REDACTED: &str = "REDACTED";
+const QUOTED_REDACTED: &str = "\"REDACTED\""; // То же в JSON теле
+// Заголовки и параметры с такими словами в имени считаем секретными
+const SENSITIVE_WORDS: &[&str] = &[
+    "authorization", "token", "secret", "password", "passwd", "api-key", "api_key", "apikey",
+    "cookie", "session", "signature", "credential",
+];
+
+// Запись истории
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: u64, // Unix-время отправки, секунды
+    pub method: HttpMethod,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub custom_method: String,
+    #[serde(default)]
+    pub force_body: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<ClientSettings>, // Свои настройки клиента
+    #[serde(default, skip_serializing_if = "Auth::is_none")]
+    pub auth: Auth,
+    #[serde(default)]
+    pub auth_redacted: bool, // Секреты авторизации стёрты
+    pub url: String,       // URL как был введён
+    pub final_url: String, // URL вместе с query параметрами
+    #[serde(default)]
+    pub query_params: Vec<QueryParam>,
+    #[serde(default)]
+    pub array_style: ArrayStyle,
+    #[serde(default)]
+    pub headers: Vec<HeaderParam>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default, skip_serializing_if = "BodyData::is_default")]
+    pub body_data: BodyData,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub elapsed_ms: u128,
+    #[serde(default)]
+    pub response_headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub response_version: String,
+    #[serde(default)]
+    pub response_size: usize,
+    #[serde(default)]
+    pub ttfb_ms: u128,
+    #[serde(default)]
+    pub response_body: String,
+    #[serde(skip)]
+    search: String, // Всё, по чему ищем, в нижнем регистре
+}
+
+impl HistoryEntry {
+    // Секреты в файл истории не попадают: авторизация без секретов,
+    // значения секретных заголовков, параметров (и в итоговом URL), полей формы
+    // и ключей JSON тела скрыты
+    pub fn new(
+        request: SavedRequest,
+        final_url: String,
+        result: &Result<HttpResponse, String>,
+        elapsed_ms: u128,
+    ) -> Self {
+        let sensitive = |name: &str| is_sensitive(name, &request.auth);
+        let auth = request.auth.without_secrets();
+        let json_body = request.body_data.syntax() == Syntax::Json;
+        let (status, error, response) = match result {
+            Ok(response) => (Some(response.status), None, Some(response)),
+            Err(error) => (None, Some(error.clone()), None),
+        };
+
+        let mut entry = Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            method: request.method,
+            custom_method: request.custom_method,
+            force_body: request.force_body,
+            settings: request.settings,
+            auth_redacted: auth != request.auth,
+            auth,
+            url: request.url,
+            final_url: redact_url(&final_url, sensitive),
+            query_params: request
+                .query_params
+                .into_iter()
+                .map(|param| QueryParam {
+                    value: redact(&param.key, &param.value, sensitive),
+                    ..param
+                })
+                .collect(),
+            array_style: request.array_style,
+            headers: request
+                .headers
+                .into_iter()
+                .map(|header| HeaderParam {
+                    value: redact(&header.key, &header.value, sensitive),
+                    ..header
+                })
+                .collect(),
+            body: match json_body {
+                true => redact_json(&request.body, sensitive),
+                false => request.body,
+            },
+            body_data: BodyData {
+                form_fields: request
+                    .body_data
+                    .form_fields
+                    .into_iter()
+                    .map(|field| QueryParam {
+                        value: redact(&field.key, &field.value, sensitive),
+                        ..field
+                    })
+                    .collect(),
+                parts: request
+                    .body_data
+                    .parts
+                    .into_iter()
+                    .map(|part| MultipartPart {
+                        value: match part.kind {
+                            PartKind::Text => redact(&part.name, &part.value, sensitive),
+                            PartKind::File => part.value,
+                        },
+                        ..part
+                    })
+                    .collect(),
+                graphql_variables: redact_json(&request.body_data.graphql_variables, sensitive),
+                ..request.body_data
+            },
+            status,
+            error,
+            elapsed_ms,
+            response_headers: response
+                .map(|r| {
+                    r.headers
+                        .iter()
+                        .map(|(name, value)| (name.clone(), redact(name, value, sensitive)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            response_version: response.map(|r| r.version.clone()).unwrap_or_default(),
+            response_size: response.map(|r| r.size).unwrap_or_default(),
+            ttfb_ms: response.map(|r| r.ttfb.as_millis()).unwrap_or_default(),
+            response_body: response.map(|r| truncate(&r.body)).unwrap_or_default(),
+            search: String::new(),
+        };
+        entry.search = entry.search_key();
+        entry
+    }
+
+    // Обратно в запрос для редактора. Скрытые значения берём из live (что сейчас
+    // в редакторе): одноимённые заголовки, параметры, поля и секреты авторизации
+    // того же типа. Второе - что взять неоткуда, с таким запросом не отправляем
+    pub fn restore(&self, live: &SavedRequest) -> (SavedRequest, Vec<String>) {
+        let (auth, auth_missing) = match self.auth_redacted {
+            true => match self.auth.with_secrets_from(&live.auth) {
+                Some(auth) => (auth, false),
+                None => (self.auth.clone(), true),
+            },
+            false => (self.auth.clone(), false),
+        };
+
+        let request = SavedRequest {
+            name: String::new(),
+            method: self.method,
+            custom_method: self.custom_method.clone(),
+            force_body: self.force_body,
+            settings: self.settings.clone(),
+            auth,
+            url: self.url.clone(),
+            query_params: self
+                .query_params
+                .iter()
+                .map(|param| QueryParam {
+                    value: restore(&param.value, || {
+                        live.query_params.iter().find(|live| live.key == param.key).map(|live| &live.value)
+                    }),
+                    ..param.clone()
+                })
+                .collect(),
+            array_style: self.array_style,
+            headers: self
+                .headers
+                .iter()
+                .map(|header| HeaderParam {
+                    value: restore(&header.value, || {
+                        live.headers
+                            .iter()
+                            .find(|live| live.key.eq_ignore_ascii_case(&header.key))
+                            .map(|live| &live.value)
+                    }),
+                    ..header.clone()
+                })
+                .collect(),
+            body: restore_json(&self.body, &live.body),
+            body_data: BodyData {
+                form_fields: self
+                    .body_data
+                    .form_fields
+                    .iter()
+                    .map(|field| QueryParam {
+                        value: restore(&field.value, || {
+                            live.body_data
+                                .form_fields
+                                .iter()
+                                .find(|live| live.key == field.key)
+                                .map(|live| &live.value)
+                        }),
+                        ..field.clone()
+                    })
+                    .collect(),
+                parts: self
+                    .body_data
+                    .parts
+                    .iter()
+                    .map(|part| MultipartPart {
+                        value: restore(&part.value, || {
+                            live.body_data
+                                .parts
+                                .iter()
+                                .find(|live| live.name == part.name && live.kind == part.kind)
+                                .map(|live| &live.value)
+                        }),
+                        ..part.clone()
+                    })
+                    .collect(),
+                graphql_variables: restore_json(
+                    &self.body_data.graphql_variables,
+                    &live.body_data.graphql_variables,
+                ),
+                ..self.body_data.clone()
+            },
+            connection: ConnectionType::Http,
+            grpc_method: String::new(),
+        };
+
+        let mut missing = redacted_names(&request);
+        if auth_missing {
+            missing.insert(0, format!("{} secrets", self.auth.kind));
+        }
+        (request, missing)
+    }
+
+    // Сохранённый ответ для панели Response
+    pub fn to_response(&self) -> Option<HttpResponse> {
+        self.status.map(|status| HttpResponse {
+            status,
+            version: self.response_version.clone(),
+            headers: self.response_headers.clone(),
+            ttfb: Duration::from_millis(self.ttfb_ms as u64),
+            elapsed: Duration::from_millis(self.elapsed_ms as u64),
+            size: self.response_size,
+            body: self.response_body.clone(),
+            body_file: None,
+            event_stream: None,
+        })
+    }
+
+    // Поиск по методу, URL, статусу, телу запроса и ответа.
+    // Приводим к нижнему регистру один раз, а не на каждой отрисовке
+    fn search_key(&self) -> String {
+        let status = self.status.map(|status| status.to_string()).unwrap_or_default();
+        [
+            self.method.name(&self.custom_method).as_str(),
+            &self.final_url,
+            &status,
+            &self.body,
+            &self.response_body,
+        ]
+        .join("\n")
+        .to_lowercase()
+    }
+
+    // query уже в нижнем регистре
+    fn matches(&self, query: &str) -> bool {
+        self.search.contains(query)
+    }
+}
+
+// Заголовок или параметр с секретом: по имени или имя API ключа из авторизации
+fn is_sensitive(name: &str, auth: &Auth) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_WORDS.iter().any(|word| name.contains(word))
+        || matches!(name.as_str(), "key" | "auth" | "sig")
+        || (auth.kind == AuthKind::ApiKey && name.eq_ignore_ascii_case(auth.api_key_name.trim()))
+}
+
+// Ссылки {{name}} и пустые значения оставляем, остальное скрываем
+fn redact(name: &str, value: &str, sensitive: impl Fn(&str) -> bool) -> String {
+    if value.is_empty() || environment::is_reference(value) || !sensitive(name) {
+        value.to_string()
+    } else {
+        REDACTED.to_string()
+    }
+}
+
+// Итоговый URL уже с подставленными переменными и API ключом в query
+fn redact_url(url: &str, sensitive: impl Fn(&str) -> bool) -> String {
+    let (_, params) = query::split_url(url);
+    if !params.iter().any(|param| !param.value.is_empty() && sensitive(&param.key)) {
+        return url.to_string();
+    }
+
+    let params: Vec<QueryParam> = params
+        .into_iter()
+        .map(|param| QueryParam {
+            value: redact(&param.key, &param.value, &sensitive),
+            ..param
+        })
+        .collect();
+    query::set_query(url, &params)
+}
+
+// Скрытое значение заменяем живым, если оно есть
+fn restore<'a>(value: &str, live: impl FnOnce() -> Option<&'a String>) -> String {
+    match live() {
+        Some(live) if value == REDACTED && live != REDACTED => live.clone(),
+        _ => value.to_string(),
+    }
+}
+
+// Имена полей, где осталось REDACTED: такой запрос ушёл бы с чужими данными
+pub fn redacted_names(request: &SavedRequest) -> Vec<String> {
+    let sensitive = |name: &str| is_sensitive(name, &request.auth);
+    let redacted = |name: &str, value: &str| value == REDACTED && sensitive(name);
+
+    let mut names: Vec<String> = request
+        .query_params
+        .iter()
+        .chain(&request.body_data.form_fields)
+        .filter(|param| redacted(&param.key, &param.value))
+        .map(|param| param.key.clone())
+        .chain(
+            request
+                .headers
+                .iter()
+                .filter(|header| redacted(&header.key, &header.value))
+                .map(|header| header.key.clone()),
+        )
+        .chain(
+            request
+                .body_data
+                .parts
+                .iter()
+                .filter(|part| part.kind == PartKind::Text && redacted(&part.name, &part.value))
+                .map(|part| part.name.clone()),
+        )
+        .collect();
+    for body in [&request.body, &request.body_data.graphql_variables] {
+        names.extend(
+            json_secrets(body, sensitive)
+                .into_iter()
+                .filter(|secret| &body[secret.value.clone()] == QUOTED_REDACTED)
+                .map(|secret| secret.key.to_string()),
+        );
+    }
+    names.dedup();
+    names
+}
+
+// Значение секретного ключа в JSON: "password": "..." или число
+struct JsonSecret<'a> {
+    key: &'a str,
+    value: std::ops::Range<usize>, // Байты значения вместе с кавычками
+}
+
+// Ищем пары "ключ": значение прямо в тексте, без разбора: так формат тела
+// не меняется, а тела с {{переменными}} вне кавычек тоже обрабатываются
+fn json_secrets<'a>(body: &'a str, sensitive: impl Fn(&str) -> bool) -> Vec<JsonSecret<'a>> {
+    let bytes = body.as_bytes();
+    // Конец строки, начатой кавычкой в start
+    let string_end = |start: usize| {
+        let mut index = start + 1;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'\\' => index += 2,
+                b'"' => return Some(index + 1),
+                _ => index += 1,
+            }
+        }
+        None
+    };
+    let skip_spaces = |mut index: usize| {
+        while bytes.get(index).is_some_and(u8::is_ascii_whitespace) {
+            index += 1;
+        }
+        index
+    };
+
+    let mut secrets = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] != b'"' {
+            index += 1;
+            continue;
+        }
+        let Some(key_end) = string_end(index) else {
+            break;
+        };
+        let key = &body[index + 1..key_end - 1];
+        index = key_end;
+
+        let colon = skip_spaces(key_end);
+        if bytes.get(colon) != Some(&b':') || !sensitive(key) {
+            continue;
+        }
+        let start = skip_spaces(colon + 1);
+        let end = match bytes.get(start) {
+            Some(b'"') => match string_end(start) {
+                Some(end) => end,
+                None => break,
+            },
+            Some(byte) if byte.is_ascii_digit() || *byte == b'-' => {
+                let mut end = start + 1;
+                while bytes
+                    .get(end)
+                    .is_some_and(|byte| byte.is_ascii_digit() || b".eE+-".contains(byte))
+                {
+                    end += 1;
+                }
+                end
+            }
+            _ => continue, // Объект, массив, null или {{переменная}}
+        };
+        secrets.push(JsonSecret { key, value: start..end });
+        index = end;
+    }
+    secrets
+}
+
+// Скрываем значения секретных ключей, ссылки {{name}} и пустые строки оставляем
+fn redact_json(body: &str, sensitive: impl Fn(&str) -> bool) -> String {
+    let mut redacted = body.to_string();
+    for secret in json_secrets(body, sensitive).iter().rev() {
+        let value = &body[secret.value.clone()];
+        let unquoted = value.trim_matches('"');
+        if unquoted.is_empty() || environment::is_reference(unquoted) {
+            continue;
+        }
+        redacted.replace_range(secret.value.clone(), QUOTED_REDACTED);
+    }
+    redacted
+}
+
+// Скрытые значения берём из живого тела: n-й такой же ключ
+fn restore_json(body: &str, live: &str) -> String {
+    let any = |_: &str| true;
+    let live_secrets = json_secrets(live, any);
+    let secrets = json_secrets(body, any);
+
+    let mut restored = body.to_string();
+    for (index, secret) in secrets.iter().enumerate().rev() {
+        if body[secret.value.clone()] != *QUOTED_REDACTED {
+            continue;
+        }
+        let nth = secrets[..index].iter().filter(|other| other.key == secret.key).count();
+        let value = live_secrets
+            .iter()
+            .filter(|other| other.key == secret.key)
+            .nth(nth)
+            .map(|other| &live[other.value.clone()])
+            .filter(|value| *value != QUOTED_REDACTED);
+        if let Some(value) = value {
+            restored.replace_range(secret.value.clone(), value);
+        }
+    }
+    restored
+}
+
+// Обрезаем по границе символа
+fn truncate(body: &str) -> String {
+    if body.len() <= MAX_STORED_BODY {
+        return body.to_string();
+    }
+
+    let mut end = MAX_STORED_BODY;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    body[..end].to_string()
+}
+
+// Хранилище истории, новые записи в начале. Записи не меняются после
+// добавления, поэтому общие: снимок для сохранения копирует только указатели
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub entries: Vec<Arc<HistoryEntry>>,
+}
+
+// Формат файла истории
+#[derive(Serialize, Deserialize)]
+struct Stored<T> {
+    #[serde(default = "Vec::new")]
+    entries: Vec<T>,
+}
+
+impl History {
+    // ~/.local/share/rastman/history.json (или аналог на других ОС)
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("rastman").join("history.json"))
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let stored: Stored<HistoryEntry> = serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(Self {
+            entries: stored
+                .entries
+                .into_iter()
+                .map(|mut entry| {
+                    entry.search = entry.search_key();
+                    Arc::new(entry)
+                })
+                .collect(),
+        })
+    }
+
+    // Снимок берём сейчас, сериализуем и пишем в фоне
+    pub fn save(&self) -> impl Future<Output = Result<(), String>> {
+        let snapshot = self.entries.clone();
+        let write = Self::path().map(|path| {
+            storage::write(path, move || {
+                let entries = snapshot.iter().map(Arc::as_ref).collect();
+                serde_json::to_string(&Stored::<&HistoryEntry> { entries })
+                    .map_err(|e| format!("Failed to serialize history: {}", e))
+            })
+        });
+
+        async move {
+            match write {
+                Some(write) => write.await,
+                None => Err("No data directory for history".to_string()),
+            }
+        }
+    }
+
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries.insert(0, Arc::new(entry));
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+// Состояние панели истории
+#[derive(Debug, Clone, Default)]
+pub struct Panel {
+    pub search: String,
+    pub error: Option<String>,
+    pub notice: Option<String>, // Какие скрытые значения не восстановились
+}
+
+// "5 min ago" и т.п.
+fn ago(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let seconds = now.saturating_sub(timestamp);
+
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} d ago", seconds / 86400),
+    }
+}
+
+// Панель истории с поиском
+pub fn view<'a>(history: &'a History, panel: &'a Panel) -> Element<'a, Message> {
+    let title = row![
+        text("History:").size(16).width(Fill),
+        button(text("Clear").size(12))
+            .on_press(Message::ClearHistory)
+            .padding(3)
+            .style(button::danger),
+    ]
+    .align_y(Center);
+
+    let search = text_input("Search history...", &panel.search)
+        .on_input(Message::HistorySearchChanged)
+        .padding(5)
+        .size(12);
+
+    let query = panel.search.to_lowercase();
+    let entries: Vec<Element<Message>> = history
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.matches(&query))
+        .map(|(index, entry)| {
+            let status: Element<Message> = match entry.status {
+                Some(status) => text(status.to_string())
+                    .size(12)
+                    .style(match status {
+                        200..=299 => text::success,
+                        400..=499 => text::warning,
+                        500..=599 => text::danger,
+                        _ => text::default,
+                    })
+                    .into(),
+                None => text("ERR").size(12).style(text::danger).into(),
+            };
+
+            row![
+                button(
+                    column![
+                        row![
+                            text(entry.method.name(&entry.custom_method)).size(12),
+                            status,
+                            text(format!("{} ms", entry.elapsed_ms))
+                                .size(11)
+                                .style(text::secondary),
+                        ]
+                        .spacing(5),
+                        text(&entry.final_url).size(11),
+                        text(ago(entry.timestamp)).size(10).style(text::secondary),
+                    ]
+                    .spacing(2)
+                )
+                .on_press(Message::RestoreHistoryEntry(index))
+                .padding(3)
+                .width(Fill)
+                .style(button::text),
+                button(text("↻").size(12))
+                    .on_press(Message::ResendHistoryEntry(index))
+                    .padding(3)
+                    .style(button::secondary),
+            ]
+            .spacing(3)
+            .align_y(Center)
+            .into()
+        })
+        .collect();
+
+    let list: Element<Message> = if entries.is_empty() {
+        text("No requests yet").style(text::secondary).into()
+    } else {
+        scrollable(column(entries).spacing(5)).height(300).into()
+    };
+
+    let error: Element<Message> = match (&panel.error, &panel.notice) {
+        (Some(error), _) => text(error).size(12).style(text::danger).into(),
+        (None, Some(notice)) => text(notice).size(12).style(text::warning).into(),
+        (None, None) => space().height(0).into(),
+    };
+
+    container(column![title, search, list, error].spacing(10).padding(10))
+        .width(260)
+        .style(container::bordered_box)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+
+    fn request() -> SavedRequest {
+        SavedRequest {
+            url: "https://api.test/login".to_string(),
+            auth: Auth {
+                kind: AuthKind::Bearer,
+                token: "live-token".to_string(),
+                ..Auth::default()
+            },
+            query_params: vec![QueryParam::new("api_key".into(), "k1".into())],
+            headers: vec![
+                HeaderParam::new("X-Session".into(), "s1".into()),
+                HeaderParam::new("Accept".into(), "*/*".into()),
+            ],
+            body: "{\n  \"user\": \"bob\",\n  \"password\": \"hunter2\",\n  \"pin\": {{pin}},\n  \"client_secret\": \"{{secret}}\"\n}".to_string(),
+            body_data: BodyData {
+                kind: BodyType::Json,
+                form_fields: vec![QueryParam::new("client_secret".into(), "cs".into())],
+                parts: vec![MultipartPart {
+                    name: "password".into(),
+                    value: "pw".into(),
+                    ..MultipartPart::default()
+                }],
+                ..BodyData::default()
+            },
+            ..SavedRequest::default()
+        }
+    }
+
+    fn entry(request: SavedRequest) -> HistoryEntry {
+        let final_url = "https://api.test/login?api_key=k1".to_string();
+        HistoryEntry::new(request, final_url, &Err("offline".to_string()), 1)
+    }
+
+    #[test]
+    fn secrets_are_redacted_everywhere() {
+        let entry = entry(request());
+        let stored = serde_json::to_string(&entry).unwrap();
+        for secret in ["live-token", "k1", "s1", "hunter2", "\"cs\"", "\"pw\""] {
+            assert!(!stored.contains(secret), "{} leaked: {}", secret, stored);
+        }
+        assert!(entry.auth_redacted);
+        assert_eq!(entry.final_url, "https://api.test/login?api_key=REDACTED");
+        assert_eq!(entry.headers[1].value, "*/*");
+        // Формат тела и ссылки на переменные не трогаем
+        assert_eq!(
+            entry.body,
+            "{\n  \"user\": \"bob\",\n  \"password\": \"REDACTED\",\n  \"pin\": {{pin}},\n  \"client_secret\": \"{{secret}}\"\n}"
+        );
+    }
+
+    #[test]
+    fn restore_takes_redacted_values_from_live_request() {
+        let entry = entry(request());
+        let (restored, missing) = entry.restore(&request());
+        assert!(missing.is_empty(), "{:?}", missing);
+        assert_eq!(restored.auth.token, "live-token");
+        assert_eq!(restored.query_params[0].value, "k1");
+        assert_eq!(restored.headers[0].value, "s1");
+        assert_eq!(restored.body, request().body);
+        assert_eq!(restored.body_data.form_fields[0].value, "cs");
+        assert_eq!(restored.body_data.parts[0].value, "pw");
+    }
+
+    #[test]
+    fn restore_reports_values_it_cannot_fill() {
+        let entry = entry(request());
+        let (restored, missing) = entry.restore(&SavedRequest::default());
+        assert_eq!(
+            missing,
+            ["Bearer token secrets", "api_key", "client_secret", "X-Session", "password"]
+        );
+        assert_eq!(redacted_names(&restored).len(), 4);
+
+        // Без секретов запрос восстанавливается как есть
+        let plain = SavedRequest {
+            headers: vec![HeaderParam::new("Accept".into(), "REDACTED".into())],
+            ..SavedRequest::default()
+        };
+        let (_, missing) = HistoryEntry::new(plain, String::new(), &Err(String::new()), 0)
+            .restore(&SavedRequest::default());
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn search_key_survives_save_and_load() {
+        let entry = entry(request());
+        assert!(entry.matches("api.test/login"));
+        assert!(entry.matches("\"user\": \"bob\""));
+        assert!(!entry.matches("hunter2"));
+
+        let entries = [Arc::new(entry)];
+        let stored = Stored::<&HistoryEntry> {
+            entries: entries.iter().map(Arc::as_ref).collect(),
+        };
+        let json = serde_json::to_string(&stored).unwrap();
+        assert!(!json.contains("\"search\""));
+
+        let loaded: Stored<HistoryEntry> = serde_json::from_str(&json).unwrap();
+        let mut loaded = loaded.entries.into_iter().next().unwrap();
+        assert!(!loaded.matches("login"));
+        loaded.search = loaded.search_key();
+        assert!(loaded.matches("login"));
+    }
+}
@@ -0,0 +1,481 @@
+// JSON-RPC 2.0: метод и params собираются в конверт {jsonrpc, method, params, id}
+// с растущим id, пакет - массив таких конвертов. В ответе result/error
+// каждого вызова показываем отдельно, коды ошибок расшифровываем
+use serde_json::{json, Map, Value};
+
+use iced::widget::{button, checkbox, column, container, row, space, text, text_input};
+use iced::{Center, Element, Fill, Font};
+
+use crate::body::BodyData;
+use crate::Message;
+
+// Один вызов до упаковки в конверт
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub method: String,
+    pub params: Option<Value>,
+    pub notification: bool, // Без id - сервер не отвечает
+}
+
+// params: пусто - без params, иначе JSON массив или объект
+pub fn parse_params(params: &str) -> Result<Option<Value>, String> {
+    if params.trim().is_empty() {
+        return Ok(None);
+    }
+    match serde_json::from_str::<Value>(params) {
+        Ok(value @ (Value::Array(_) | Value::Object(_))) => Ok(Some(value)),
+        Ok(_) => Err("Params must be a JSON array or object".to_string()),
+        Err(e) => Err(format!("Invalid params JSON: {}", e)),
+    }
+}
+
+// Пакет из редактора: [{"method": ..., "params": ..., "notification": true}, ...]
+fn parse_batch(text: &str) -> Result<Vec<Call>, String> {
+    let items = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) if !items.is_empty() => items,
+        Ok(_) => return Err("Batch must be a non-empty JSON array of calls".to_string()),
+        Err(e) => return Err(format!("Invalid batch JSON: {}", e)),
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let method = item
+                .get("method")
+                .and_then(Value::as_str)
+                .filter(|method| !method.is_empty())
+                .ok_or_else(|| format!("Batch call {} has no method", index + 1))?;
+            let params = match item.get("params") {
+                None | Some(Value::Null) => None,
+                Some(params @ (Value::Array(_) | Value::Object(_))) => Some(params.clone()),
+                Some(_) => {
+                    return Err(format!("Batch call {}: params must be an array or object", index + 1))
+                }
+            };
+            Ok(Call {
+                method: method.to_string(),
+                params,
+                notification: item.get("notification").and_then(Value::as_bool).unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+// Вызовы из полей режима: метод и params или пакет из редактора
+pub fn calls(method: &str, text: &str, notification: bool, batch: bool) -> Result<Vec<Call>, String> {
+    if batch {
+        return parse_batch(text);
+    }
+    if method.trim().is_empty() {
+        return Err("JSON-RPC method is empty".to_string());
+    }
+    Ok(vec![Call {
+        method: method.trim().to_string(),
+        params: parse_params(text)?,
+        notification,
+    }])
+}
+
+// Сколько id заберут вызовы
+pub fn id_count(calls: &[Call]) -> u64 {
+    calls.iter().filter(|call| !call.notification).count() as u64
+}
+
+// Тело запроса: id идут подряд от first_id, уведомления без id
+pub fn envelope(calls: &[Call], first_id: u64, batch: bool) -> String {
+    let mut id = first_id;
+    let mut envelopes = calls.iter().map(|call| {
+        let mut envelope = json!({ "jsonrpc": "2.0", "method": call.method });
+        if let Some(params) = &call.params {
+            envelope["params"] = params.clone();
+        }
+        if !call.notification {
+            envelope["id"] = id.into();
+            id += 1;
+        }
+        envelope
+    });
+    if batch {
+        Value::Array(envelopes.collect()).to_string()
+    } else {
+        envelopes.next().unwrap_or_default().to_string()
+    }
+}
+
+// Обратно из конверта (curl импорт): (method, params, notification, batch)
+pub fn from_envelope(body: &str) -> Option<(String, String, bool, bool)> {
+    fn call(value: &Value) -> Option<&Map<String, Value>> {
+        let object = value.as_object()?;
+        let is_call = object.get("jsonrpc")?.as_str()? == "2.0"
+            && object.get("method")?.is_string()
+            && object
+                .keys()
+                .all(|key| matches!(key.as_str(), "jsonrpc" | "method" | "params" | "id"));
+        is_call.then_some(object)
+    }
+
+    match serde_json::from_str(body).ok()? {
+        Value::Array(items) if !items.is_empty() => {
+            let items = items
+                .iter()
+                .map(|item| {
+                    let object = call(item)?;
+                    let mut item = json!({ "method": object["method"] });
+                    if let Some(params) = object.get("params") {
+                        item["params"] = params.clone();
+                    }
+                    if !object.contains_key("id") {
+                        item["notification"] = true.into();
+                    }
+                    Some(item)
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let batch = serde_json::to_string_pretty(&items).ok()?;
+            Some((String::new(), batch, false, true))
+        }
+        value => {
+            let object = call(&value)?;
+            let params = match object.get("params") {
+                Some(params) => serde_json::to_string_pretty(params).ok()?,
+                None => String::new(),
+            };
+            let method = object["method"].as_str()?.to_string();
+            Some((method, params, !object.contains_key("id"), false))
+        }
+    }
+}
+
+// ---------- Ответ ----------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub id: String,                        // Как пришёл: 7, "abc" или null
+    pub outcome: Result<String, RpcError>, // result отформатированным JSON
+}
+
+// Расшифровка кода ошибки по спецификации
+pub fn error_name(code: i64) -> &'static str {
+    match code {
+        -32700 => "Parse error",
+        -32600 => "Invalid Request",
+        -32601 => "Method not found",
+        -32602 => "Invalid params",
+        -32603 => "Internal error",
+        -32099..=-32000 => "Server error",
+        -32768..=-32000 => "Reserved error",
+        _ => "Application error",
+    }
+}
+
+fn reply(value: &Value) -> Option<Reply> {
+    let object = value.as_object()?;
+    if object.get("jsonrpc")?.as_str()? != "2.0" {
+        return None;
+    }
+    let id = match object.get("id") {
+        Some(Value::String(id)) => format!("\"{}\"", id),
+        Some(id) => id.to_string(),
+        None => "null".to_string(),
+    };
+    let outcome = match (object.get("result"), object.get("error")) {
+        (Some(result), None) => Ok(serde_json::to_string_pretty(result).ok()?),
+        (None, Some(error)) => Err(RpcError {
+            code: error.get("code")?.as_i64()?,
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            data: error.get("data").and_then(|data| serde_json::to_string_pretty(data).ok()),
+        }),
+        _ => return None,
+    };
+    Some(Reply { id, outcome })
+}
+
+// Ответ на одиночный вызов или пакет. None - это не JSON-RPC ответ
+pub fn parse_response(body: &str) -> Option<Vec<Reply>> {
+    match serde_json::from_str(body).ok()? {
+        Value::Array(items) if !items.is_empty() => items.iter().map(reply).collect(),
+        value => Some(vec![reply(&value)?]),
+    }
+}
+
+// ---------- Состояние и панель режима JSON-RPC ----------
+
+#[derive(Debug)]
+pub struct Panel {
+    pub next_id: u64, // id следующего вызова, растёт с каждой отправкой
+    pub sent: Vec<(u64, String)>, // id и методы последней отправки - подписи к ответам
+    pub replies: Vec<Reply>,
+}
+
+impl Default for Panel {
+    fn default() -> Self {
+        Self {
+            next_id: 1,
+            sent: Vec::new(),
+            replies: Vec::new(),
+        }
+    }
+}
+
+impl Panel {
+    // Занимаем id под отправленные вызовы
+    pub fn sent(&mut self, calls: &[Call]) {
+        self.sent = calls
+            .iter()
+            .filter(|call| !call.notification)
+            .zip(self.next_id..)
+            .map(|(call, id)| (id, call.method.clone()))
+            .collect();
+        self.next_id += id_count(calls);
+    }
+
+    fn method(&self, id: &str) -> Option<&str> {
+        self.sent
+            .iter()
+            .find(|(sent, _)| sent.to_string() == id)
+            .map(|(_, method)| method.as_str())
+    }
+}
+
+// Метод, уведомление, пакет и счётчик id над редактором params
+pub fn call_view<'a>(data: &'a BodyData, panel: &Panel) -> Element<'a, Message> {
+    let method: Element<Message> = if data.jsonrpc_batch {
+        text(r#"Batch: [{"method": "...", "params": [...], "notification": false}, ...]"#)
+            .size(12)
+            .font(Font::MONOSPACE)
+            .style(text::secondary)
+            .into()
+    } else {
+        row![
+            text("Method:").size(14),
+            text_input("e.g. eth_blockNumber", &data.jsonrpc_method)
+                .on_input(move |jsonrpc_method| {
+                    Message::BodyDataChanged(BodyData { jsonrpc_method, ..data.clone() })
+                })
+                .width(Fill)
+                .padding(5),
+            checkbox(data.jsonrpc_notification)
+                .label("Notification")
+                .on_toggle(move |jsonrpc_notification| {
+                    Message::BodyDataChanged(BodyData { jsonrpc_notification, ..data.clone() })
+                })
+                .text_size(12),
+        ]
+        .spacing(10)
+        .align_y(Center)
+        .into()
+    };
+
+    column![
+        row![
+            checkbox(data.jsonrpc_batch)
+                .label("Batch")
+                .on_toggle(move |jsonrpc_batch| {
+                    Message::BodyDataChanged(BodyData { jsonrpc_batch, ..data.clone() })
+                })
+                .text_size(12),
+            space().width(Fill),
+            text(format!("Next id: {}", panel.next_id)).size(12).style(text::secondary),
+            button(text("Reset").size(12))
+                .on_press(Message::JsonRpcResetId)
+                .padding([3, 8])
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(Center),
+        method,
+        text(if data.jsonrpc_batch { "Calls (JSON):" } else { "Params (JSON):" }).size(14),
+    ]
+    .spacing(8)
+    .into()
+}
+
+// result и error каждого вызова над телом ответа
+pub fn replies_view(panel: &Panel) -> Element<'_, Message> {
+    let items = panel.replies.iter().map(|reply| {
+        let title = match panel.method(&reply.id) {
+            Some(method) => format!("id {} · {}", reply.id, method),
+            None => format!("id {}", reply.id),
+        };
+        let outcome: Element<Message> = match &reply.outcome {
+            Ok(result) => text(result).size(12).font(Font::MONOSPACE).into(),
+            Err(error) => column![
+                text(format!("{} {}: {}", error.code, error_name(error.code), error.message))
+                    .size(13)
+                    .style(text::danger),
+                match &error.data {
+                    Some(data) => Element::from(
+                        text(data).size(12).font(Font::MONOSPACE).style(text::secondary),
+                    ),
+                    None => space().height(0).into(),
+                },
+            ]
+            .spacing(2)
+            .into(),
+        };
+        column![
+            text(title).size(12).style(if reply.outcome.is_ok() {
+                text::success
+            } else {
+                text::danger
+            }),
+            outcome,
+        ]
+        .spacing(4)
+        .into()
+    });
+
+    let errors = panel.replies.iter().filter(|reply| reply.outcome.is_err()).count();
+    container(
+        column![
+            text(format!(
+                "JSON-RPC replies ({}, errors: {}):",
+                panel.replies.len(),
+                errors
+            ))
+            .size(14),
+            column(items).spacing(10),
+        ]
+        .spacing(8),
+    )
+    .padding(10)
+    .width(Fill)
+    .style(container::bordered_box)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(method: &str, params: Option<Value>, notification: bool) -> Call {
+        Call { method: method.to_string(), params, notification }
+    }
+
+    #[test]
+    fn envelope_numbers_ids_and_skips_notifications() {
+        let calls = vec![
+            call("a", Some(json!([1])), false),
+            call("log", None, true),
+            call("b", Some(json!({"x": 1})), false),
+        ];
+        assert_eq!(id_count(&calls), 2);
+
+        let batch: Value = serde_json::from_str(&envelope(&calls, 7, true)).unwrap();
+        assert_eq!(
+            batch,
+            json!([
+                {"jsonrpc": "2.0", "method": "a", "params": [1], "id": 7},
+                {"jsonrpc": "2.0", "method": "log"},
+                {"jsonrpc": "2.0", "method": "b", "params": {"x": 1}, "id": 8},
+            ])
+        );
+
+        let single: Value = serde_json::from_str(&envelope(&calls[..1], 3, false)).unwrap();
+        assert_eq!(single, json!({"jsonrpc": "2.0", "method": "a", "params": [1], "id": 3}));
+    }
+
+    #[test]
+    fn panel_reserves_ids_for_calls_only() {
+        let mut panel = Panel::default();
+        panel.sent(&[call("a", None, false), call("log", None, true), call("b", None, false)]);
+        assert_eq!(panel.sent, [(1, "a".to_string()), (2, "b".to_string())]);
+        assert_eq!(panel.next_id, 3);
+        assert_eq!(panel.method("2"), Some("b"));
+        assert_eq!(panel.method("\"2\""), None);
+    }
+
+    #[test]
+    fn calls_validate_params_and_batch() {
+        assert_eq!(calls(" sum ", "[1, 2]", false, false).unwrap(), [call("sum", Some(json!([1, 2])), false)]);
+        assert!(calls("", "", false, false).is_err());
+        assert!(calls("sum", "5", false, false).is_err());
+
+        let batch = calls("", r#"[{"method": "a"}, {"method": "b", "params": {}, "notification": true}]"#, false, true);
+        assert_eq!(batch.unwrap(), [call("a", None, false), call("b", Some(json!({})), true)]);
+        assert!(calls("", "[]", false, true).is_err());
+        assert!(calls("", r#"[{"params": []}]"#, false, true).is_err());
+    }
+
+    #[test]
+    fn from_envelope_single_and_batch() {
+        let (method, params, notification, batch) =
+            from_envelope(r#"{"jsonrpc": "2.0", "method": "sum", "params": [1], "id": 1}"#).unwrap();
+        assert_eq!((method.as_str(), notification, batch), ("sum", false, false));
+        assert_eq!(serde_json::from_str::<Value>(&params).unwrap(), json!([1]));
+
+        let (method, params, notification, _) =
+            from_envelope(r#"{"jsonrpc": "2.0", "method": "ping"}"#).unwrap();
+        assert_eq!((method.as_str(), params.as_str(), notification), ("ping", "", true));
+
+        let (_, calls, _, batch) = from_envelope(
+            r#"[{"jsonrpc": "2.0", "method": "a", "id": 1}, {"jsonrpc": "2.0", "method": "log", "params": {"m": "x"}}]"#,
+        )
+        .unwrap();
+        assert!(batch);
+        assert_eq!(
+            serde_json::from_str::<Value>(&calls).unwrap(),
+            json!([{"method": "a"}, {"method": "log", "params": {"m": "x"}, "notification": true}])
+        );
+
+        // Не конверт: нет версии, лишние поля или это ответ
+        assert!(from_envelope(r#"{"method": "a"}"#).is_none());
+        assert!(from_envelope(r#"{"jsonrpc": "2.0", "method": "a", "extra": 1}"#).is_none());
+        assert!(from_envelope(r#"{"jsonrpc": "2.0", "result": 1, "id": 1}"#).is_none());
+        assert!(from_envelope("[]").is_none());
+    }
+
+    #[test]
+    fn parse_response_batch_with_string_and_null_ids() {
+        let replies = parse_response(
+            r#"[
+                {"jsonrpc": "2.0", "result": {"n": 1}, "id": 1},
+                {"jsonrpc": "2.0", "result": null, "id": "abc"},
+                {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+                {"jsonrpc": "2.0", "error": {"code": -32001, "message": "Busy", "data": {"retry": 5}}, "id": 2}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(replies[0].id, "1");
+        assert_eq!(replies[0].outcome, Ok("{\n  \"n\": 1\n}".to_string()));
+        assert_eq!(replies[1].id, "\"abc\"");
+        assert_eq!(replies[1].outcome, Ok("null".to_string()));
+        assert_eq!(replies[2].id, "null");
+        assert_eq!(
+            replies[2].outcome,
+            Err(RpcError { code: -32600, message: "Invalid Request".to_string(), data: None })
+        );
+        let error = replies[3].outcome.as_ref().unwrap_err();
+        assert_eq!((error.code, error_name(error.code)), (-32001, "Server error"));
+        assert_eq!(error.data.as_deref(), Some("{\n  \"retry\": 5\n}"));
+    }
+
+    #[test]
+    fn parse_response_rejects_non_rpc_bodies() {
+        assert!(parse_response(r#"{"result": 1, "id": 1}"#).is_none());
+        assert!(parse_response(r#"{"jsonrpc": "2.0", "id": 1}"#).is_none());
+        assert!(parse_response(r#"{"jsonrpc": "2.0", "result": 1, "error": {"code": 1}, "id": 1}"#).is_none());
+        assert!(parse_response(r#"[{"jsonrpc": "2.0", "result": 1, "id": 1}, {"ok": true}]"#).is_none());
+        assert!(parse_response("not json").is_none());
+    }
+
+    #[test]
+    fn error_names() {
+        assert_eq!(error_name(-32700), "Parse error");
+        assert_eq!(error_name(-32601), "Method not found");
+        assert_eq!(error_name(-32050), "Server error");
+        assert_eq!(error_name(-32500), "Reserved error");
+        assert_eq!(error_name(42), "Application error");
+    }
+}
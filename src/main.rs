@@ -1,4 +1,4 @@
-use iced::{keyboard, Task};
+use iced::{keyboard, window, Task};
 use iced::widget::{
     button, center_x, center_y, checkbox, column, container, pick_list,
     progress_bar, row, rule, scrollable, slider, space, text, text_input,
@@ -6,13 +6,48 @@ use iced::widget::{
 };
 use iced::{Center, Element, Fill, Shrink, Subscription, Theme, Font};
 use iced::highlighter; // Для подсветки синтаксиса
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
 use iced::widget::text_editor;
-
+use serde::{Deserialize, Serialize};
+
+mod auth;
+mod body;
+mod collection;
+mod curl;
+mod digest_auth;
+mod environment;
+mod graphql;
+mod grpc;
+mod history;
+mod jsonrpc;
+mod oauth;
+mod query;
+mod response;
+mod settings;
+mod signing;
+mod sigv4;
+mod sse;
+mod storage;
+mod syntax;
+mod websocket;
+
+use auth::Auth;
+use body::{BodyData, BodyType, RequestBody};
+use collection::{Collection, SavedRequest};
+use environment::Environments;
+use history::{History, HistoryEntry};
+use syntax::Syntax;
+use oauth::{OAuth2Config, Token, TokenCache};
+use query::ArrayStyle;
+use response::HttpResponse;
+use settings::ClientSettings;
 
 pub fn main() -> iced::Result {
     iced::application(Styling::default, Styling::update, Styling::view)
         .subscription(Styling::subscription)
         .theme(Styling::theme)
+        .exit_on_close_request(false)
         .run()
 }
 
@@ -48,7 +83,8 @@ const COMMON_HEADERS: &[&str] = &[
 ];
 
 // Перечисление HTTP методов
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[allow(clippy::upper_case_acronyms)] // Названия методов как в спецификации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 enum HttpMethod {
     #[default]
     GET,
@@ -56,6 +92,11 @@ enum HttpMethod {
     PUT,
     DELETE,
     PATCH,
+    HEAD,
+    OPTIONS,
+    TRACE,
+    CONNECT,
+    CUSTOM, // Произвольный метод (PROPFIND, PURGE ...), имя вводится отдельно
 }
 
 impl HttpMethod {
@@ -66,24 +107,108 @@ impl HttpMethod {
         HttpMethod::PUT,
         HttpMethod::DELETE,
         HttpMethod::PATCH,
+        HttpMethod::HEAD,
+        HttpMethod::OPTIONS,
+        HttpMethod::TRACE,
+        HttpMethod::CONNECT,
+        HttpMethod::CUSTOM,
     ];
+
+    // Методы, у которых обычно нет тела (его можно отправить принудительно)
+    fn has_body(self) -> bool {
+        !matches!(
+            self,
+            HttpMethod::GET | HttpMethod::HEAD | HttpMethod::TRACE | HttpMethod::CONNECT
+        )
+    }
+
+    // Имя метода для отправки, для CUSTOM - введённое пользователем
+    fn name(self, custom: &str) -> String {
+        match self {
+            HttpMethod::CUSTOM => custom.trim().to_string(),
+            method => method.to_string(),
+        }
+    }
+
+    // Метод для reqwest: стандартные и любые валидные токены
+    fn to_reqwest(self, custom: &str) -> Result<reqwest::Method, String> {
+        let name = self.name(custom);
+        if name.is_empty() {
+            return Err("Custom method is empty".to_string());
+        }
+        reqwest::Method::from_bytes(name.as_bytes())
+            .map_err(|_| format!("Invalid HTTP method: {}", name))
+    }
+
+    // Обратно из имени: неизвестные методы становятся CUSTOM
+    fn parse(name: &str) -> (HttpMethod, String) {
+        match HttpMethod::ALL
+            .iter()
+            .copied()
+            .find(|m| *m != HttpMethod::CUSTOM && m.to_string().eq_ignore_ascii_case(name))
+        {
+            Some(method) => (method, String::new()),
+            None => (HttpMethod::CUSTOM, name.to_string()),
+        }
+    }
+}
+
+// Тип соединения: обычный HTTP запрос, WebSocket, поток Server-Sent Events или gRPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum ConnectionType {
+    #[default]
+    Http,
+    WebSocket,
+    Sse,
+    Grpc,
+}
+
+impl ConnectionType {
+    const ALL: &'static [ConnectionType] = &[
+        ConnectionType::Http,
+        ConnectionType::WebSocket,
+        ConnectionType::Sse,
+        ConnectionType::Grpc,
+    ];
+
+    fn is_http(&self) -> bool {
+        *self == ConnectionType::Http
+    }
+
+    // Обычный HTTP запрос с методом и настройками клиента
+    fn sends_http(&self) -> bool {
+        matches!(self, ConnectionType::Http | ConnectionType::Sse)
+    }
+}
+
+impl std::fmt::Display for ConnectionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionType::Http => write!(f, "HTTP"),
+            ConnectionType::WebSocket => write!(f, "WebSocket"),
+            ConnectionType::Sse => write!(f, "SSE"),
+            ConnectionType::Grpc => write!(f, "gRPC"),
+        }
+    }
 }
 
 // Добавим структуру для Query параметра
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct QueryParam {
     key: String,
     value: String,
+    #[serde(default)]
+    bare: bool, // Ключ без "=" (?debug)
 }
 
 impl QueryParam {
     fn new(key: String, value: String) -> Self {
-        Self { key, value }
+        Self { key, value, bare: false }
     }
 }
 
 // Добавим структуру для Header
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct HeaderParam {
     key: String,
     value: String,
@@ -122,25 +247,68 @@ struct Styling {
     checkbox_value: bool,
     toggler_value: bool,
     http_method: HttpMethod, // HTTP метод
+    custom_method: String,   // Имя метода для CUSTOM
+    force_body: bool,        // Отправлять тело и для GET/HEAD/...
     url_input: String, // Поле для ввода URL
     // ↓ Добавленные поля ↓
     query_params: Vec<QueryParam>, // Список параметров
     new_query_key: String,         // Поле для нового ключа
     new_query_value: String,       // Поле для нового значения
+    array_style: ArrayStyle,       // Как кодировать повторяющиеся ключи
      // ↓ Добавленные поля для Headers ↓
     headers: Vec<HeaderParam>,
     new_header_key: String,
     new_header_value: String,
-    // ↓ Поля для JSON редактора ↓
-    json_theme: highlighter::Theme,
+    // ↓ Поля для редактора тела ↓
+    syntax_theme: highlighter::Theme,
     body_content: text_editor::Content,
-    json_valid: bool,
+    body_error: Option<String>, // Ошибка разбора по синтаксису тела
+    body_data: BodyData, // Тип тела и поля формы
+    graphql: graphql::Panel, // Переменные, схема и подсказки режима GraphQL
+    jsonrpc: jsonrpc::Panel, // Счётчик id и разобранные ответы JSON-RPC
+    connection_type: ConnectionType,
+    websocket: websocket::Panel, // Соединение и журнал кадров
+    sse: sse::Panel,             // Поток и таблица событий
+    grpc: grpc::Panel,           // Описания сервисов и ход вызова
     // ↓ Добавляем новые поля ↓
     is_loading: bool,               // Индикатор загрузки
-    response_status: Option<u16>,   // Статус ответа
-    response_body: String,          // Тело ответа
+    response_progress: Option<response::Progress>, // Чтение тела ответа
+    body_file_error: Option<String>,
+    spill_file: Option<std::path::PathBuf>, // Временный файл последнего большого ответа
+    request_handle: Option<iced::task::Handle>, // Для отмены текущего запроса
+    response: Option<HttpResponse>, // Ответ: статус, заголовки, тайминги
+    response_body: String,          // Тело ответа (отформатированное)
     response_error: Option<String>, // Ошибка если была
     header_suggestions: Vec<String>,    // Текущие подсказки
+    // ↓ Авторизация ↓
+    auth: Auth,
+    auth_reveal: bool, // Показывать секреты открытым текстом
+    oauth_tokens: TokenCache,   // Токены OAuth2 по окружениям
+    oauth_fetching: bool,       // Идёт получение токена
+    oauth_fetched: bool,        // Токен только что получен для отправляемого запроса
+    oauth_error: Option<String>,
+    // ↓ Коллекция сохранённых запросов ↓
+    collection: Collection,
+    sidebar: collection::Sidebar,
+    // ↓ История запросов ↓
+    history: History,
+    history_panel: history::Panel,
+    pending_request: Option<(SavedRequest, String)>, // Отправленный запрос и итоговый URL
+    request_started: Option<std::time::Instant>,     // Когда начали запрос
+    // ↓ Окружения и переменные ↓
+    environments: Environments,
+    environment_editor: environment::Editor,
+    environments_save: storage::Debounce,
+    collection_save: storage::Debounce,
+    // ↓ Импорт cURL ↓
+    curl_input: String,
+    curl_error: Option<String>,
+    // ↓ Настройки клиента ↓
+    client_settings: ClientSettings,          // Глобальные
+    request_settings: Option<ClientSettings>, // Свои у текущего запроса
+    settings_open: bool,
+    settings_error: Option<String>,
+    settings_save: storage::Debounce,
 }
 
 // 3. Реализуй Default вручную
@@ -153,25 +321,67 @@ impl Default for Styling {
             checkbox_value: false,
             toggler_value: false,
             http_method: HttpMethod::default(),
+            custom_method: String::new(),
+            force_body: false,
             url_input: String::new(),
             query_params: Vec::new(),
             new_query_key: String::new(),
             new_query_value: String::new(),
+            array_style: ArrayStyle::default(),
             headers: Vec::new(),
             new_header_key: String::new(),
             new_header_value: String::new(),
-            // Инициализируем поля для JSON редактора
-            json_theme: highlighter::Theme::SolarizedDark, // или другой вариант
+            // Инициализируем поля для редактора тела
+            syntax_theme: highlighter::Theme::SolarizedDark, // или другой вариант
             body_content: text_editor::Content::new(),
             // Пустое тело считаем валидным
-            json_valid: true,
+            body_error: None,
+            body_data: BodyData::default(),
+            graphql: graphql::Panel::default(),
+            jsonrpc: jsonrpc::Panel::default(),
+            connection_type: ConnectionType::default(),
+            websocket: websocket::Panel::default(),
+            sse: sse::Panel::default(),
+            grpc: grpc::Panel::default(),
             // ↓ Инициализируем поля для запроса ↓
             is_loading: false,
-            response_status: None,
+            response_progress: None,
+            body_file_error: None,
+            spill_file: None,
+            request_handle: None,
+            response: None,
             response_body: String::new(),
             response_error: None,
             header_suggestions: Vec::new(),
+            auth: Auth::default(),
+            auth_reveal: false,
+            oauth_tokens: TokenCache::default(),
+            oauth_fetching: false,
+            oauth_fetched: false,
+            oauth_error: None,
+            collection: Collection::default(),
+            sidebar: collection::Sidebar::default(),
+            history: History::default(),
+            history_panel: history::Panel::default(),
+            pending_request: None,
+            request_started: None,
+            environments: Environments::default(),
+            environment_editor: environment::Editor::default(),
+            environments_save: storage::Debounce::default(),
+            collection_save: storage::Debounce::default(),
+            curl_input: String::new(),
+            curl_error: None,
+            client_settings: ClientSettings::default(),
+            request_settings: None,
+            settings_open: false,
+            settings_error: None,
+            settings_save: storage::Debounce::default(),
         }
+        .with_collection()
+        .with_history()
+        .with_environments()
+        .with_settings()
+        .with_tokens()
     }
 }
 
@@ -186,7 +396,10 @@ enum Message {
     PreviousTheme,
     NextTheme,
     ClearTheme,
+    ConnectionTypeChanged(ConnectionType), // HTTP или WebSocket
     HttpMethodChanged(HttpMethod), // Изменение HTTP метода
+    CustomMethodChanged(String),   // Имя произвольного метода
+    ForceBodyToggled(bool),        // Тело для методов без тела
     UrlInputChanged(String), // Изменение URL
     // ↓ Добавленные сообщения для Query Parameters ↓
     NewQueryKeyChanged(String),       // Изменение поля нового ключа
@@ -195,6 +408,8 @@ enum Message {
     RemoveQueryParam(usize),          // Удалить параметр по индексу
     UpdateQueryParamKey(usize, String), // Обновить ключ параметра
     UpdateQueryParamValue(usize, String), // Обновить значение параметра
+    ToggleQueryParamBare(usize, bool),    // Ключ без "="
+    ArrayStyleChanged(ArrayStyle),        // Стиль кодирования массивов
     // ↓ Добавленные сообщения для Headers ↓
     NewHeaderKeyChanged(String),
     NewHeaderValueChanged(String),
@@ -204,12 +419,111 @@ enum Message {
     UpdateHeaderValue(usize, String),
     // ↓ Добавляем обработку действий редактора
     BodyActionPerformed(text_editor::Action),
+    BodyDataChanged(BodyData), // Тип тела, поля формы, части multipart
+    PickMultipartFile(usize),
+    MultipartFilePicked(usize, Option<String>),
+    PickBinaryFile,
+    BinaryFilePicked(Option<String>),
+    PrettifyBody,
+    MinifyBody,
+    // ↓ GraphQL ↓
+    GraphQlVariablesAction(text_editor::Action),
+    FetchGraphQlSchema,
+    GraphQlSchemaFetched(Result<graphql::Schema, String>),
+    ApplyGraphQlCompletion(String), // Допечатать выбранную подсказку
+    ToggleGraphQlDocs,
+    GraphQlDocsOpen(String), // Перейти к типу в справочнике
+    GraphQlDocsBack,
+    // ↓ JSON-RPC ↓
+    JsonRpcResetId,
+    // ↓ WebSocket ↓
+    WsConnect,
+    WsDisconnect,
+    WsEvent(websocket::Event), // Из подписки с соединением
+    WsSend,                    // Текст редактора - кадром
+    WsFrameKindChanged(websocket::FrameKind),
+    WsClearLog,
+    // ↓ Server-Sent Events ↓
+    SseConnect,
+    SseDisconnect,
+    SseEvent(sse::Event), // Из подписки с потоком
+    SseLastEventIdChanged(String),
+    SseAutoReconnectToggled(bool),
+    SseClear,
+    // ↓ gRPC ↓
+    GrpcPickProto,
+    GrpcProtoPicked(Option<String>),
+    GrpcReflect,
+    GrpcDescriptorsLoaded(Result<prost_reflect::DescriptorPool, String>, String), // И откуда они
+    GrpcServiceSelected(String),
+    GrpcMethodSelected(String),
+    GrpcTemplate, // Заготовка сообщения в редактор
+    GrpcEvent(grpc::Event),
     // ↓ Опционально: для смены темы подсветки
-    JsonThemeChanged(highlighter::Theme),
+    SyntaxThemeChanged(highlighter::Theme),
     // ↓ Добавляем ↓
     SendRequest,  // Отправка запроса
-    RequestCompleted(Result<(u16, String), String>), // ← По завершении запроса
+    CancelRequest, // Отмена запроса в процессе
+    RequestCompleted(Result<HttpResponse, String>), // ← По завершении запроса
+    RequestProgress(response::Progress),            // Сколько тела уже пришло
+    OpenResponseFile,                               // Большое тело из временного файла
     ApplyHeaderSuggestion(String),   // Применить подсказку (клик по ней)
+    // ↓ Авторизация ↓
+    AuthChanged(Box<Auth>), // Auth крупная, держим в куче
+    ToggleAuthSecretVisible,
+    FetchOAuthToken,
+    // Ключ кэша, результат и нужно ли после этого отправить запрос
+    OAuthTokenFetched(String, Result<Token, String>, bool),
+    ClearOAuthToken,
+    OAuthTokensSaved(Result<(), String>),
+    // ↓ Коллекция ↓
+    NewCollectionFolderChanged(String),
+    AddCollectionFolder,
+    DeleteCollectionFolder(usize),
+    SaveFolderSelected(String),
+    SaveNameChanged(String),
+    SaveCurrentRequest,
+    OpenSavedRequest(usize, usize),
+    StartRenameSavedRequest(usize, usize),
+    RenameSavedRequestInput(String),
+    ConfirmRenameSavedRequest,
+    DuplicateSavedRequest(usize, usize),
+    DeleteSavedRequest(usize, usize),
+    // ↓ История ↓
+    HistorySearchChanged(String),
+    RestoreHistoryEntry(usize),
+    ResendHistoryEntry(usize),
+    ClearHistory,
+    HistorySaved(Result<(), String>),
+    // ↓ Окружения ↓
+    EnvironmentSelected(String),
+    ToggleEnvironmentEditor,
+    NewEnvironmentNameChanged(String),
+    AddEnvironment,
+    DeleteEnvironment,
+    NewVariableKeyChanged(String),
+    NewVariableValueChanged(String),
+    AddVariable,
+    RemoveVariable(usize),
+    UpdateVariableKey(usize, String),
+    UpdateVariableValue(usize, String),
+    SaveEnvironments(u64), // Правки затихли - пишем файл
+    EnvironmentsSaved(Result<(), String>),
+    SaveCollection(u64),
+    CollectionSaved(Result<(), String>),
+    // ↓ cURL ↓
+    CurlInputChanged(String),
+    ImportCurl,
+    CurlImported(Result<Box<SavedRequest>, String>),
+    CopyAsCurl,
+    // ↓ Настройки клиента ↓
+    ToggleSettingsPanel,
+    ClientSettingsChanged(ClientSettings),
+    PerRequestSettingsToggled(bool),
+    SaveSettings(u64),
+    SettingsSaved(Result<(), String>),
+    // Закрытие окна: дописываем отложенные сохранения и выходим
+    CloseRequested,
 }
 
 impl Styling {
@@ -263,11 +577,25 @@ impl Styling {
                 self.theme = None;
                 Task::none()
             }
+            Message::ConnectionTypeChanged(connection_type) => {
+                self.set_connection_type(connection_type);
+                Task::none()
+            }
             Message::HttpMethodChanged(method) => {
                 self.http_method = method;
                 Task::none()
             }
+            Message::CustomMethodChanged(name) => {
+                self.custom_method = name;
+                Task::none()
+            }
+            Message::ForceBodyToggled(force) => {
+                self.force_body = force;
+                Task::none()
+            }
             Message::UrlInputChanged(url) => {
+                // Query строка из адреса сразу попадает в таблицу
+                self.query_params = query::merge(&self.query_params, query::split_url(&url).1);
                 self.url_input = url;
                 Task::none()
             }
@@ -288,6 +616,7 @@ impl Styling {
                     self.query_params.push(param);
                     self.new_query_key.clear();
                     self.new_query_value.clear();
+                    self.sync_url_from_params();
                 }
                 Task::none()
             }
@@ -295,18 +624,32 @@ impl Styling {
                 if index < self.query_params.len() {
                     self.query_params.remove(index);
                 }
+                self.sync_url_from_params();
                 Task::none()
             }
             Message::UpdateQueryParamKey(index, key) => {
                 if let Some(param) = self.query_params.get_mut(index) {
                     param.key = key;
                 }
+                self.sync_url_from_params();
                 Task::none()
             }
             Message::UpdateQueryParamValue(index, value) => {
                 if let Some(param) = self.query_params.get_mut(index) {
                     param.value = value;
                 }
+                self.sync_url_from_params();
+                Task::none()
+            }
+            Message::ToggleQueryParamBare(index, bare) => {
+                if let Some(param) = self.query_params.get_mut(index) {
+                    param.bare = bare;
+                }
+                self.sync_url_from_params();
+                Task::none()
+            }
+            Message::ArrayStyleChanged(style) => {
+                self.array_style = style;
                 Task::none()
             }
             Message::NewHeaderKeyChanged(key) => {
@@ -329,6 +672,66 @@ impl Styling {
                 self.header_suggestions.clear(); // ← ОЧИЩАЕМ подсказки
                 Task::none()
             }
+            Message::AuthChanged(auth) => {
+                self.auth = *auth;
+                Task::none()
+            }
+            Message::ToggleAuthSecretVisible => {
+                self.auth_reveal = !self.auth_reveal;
+                Task::none()
+            }
+            Message::FetchOAuthToken => {
+                // Явный запрос - всегда новый токен, без refresh из кэша
+                let (key, config) = self.oauth_config();
+                self.oauth_fetching = true;
+                self.oauth_error = None;
+                let settings = self.effective_settings().clone();
+                Task::perform(oauth::obtain(config, None, settings), move |result| {
+                    Message::OAuthTokenFetched(key.clone(), result, false)
+                })
+            }
+            Message::OAuthTokenFetched(key, result, send_after) => {
+                // Запрос, ради которого получали токен, уже отменён
+                if send_after && self.request_handle.take().is_none() {
+                    return Task::none();
+                }
+                self.oauth_fetching = false;
+
+                match result {
+                    Ok(token) => {
+                        self.oauth_tokens.tokens.insert(key, token);
+                        self.oauth_error = None;
+                        let save = Task::perform(self.oauth_tokens.save(), Message::OAuthTokensSaved);
+                        if send_after {
+                            // Отправляем с этим токеном, даже если он живёт меньше запаса
+                            self.is_loading = false;
+                            self.oauth_fetched = true;
+                            return Task::batch([save, self.update(Message::SendRequest)]);
+                        }
+                        return save;
+                    }
+                    Err(error) => {
+                        self.oauth_error = Some(error.clone());
+                        if send_after {
+                            self.is_loading = false;
+                            self.response = None;
+                            self.response_error = Some(error);
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::ClearOAuthToken => {
+                let (key, _) = self.oauth_config();
+                self.oauth_tokens.tokens.remove(&key);
+                Task::perform(self.oauth_tokens.save(), Message::OAuthTokensSaved)
+            }
+            Message::OAuthTokensSaved(result) => {
+                if let Err(error) = result {
+                    self.oauth_error = Some(error);
+                }
+                Task::none()
+            }
             Message::NewHeaderValueChanged(value) => {
                 self.new_header_value = value;
                 Task::none()
@@ -364,111 +767,896 @@ impl Styling {
                 Task::none()
             }
             Message::BodyActionPerformed(action) => {
+                let is_edit = action.is_edit();
                 self.body_content.perform(action);
 
-                // Проверяем валидность JSON
+                // Проверяем тело по его синтаксису
+                if is_edit {
+                    self.validate_body();
+                }
+                // Курсор мог сдвинуться - подсказки GraphQL по новой позиции
+                self.update_graphql_completions();
+                Task::none()
+            }
+            Message::BodyDataChanged(data) => {
+                self.body_data = data;
+                // Тип тела мог смениться - проверяем по новому синтаксису
+                self.validate_body();
+                self.update_graphql_completions();
+                Task::none()
+            }
+            Message::GraphQlVariablesAction(action) => {
+                let is_edit = action.is_edit();
+                self.graphql.variables.perform(action);
+                if is_edit {
+                    self.body_data.graphql_variables = self.graphql.variables.text();
+                }
+                Task::none()
+            }
+            Message::FetchGraphQlSchema => {
+                if self.url_input.trim().is_empty() {
+                    self.graphql.error = Some("URL is empty".to_string());
+                    return Task::none();
+                }
+
+                // Интроспекция уходит на тот же адрес с теми же заголовками и авторизацией
+                let (url, headers, _) = self.prepared_request();
+                let body = RequestBody::Text(graphql::envelope(graphql::INTROSPECTION_QUERY, "", ""));
+                let settings = self.effective_settings().clone();
+                let signing = self.auth.resolve(|value| self.environments.resolve(value));
+                self.graphql.fetching = true;
+                self.graphql.error = None;
+
+                Task::perform(
+                    async move {
+                        send_http_request(reqwest::Method::POST, url, headers, body, true, settings, signing)
+                            .await
+                    },
+                    |result| {
+                        Message::GraphQlSchemaFetched(result.and_then(|response| {
+                            graphql::parse_schema(response.status, &response.body)
+                        }))
+                    },
+                )
+            }
+            Message::GraphQlSchemaFetched(result) => {
+                self.graphql.fetching = false;
+                match result {
+                    Ok(schema) => {
+                        self.graphql.schema = Some(schema);
+                        self.graphql.error = None;
+                        self.update_graphql_completions();
+                    }
+                    Err(error) => self.graphql.error = Some(error),
+                }
+                Task::none()
+            }
+            Message::ApplyGraphQlCompletion(insert) => {
+                self.body_content.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                    std::sync::Arc::new(insert),
+                )));
+                self.validate_body();
+                self.update_graphql_completions();
+                Task::none()
+            }
+            Message::ToggleGraphQlDocs => {
+                self.graphql.docs = match self.graphql.docs {
+                    Some(_) => None,
+                    None => Some(Vec::new()),
+                };
+                Task::none()
+            }
+            Message::GraphQlDocsOpen(name) => {
+                self.graphql.docs.get_or_insert_default().push(name);
+                Task::none()
+            }
+            Message::JsonRpcResetId => {
+                self.jsonrpc.next_id = 1;
+                Task::none()
+            }
+            Message::GraphQlDocsBack => {
+                if let Some(path) = &mut self.graphql.docs {
+                    path.pop();
+                }
+                Task::none()
+            }
+            Message::WsConnect => {
+                // URL, заголовки и авторизация - как у HTTP запроса
+                let (url, headers, _) = self.prepared_request();
+                let headers = headers
+                    .into_iter()
+                    .filter(|header| !header.key.trim().is_empty())
+                    .map(|header| (header.key, header.value))
+                    .collect();
+                if let Err(error) = self.websocket.connect(url, headers) {
+                    self.websocket.push(websocket::Direction::Error, error);
+                }
+                Task::none()
+            }
+            Message::WsDisconnect => {
+                self.websocket.disconnect();
+                Task::none()
+            }
+            Message::WsEvent(event) => {
+                self.websocket.handle(event);
+                Task::none()
+            }
+            Message::WsSend => {
+                let text = self.environments.resolve(&self.body_content.text());
+                if let Err(error) = self.websocket.send(&text) {
+                    self.websocket.push(websocket::Direction::Error, error);
+                }
+                Task::none()
+            }
+            Message::WsFrameKindChanged(frame_kind) => {
+                self.websocket.frame_kind = frame_kind;
+                Task::none()
+            }
+            Message::WsClearLog => {
+                self.websocket.log.clear();
+                Task::none()
+            }
+            Message::SseConnect => {
+                self.connect_sse(None);
+                Task::none()
+            }
+            Message::SseDisconnect => {
+                self.sse.disconnect();
+                Task::none()
+            }
+            Message::SseEvent(event) => {
+                self.sse.handle(event);
+                Task::none()
+            }
+            Message::SseLastEventIdChanged(id) => {
+                self.sse.last_event_id = id;
+                Task::none()
+            }
+            Message::SseAutoReconnectToggled(enabled) => {
+                self.sse.auto_reconnect = enabled;
+                Task::none()
+            }
+            Message::SseClear => {
+                self.sse.events.clear();
+                Task::none()
+            }
+            Message::GrpcPickProto => Task::perform(grpc::pick_proto(), Message::GrpcProtoPicked),
+            Message::GrpcProtoPicked(path) => {
+                let Some(path) = path else {
+                    return Task::none();
+                };
+                self.grpc.loading = true;
+                Task::perform(grpc::load_proto(path.clone()), move |result| {
+                    Message::GrpcDescriptorsLoaded(result, path)
+                })
+            }
+            Message::GrpcReflect => {
+                if self.url_input.trim().is_empty() {
+                    self.grpc.error = Some("URL is empty".to_string());
+                    return Task::none();
+                }
+                // Reflection - на тот же адрес с теми же metadata и авторизацией
+                let (url, headers, _) = self.prepared_request();
+                let settings = self.effective_settings().clone();
+                self.grpc.loading = true;
+                Task::perform(grpc::reflect(url.clone(), headers, settings), move |result| {
+                    Message::GrpcDescriptorsLoaded(result, url)
+                })
+            }
+            Message::GrpcDescriptorsLoaded(result, source) => {
+                self.grpc.loading = false;
+                match result {
+                    Ok(pool) => self.grpc.set_pool(pool, source),
+                    Err(error) => self.grpc.error = Some(error),
+                }
+                Task::none()
+            }
+            Message::GrpcServiceSelected(service) => {
+                self.grpc.service = Some(service);
+                self.grpc.method = self.grpc.methods().into_iter().next();
+                Task::none()
+            }
+            Message::GrpcMethodSelected(method) => {
+                self.grpc.method = Some(method);
+                Task::none()
+            }
+            Message::GrpcTemplate => {
+                if let Ok(method) = self.grpc.method_descriptor() {
+                    self.body_content = text_editor::Content::with_text(&grpc::template(&method));
+                    self.validate_body();
+                }
+                Task::none()
+            }
+            Message::GrpcEvent(event) => {
+                if self.grpc.handle(event) {
+                    self.is_loading = false;
+                    self.request_handle = None;
+                }
+                Task::none()
+            }
+            Message::PrettifyBody | Message::MinifyBody => {
+                let syntax = self.editor_syntax();
                 let text = self.body_content.text();
-                self.json_valid = if text.trim().is_empty() {
-                    true // Пустое тело - валидно
+                let formatted = if matches!(message, Message::PrettifyBody) {
+                    syntax.prettify(&text)
                 } else {
-                    serde_json::from_str::<serde_json::Value>(&text).is_ok()
+                    syntax.minify(&text)
                 };
-
+                match formatted {
+                    Ok(formatted) => {
+                        self.body_content = text_editor::Content::with_text(&formatted);
+                        self.body_error = None;
+                    }
+                    Err(error) => self.body_error = Some(error),
+                }
                 Task::none()
             }
-            Message::JsonThemeChanged(theme) => {
-                self.json_theme = theme;
+            Message::PickMultipartFile(index) => {
+                Task::perform(body::pick_file(), move |path| {
+                    Message::MultipartFilePicked(index, path)
+                })
+            }
+            Message::MultipartFilePicked(index, path) => {
+                // Отмена диалога - ничего не меняем
+                if let (Some(part), Some(path)) = (self.body_data.parts.get_mut(index), path) {
+                    part.value = path;
+                }
+                Task::none()
+            }
+            Message::PickBinaryFile => Task::perform(body::pick_file(), Message::BinaryFilePicked),
+            Message::BinaryFilePicked(path) => {
+                if let Some(path) = path {
+                    self.body_data.file = path;
+                }
+                Task::none()
+            }
+            Message::SyntaxThemeChanged(theme) => {
+                self.syntax_theme = theme;
                 Task::none()
             }
             // 3. ОБНОВЛЯЕМ SendRequest для асинхронной работы
             Message::SendRequest => {
+                // Пока идёт запрос, второй не запускаем (его можно отменить)
+                if self.is_loading {
+                    return Task::none();
+                }
+                let oauth_fetched = std::mem::take(&mut self.oauth_fetched);
+
                 // Проверяем URL
                 if self.url_input.trim().is_empty() {
                     self.response_error = Some("URL is empty".to_string());
-                    self.response_status = None;
+                    self.response = None;
                     self.is_loading = false;
                     return Task::none();
                 }
 
+                // REDACTED из истории ушёл бы вместо секрета
+                let redacted = history::redacted_names(&self.current_request(String::new()));
+                if !redacted.is_empty() {
+                    self.response_error = Some(format!(
+                        "Fill in redacted values before sending: {}",
+                        redacted.join(", ")
+                    ));
+                    self.response = None;
+                    return Task::none();
+                }
+
+                if self.connection_type == ConnectionType::Grpc {
+                    return self.call_grpc();
+                }
+
+                let method = match self.http_method.to_reqwest(&self.custom_method) {
+                    Ok(method) => method,
+                    Err(error) => {
+                        self.response_error = Some(error);
+                        self.response = None;
+                        return Task::none();
+                    }
+                };
+
+                // Кривые переменные GraphQL не отправляем молча без них
+                if self.body_data.kind == BodyType::GraphQl {
+                    let variables = self.environments.resolve(&self.body_data.graphql_variables);
+                    if let Err(error) = graphql::parse_variables(&variables) {
+                        self.response_error = Some(error);
+                        self.response = None;
+                        return Task::none();
+                    }
+                }
+                // И вызовы JSON-RPC: без метода или с кривыми params конверт не собрать
+                let jsonrpc_calls = if self.body_data.kind == BodyType::JsonRpc {
+                    let calls = self
+                        .body_data
+                        .jsonrpc_calls(&self.body_content.text(), |value| self.environments.resolve(value));
+                    match calls {
+                        Ok(calls) => calls,
+                        Err(error) => {
+                            self.response_error = Some(error);
+                            self.response = None;
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    Vec::new()
+                };
+                let send_body = self.force_body || self.http_method.has_body();
+                let settings = self.effective_settings().clone();
+                let signing = self.auth.resolve(|value| self.environments.resolve(value));
+
                 // 1. Сразу показываем индикатор загрузки
                 self.is_loading = true;
                 self.response_error = None;
+                self.response_progress = None;
+
+                // OAuth2: нет свежего токена - сначала получаем/обновляем его,
+                // запрос уйдёт после OAuthTokenFetched
+                if self.auth.kind == auth::AuthKind::OAuth2
+                    && oauth::needs_token(self.oauth_token(), oauth_fetched)
+                {
+                    let (key, config) = self.oauth_config();
+                    let cached = self.oauth_token().cloned();
+                    self.oauth_fetching = true;
+                    self.oauth_error = None;
+
+                    let obtain = oauth::obtain(config, cached, settings.clone());
+                    let (task, handle) = Task::perform(obtain, move |result| {
+                        Message::OAuthTokenFetched(key.clone(), result, true)
+                    })
+                    .abortable();
+                    self.request_handle = Some(handle);
+                    return task;
+                }
 
-                // Клонируем данные для передачи в async задачу
-                let method = self.http_method;
-                let url = self.url_input.clone();
-                let query_params = self.query_params.clone();
-                let headers = self.headers.clone();
-                let body_text = self.body_content.text();
+                // Клонируем данные для передачи в async задачу,
+                // сразу подставляя переменные окружения и авторизацию
+                let (url, headers, body) = self.prepared_request();
+                if !jsonrpc_calls.is_empty() {
+                    // id заняты - следующая отправка получит новые
+                    self.jsonrpc.sent(&jsonrpc_calls);
+                }
 
-                // 2. Запускаем асинхронную задачу
-                Task::perform(
-                    async move {
-                        // Вызываем асинхронную функцию
-                        send_http_request(method, url, query_params, headers, body_text).await
+                // Запоминаем запрос для истории
+                self.pending_request = Some((self.current_request(String::new()), url.clone()));
+                self.request_started = Some(std::time::Instant::now());
+
+                // 2. Запускаем асинхронную задачу: по ходу чтения тела она шлёт прогресс,
+                // 3. а в конце - RequestCompleted
+                let (task, handle) = Task::stream(iced::stream::channel(
+                    100,
+                    async move |mut output: mpsc::Sender<Message>| {
+                        let mut progress = output.clone();
+                        let opened =
+                            open_response(method, url, headers, body, send_body, settings, signing).await;
+                        let result = match opened {
+                            Ok((response, started)) => {
+                                read_response(response, started, move |received| {
+                                    // Не успевает интерфейс - промежуточное значение пропускаем
+                                    let _ = progress.try_send(Message::RequestProgress(received));
+                                })
+                                .await
+                            }
+                            Err(error) => Err(error),
+                        };
+                        let _ = output.send(Message::RequestCompleted(result)).await;
                     },
-                    // 3. Когда задача завершится, Iced вызовет это
-                    Message::RequestCompleted
-                )
+                ))
+                .abortable();
+
+                // Запоминаем handle, чтобы запрос можно было отменить
+                self.request_handle = Some(handle);
+                task
+            }
+            Message::RequestProgress(progress) => {
+                if self.is_loading {
+                    self.response_progress = Some(progress);
+                }
+                Task::none()
+            }
+            Message::OpenResponseFile => {
+                if let Some(path) = self.response.as_ref().and_then(|response| response.body_file.as_ref()) {
+                    self.body_file_error = open::that_detached(path)
+                        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+                        .err();
+                }
+                Task::none()
+            }
+            Message::CancelRequest => {
+                if let Some(handle) = self.request_handle.take() {
+                    // Прерываем задачу - future с запросом будет сброшен
+                    handle.abort();
+                    self.oauth_fetching = false;
+                    // Статус отмены - только идущему gRPC вызову, не прошлому
+                    if self.connection_type == ConnectionType::Grpc && self.grpc.is_calling() {
+                        self.grpc.handle(grpc::Event::Finished(Err("Request cancelled".to_string())));
+                    }
+
+                    let error = "Request cancelled".to_string();
+                    let save = self.record_history(&Err(error.clone()));
+                    self.is_loading = false;
+                    self.response = None;
+                    self.response_body.clear();
+                    self.response_error = Some(error);
+                    return save;
+                }
+                Task::none()
             }
             // 4. ДОБАВЛЯЕМ обработчик для RequestCompleted
             Message::RequestCompleted(result) => {
+                // Отменённый запрос уже обработан
+                if self.request_handle.take().is_none() {
+                    return Task::none();
+                }
                 self.is_loading = false;
+                self.body_file_error = None;
+                let save = self.record_history(&result);
 
-                match result {
-                    Ok((status, body)) => {
-                        self.response_status = Some(status);
-
-                        // Десериализация в структуру
-                        let json_row = serde_json::from_str::<serde_json::Value>(&body);
-                        match json_row {
-                            Ok(json_row) => {
-                                // Сериализация обратно в красивую строку (pretty print)
-                                let json_body = serde_json::to_string_pretty(&json_row).unwrap();
-                                self.response_body = json_body;
-                            },
-                            Err(_e) => {
-                                // Не json или он кривой, выводим как есть
-                                self.response_body = body;
-                            }
-                        };
+                // Сервер ответил потоком событий - дальше его читает режим SSE
+                let event_stream = result
+                    .as_ref()
+                    .ok()
+                    .and_then(|response| response.event_stream.clone());
 
+                match result {
+                    Ok(response) => {
+                        // Временный файл прошлого большого ответа больше не нужен
+                        if self.spill_file != response.body_file {
+                            self.remove_spill_file();
+                            self.spill_file = response.body_file.clone();
+                        }
+                        // JSON уже отформатирован в read_response
+                        self.show_response_body(response.body.clone());
+                        self.response = Some(response);
                         self.response_error = None;
                     }
                     Err(error) => {
-                        self.response_status = None;
+                        self.response = None;
                         self.response_body.clear();
                         self.response_error = Some(error);
                     }
                 }
 
+                if let Some(stream) = event_stream {
+                    self.connection_type = ConnectionType::Sse;
+                    self.connect_sse(Some(stream));
+                }
+                save
+            }
+            Message::NewCollectionFolderChanged(name) => {
+                self.sidebar.new_folder_name = name;
+                Task::none()
+            }
+            Message::AddCollectionFolder => {
+                let name = self.sidebar.new_folder_name.trim().to_string();
+                if !name.is_empty() {
+                    self.collection.add_folder(&name);
+                    self.sidebar.save_folder = Some(name);
+                    self.sidebar.new_folder_name.clear();
+                    return self.save_collection();
+                }
+                Task::none()
+            }
+            Message::DeleteCollectionFolder(folder) => {
+                self.collection.remove_folder(folder);
+                self.sidebar.active = None;
+                self.sidebar.renaming = None;
+                self.save_collection()
+            }
+            Message::SaveFolderSelected(folder) => {
+                self.sidebar.save_folder = Some(folder);
+                Task::none()
+            }
+            Message::SaveNameChanged(name) => {
+                self.sidebar.save_name = name;
+                Task::none()
+            }
+            Message::SaveCurrentRequest => {
+                let name = self.sidebar.save_name.trim().to_string();
+                if name.is_empty() {
+                    self.sidebar.error = Some("Request name is empty".to_string());
+                    return Task::none();
+                }
+
+                // Без выбранной папки кладём в "Default"
+                let folder = self
+                    .sidebar
+                    .save_folder
+                    .get_or_insert_with(|| "Default".to_string())
+                    .clone();
+                // Секреты в коллекцию не кладём - файл коммитят в git
+                let mut request = self.current_request(name);
+                let auth = request.auth.without_secrets();
+                self.sidebar.notice = (auth != request.auth).then(|| {
+                    "Auth secrets are not saved to the collection, use {{variables}} from an environment"
+                        .to_string()
+                });
+                request.auth = auth;
+                self.sidebar.active = Some(self.collection.upsert(&folder, request));
+                self.save_collection()
+            }
+            Message::OpenSavedRequest(folder, index) => {
+                if let Some(request) = self.collection.get(folder, index).cloned() {
+                    self.sidebar.save_name = request.name.clone();
+                    self.sidebar.save_folder = Some(self.collection.folders[folder].name.clone());
+                    self.sidebar.active = Some((folder, index));
+                    self.load_request(request);
+                }
+                Task::none()
+            }
+            Message::StartRenameSavedRequest(folder, index) => {
+                if let Some(request) = self.collection.get(folder, index) {
+                    self.sidebar.renaming = Some((folder, index, request.name.clone()));
+                }
+                Task::none()
+            }
+            Message::RenameSavedRequestInput(name) => {
+                if let Some((_, _, current)) = &mut self.sidebar.renaming {
+                    *current = name;
+                }
+                Task::none()
+            }
+            Message::ConfirmRenameSavedRequest => {
+                if let Some((folder, index, name)) = self.sidebar.renaming.take() {
+                    let name = name.trim().to_string();
+                    if !name.is_empty() {
+                        self.collection.rename(folder, index, name);
+                        return self.save_collection();
+                    }
+                }
                 Task::none()
             }
+            Message::DuplicateSavedRequest(folder, index) => {
+                self.collection.duplicate(folder, index);
+                self.sidebar.active = None;
+                self.save_collection()
+            }
+            Message::DeleteSavedRequest(folder, index) => {
+                self.collection.delete(folder, index);
+                self.sidebar.active = None;
+                self.sidebar.renaming = None;
+                self.save_collection()
+            }
+            Message::HistorySearchChanged(search) => {
+                self.history_panel.search = search;
+                Task::none()
+            }
+            Message::RestoreHistoryEntry(index) => {
+                if let Some(entry) = self.history.entries.get(index).cloned() {
+                    self.sidebar.active = None;
+                    self.restore_history_entry(&entry);
+
+                    // Показываем и сохранённый ответ
+                    self.response = entry.to_response();
+                    self.jsonrpc.sent.clear();
+                    self.show_response_body(entry.response_body.clone());
+                    self.response_error = entry.error.clone();
+                }
+                Task::none()
+            }
+            Message::ResendHistoryEntry(index) => {
+                if let Some(entry) = self.history.entries.get(index).cloned() {
+                    self.sidebar.active = None;
+                    // Со скрытыми секретами не отправляем - только в редактор
+                    if self.restore_history_entry(&entry) {
+                        return self.update(Message::SendRequest);
+                    }
+                }
+                Task::none()
+            }
+            Message::ClearHistory => {
+                self.history.clear();
+                Task::perform(self.history.save(), Message::HistorySaved)
+            }
+            Message::HistorySaved(result) => {
+                self.history_panel.error = result.err();
+                Task::none()
+            }
+            Message::CurlInputChanged(command) => {
+                self.curl_input = command;
+                Task::none()
+            }
+            Message::ImportCurl => {
+                // Файлы из -d @file читаются в фоне
+                Task::perform(curl::parse(self.curl_input.clone()), |result| {
+                    Message::CurlImported(result.map(Box::new))
+                })
+            }
+            Message::CurlImported(result) => {
+                match result {
+                    Ok(request) => {
+                        self.sidebar.active = None;
+                        self.load_request(*request);
+                        self.curl_input.clear();
+                        self.curl_error = None;
+                    }
+                    Err(error) => self.curl_error = Some(error),
+                }
+                Task::none()
+            }
+            Message::CopyAsCurl => {
+                // Копируем запрос уже с подставленными переменными и авторизацией
+                let (url, headers, body) = self.prepared_request();
+
+                let send_body = self.force_body || self.http_method.has_body();
+                let signing = self.auth.resolve(|value| self.environments.resolve(value));
+
+                iced::clipboard::write(curl::to_curl(
+                    &self.http_method.name(&self.custom_method),
+                    &url,
+                    &headers,
+                    body,
+                    send_body,
+                    self.effective_settings(),
+                    &signing,
+                ))
+            }
+            Message::ToggleSettingsPanel => {
+                self.settings_open = !self.settings_open;
+                Task::none()
+            }
+            Message::ClientSettingsChanged(settings) => {
+                // Меняем свои настройки запроса, если они есть, иначе глобальные
+                match &mut self.request_settings {
+                    Some(request_settings) => *request_settings = settings,
+                    None => {
+                        self.client_settings = settings;
+                        return Task::perform(self.settings_save.changed(), Message::SaveSettings);
+                    }
+                }
+                Task::none()
+            }
+            Message::PerRequestSettingsToggled(per_request) => {
+                // Свои настройки начинаются с копии глобальных
+                self.request_settings = per_request.then(|| self.client_settings.clone());
+                Task::none()
+            }
+            Message::EnvironmentSelected(name) => {
+                self.environments.select(name);
+                self.save_environments()
+            }
+            Message::ToggleEnvironmentEditor => {
+                self.environment_editor.open = !self.environment_editor.open;
+                Task::none()
+            }
+            Message::NewEnvironmentNameChanged(name) => {
+                self.environment_editor.new_name = name;
+                Task::none()
+            }
+            Message::AddEnvironment => {
+                let name = self.environment_editor.new_name.trim().to_string();
+                if !name.is_empty() && name != environment::NO_ENVIRONMENT {
+                    self.environments.add(&name);
+                    self.environment_editor.new_name.clear();
+                    return self.save_environments();
+                }
+                Task::none()
+            }
+            Message::DeleteEnvironment => {
+                self.environments.remove_active();
+                self.save_environments()
+            }
+            Message::NewVariableKeyChanged(key) => {
+                self.environment_editor.new_key = key;
+                Task::none()
+            }
+            Message::NewVariableValueChanged(value) => {
+                self.environment_editor.new_value = value;
+                Task::none()
+            }
+            Message::AddVariable => {
+                let key = self.environment_editor.new_key.trim().to_string();
+                if !key.is_empty() {
+                    if let Some(env) = self.environments.active_mut() {
+                        env.variables.push(environment::Variable {
+                            key,
+                            value: self.environment_editor.new_value.trim().to_string(),
+                        });
+                        self.environment_editor.new_key.clear();
+                        self.environment_editor.new_value.clear();
+                        return self.save_environments();
+                    }
+                }
+                Task::none()
+            }
+            Message::RemoveVariable(index) => {
+                if let Some(env) = self.environments.active_mut() {
+                    if index < env.variables.len() {
+                        env.variables.remove(index);
+                    }
+                }
+                self.save_environments()
+            }
+            Message::UpdateVariableKey(index, key) => {
+                if let Some(variable) = self
+                    .environments
+                    .active_mut()
+                    .and_then(|env| env.variables.get_mut(index))
+                {
+                    variable.key = key;
+                }
+                self.save_environments()
+            }
+            Message::UpdateVariableValue(index, value) => {
+                if let Some(variable) = self
+                    .environments
+                    .active_mut()
+                    .and_then(|env| env.variables.get_mut(index))
+                {
+                    variable.value = value;
+                }
+                self.save_environments()
+            }
+            Message::SaveEnvironments(revision) => {
+                if !self.environments_save.is_due(revision) {
+                    return Task::none();
+                }
+                Task::perform(
+                    self.environments.save(),
+                    Message::EnvironmentsSaved,
+                )
+            }
+            Message::EnvironmentsSaved(result) => {
+                self.environment_editor.error = result.err();
+                Task::none()
+            }
+            Message::SaveCollection(revision) => {
+                if !self.collection_save.is_due(revision) {
+                    return Task::none();
+                }
+                Task::perform(
+                    self.collection.save(collection::COLLECTION_FILE),
+                    Message::CollectionSaved,
+                )
+            }
+            Message::CollectionSaved(result) => {
+                self.sidebar.error = result.err();
+                Task::none()
+            }
+            Message::SaveSettings(revision) => {
+                if !self.settings_save.is_due(revision) {
+                    return Task::none();
+                }
+                Task::perform(self.client_settings.save(), Message::SettingsSaved)
+            }
+            Message::SettingsSaved(result) => {
+                self.settings_error = result.err();
+                Task::none()
+            }
+            Message::CloseRequested => {
+                let mut saves = Vec::new();
+                if self.environments_save.flush() {
+                    saves.push(Task::perform(
+                        self.environments.save(),
+                        Message::EnvironmentsSaved,
+                    ));
+                }
+                if self.collection_save.flush() {
+                    saves.push(Task::perform(
+                        self.collection.save(collection::COLLECTION_FILE),
+                        Message::CollectionSaved,
+                    ));
+                }
+                if self.settings_save.flush() {
+                    saves.push(Task::perform(self.client_settings.save(), Message::SettingsSaved));
+                }
+                self.remove_spill_file();
+                Task::batch(saves).chain(iced::exit())
+            }
         }
     }
 
-    fn view(&self) -> Element<'_, Message> {
-        let choose_theme = column![
-            text("Theme:"),
-            pick_list(Theme::ALL, self.theme.as_ref(), Message::ThemeChanged)
-                .width(Fill)
-                .placeholder("System"),
-        ]
-            .spacing(10);
+    fn view(&self) -> Element<'_, Message> {
+        let choose_theme = column![
+            text("Theme:"),
+            pick_list(Theme::ALL, self.theme.as_ref(), Message::ThemeChanged)
+                .width(Fill)
+                .placeholder("System"),
+        ]
+            .spacing(10);
+
+        // ↓ Переключатель окружения ↓
+        let choose_environment = column![
+            text("Environment:"),
+            row![
+                pick_list(
+                    self.environments.names(),
+                    Some(
+                        self.environments
+                            .active
+                            .clone()
+                            .unwrap_or_else(|| environment::NO_ENVIRONMENT.to_string())
+                    ),
+                    Message::EnvironmentSelected
+                )
+                .width(Fill),
+                button(text("⚙").size(14))
+                    .on_press(Message::ToggleEnvironmentEditor)
+                    .padding(8)
+                    .style(if self.environment_editor.open {
+                        button::primary
+                    } else {
+                        button::secondary
+                    }),
+            ]
+            .spacing(5)
+            .align_y(Center),
+        ]
+            .spacing(10);
+
+        let top_bar = row![choose_theme, choose_environment].spacing(20);
+
+        let environment_editor: Element<Message> = if self.environment_editor.open {
+            environment::view(&self.environments, &self.environment_editor)
+        } else {
+            Element::from(space().height(0))
+        };
+
+        // ↓ Добавленный выбор HTTP метода ↓
+        let method_pick = pick_list(
+            HttpMethod::ALL,
+            Some(&self.http_method),
+            Message::HttpMethodChanged
+        )
+            .width(Fill)
+            .placeholder("Select method");
+
+        // Для CUSTOM рядом поле для имени метода
+        let method_row: Element<Message> = if self.http_method == HttpMethod::CUSTOM {
+            row![
+                method_pick,
+                text_input("PROPFIND, PURGE...", &self.custom_method)
+                    .on_input(Message::CustomMethodChanged)
+                    .width(Fill)
+                    .padding(5),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into()
+        } else {
+            method_pick.into()
+        };
+
+        // Рядом с методом - тип соединения; у WebSocket и gRPC метода и настроек клиента нет,
+        // SSE отправляет обычный HTTP запрос
+        let connection_pick = pick_list(
+            ConnectionType::ALL,
+            Some(self.connection_type),
+            Message::ConnectionTypeChanged
+        )
+            .width(130);
 
-        // ↓ Добавленный выбор HTTP метода ↓
         let choose_http_method = column![
-            text("HTTP Method:"),
-            pick_list(
-                HttpMethod::ALL,
-                Some(&self.http_method),
-                Message::HttpMethodChanged
-            )
-                .width(Fill)
-                .placeholder("Select method"),
+            text(if self.connection_type.sends_http() { "HTTP Method:" } else { "Connection:" }),
+            if self.connection_type.sends_http() {
+                row![
+                    connection_pick,
+                    method_row,
+                    button(text("⚙ Client").size(14))
+                        .on_press(Message::ToggleSettingsPanel)
+                        .padding(8)
+                        .style(if self.settings_open {
+                            button::primary
+                        } else {
+                            button::secondary
+                        }),
+                ]
+            } else {
+                row![connection_pick]
+            }
+            .spacing(10)
+            .align_y(Center),
         ]
             .spacing(10);
 
+        let settings_panel: Element<Message> = if self.settings_open {
+            settings::view(
+                self.effective_settings(),
+                self.request_settings.is_some(),
+                self.settings_error.as_ref(),
+            )
+        } else {
+            Element::from(space().height(0))
+        };
+
         // ↓ Добавленное текстовое поле для URL ↓
         let url_input = text_input("Enter API URL...", &self.url_input)
             .on_input(Message::UrlInputChanged)
@@ -476,9 +1664,59 @@ impl Styling {
             .size(16) // Чуть меньше шрифт для URL
             .width(Fill); // Заполняет всю доступную ширину
 
+        // Вставка команды curl и копирование запроса как curl
+        let curl_row = {
+            let import = row![
+                text_input("Paste a curl command...", &self.curl_input)
+                    .on_input(Message::CurlInputChanged)
+                    .on_paste(Message::CurlInputChanged)
+                    .on_submit(Message::ImportCurl)
+                    .padding(5)
+                    .size(12)
+                    .width(Fill),
+                button(text("Import cURL").size(12))
+                    .on_press(Message::ImportCurl)
+                    .padding(5)
+                    .style(button::secondary),
+                button(text("📋 Copy as cURL").size(12))
+                    .on_press(Message::CopyAsCurl)
+                    .padding(5)
+                    .style(button::secondary),
+            ]
+            .spacing(8)
+            .align_y(Center);
+
+            match &self.curl_error {
+                Some(error) => column![import, text(error).size(12).style(text::danger)]
+                    .spacing(5)
+                    .into(),
+                None => Element::from(import),
+            }
+        };
+
+        // Превью URL после подстановки переменных
+        let url_preview: Element<Message> = if self.environments.active().is_some()
+            && !self.url_input.trim().is_empty()
+        {
+            text(format!("→ {}", self.resolved_url()))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(text::secondary)
+                .into()
+        } else {
+            Element::from(space().height(0))
+        };
+
         // Функция для Query Parameters таблицы
         let query_params_table = {
-            let title = text("Query Parameters:").size(16);
+            let title = row![
+                text("Query Parameters:").size(16).width(Fill),
+                pick_list(ArrayStyle::ALL, Some(self.array_style), Message::ArrayStyleChanged)
+                    .text_size(12)
+                    .padding(3),
+            ]
+            .spacing(8)
+            .align_y(Center);
             
             let items_table: Element<Message> = if self.query_params.is_empty() {
                 container(text("No query parameters added yet").style(text::secondary))
@@ -496,6 +1734,9 @@ impl Styling {
                             .on_input(move |value| Message::UpdateQueryParamValue(index, value))
                             .width(140)
                             .padding(5),
+                        checkbox(param.bare)
+                            .label("bare")
+                            .on_toggle(move |bare| Message::ToggleQueryParamBare(index, bare)),
                         button(text("🗑️").size(14))
                             .on_press(Message::RemoveQueryParam(index))
                             .padding(5)
@@ -634,14 +1875,24 @@ impl Styling {
                 .style(container::bordered_box)
         };
 
-        // 4. Собираем таблицы рядом
-        let params_tables = row![
-            query_params_table,
-            space().width(20),
-            headers_table,
+        // 4. Собираем таблицы рядом, под ними авторизация
+        let params_tables = column![
+            row![
+                query_params_table,
+                space().width(20),
+                headers_table,
+            ]
+            .spacing(10)
+            .align_y(Center),
+            auth::view(
+                &self.auth,
+                self.auth_reveal,
+                self.oauth_token(),
+                self.oauth_fetching,
+                self.oauth_error.as_ref(),
+            ),
         ]
-        .spacing(10)
-        .align_y(Center);   
+        .spacing(10);   
 
         let text_input = text_input("Type something...", &self.input_value)
             .on_input(Message::InputChanged)
@@ -682,51 +1933,171 @@ impl Styling {
 
         // Создадим секцию Body с text_editor
         let body_section = {
-            let title = row![
-                text("Body (JSON):").size(16),
-                space().width(10),
-                if self.json_valid {
-                    text("✅ Valid JSON").size(12).style(text::success)
-                } else {
-                    text("❌ Invalid JSON").size(12).style(text::danger)
-                }
-            ]
-                .align_y(Center);
-            
-            // Опционально: выбор темы подсветки
-            let theme_selector = row![
-                text("Syntax theme:").size(14),
-                pick_list(
-                    highlighter::Theme::ALL,
-                    Some(self.json_theme),
-                    Message::JsonThemeChanged
-                )
-                .width(200)
-                .padding(5)
-            ]
-            .spacing(10)
-            .align_y(Center);
+            let syntax = self.editor_syntax();
+            let body_valid = self.body_error.is_none() || syntax.is_plain();
+
+            // WebSocket: вместо типа тела - тип кадра и отправка, gRPC - выбор метода
+            let title: Element<Message> = if self.connection_type == ConnectionType::WebSocket {
+                websocket::composer_view(&self.websocket)
+            } else if self.connection_type == ConnectionType::Grpc {
+                grpc::method_view(&self.grpc)
+            } else {
+                row![
+                    text("Body:").size(16),
+                    space().width(10),
+                    pick_list(BodyType::ALL, Some(self.body_data.kind), |kind| {
+                        Message::BodyDataChanged(BodyData { kind, ..self.body_data.clone() })
+                    })
+                    .text_size(12)
+                    .padding(3),
+                    space().width(10),
+                    match &self.body_error {
+                        _ if syntax.is_plain() => text(""),
+                        None => text(format!("✅ Valid {}", syntax)).size(12).style(text::success),
+                        Some(error) => text(format!("❌ Invalid {}: {}", syntax, error))
+                            .size(12)
+                            .style(text::danger),
+                    },
+                    space().width(Fill),
+                    // Для GET/HEAD/... тело уходит только по явному желанию
+                    if self.http_method.has_body() {
+                        Element::from(space().width(0))
+                    } else {
+                        checkbox(self.force_body)
+                            .label(format!("Send body with {}", self.http_method))
+                            .on_toggle(Message::ForceBodyToggled)
+                            .text_size(12)
+                            .into()
+                    },
+                ]
+                .align_y(Center)
+                .into()
+            };
             
-            // Редактор JSON с подсветкой
-            let json_editor = text_editor(&self.body_content)
+            // Опционально: выбор темы подсветки. GraphQL красится цветами темы приложения,
+            // на этом месте у него загрузка схемы
+            let theme_selector: Element<Message> = if syntax == Syntax::GraphQl {
+                graphql::schema_view(&self.graphql)
+            } else {
+                row![
+                    text("Syntax theme:").size(14),
+                    pick_list(
+                        highlighter::Theme::ALL,
+                        Some(self.syntax_theme),
+                        Message::SyntaxThemeChanged
+                    )
+                    .width(200)
+                    .padding(5)
+                ]
+                .spacing(10)
+                .align_y(Center)
+                .into()
+            };
+
+            // Форматирование для всего, кроме простого текста
+            let format_buttons: Element<Message> = if syntax.is_plain() {
+                space().width(0).into()
+            } else {
+                let format_button = |label, message| {
+                    button(text(label).size(12))
+                        .on_press(message)
+                        .padding([3, 8])
+                        .style(button::secondary)
+                };
+                row![
+                    format_button("Prettify", Message::PrettifyBody),
+                    format_button("Minify", Message::MinifyBody),
+                ]
+                .spacing(5)
+                .into()
+            };
+
+            let editor_toolbar = row![theme_selector, space().width(Fill), format_buttons]
+                .align_y(Center);
+
+            // Редактор с подсветкой по типу тела
+            let editor = text_editor(&self.body_content)
                 .height(150)
                 .on_action(Message::BodyActionPerformed)
-                .highlight("json", self.json_theme) // Подсветка JSON
                 .wrapping(text::Wrapping::Word);
-            
+            let editor: Element<Message> = if syntax == Syntax::GraphQl {
+                editor
+                    .highlight_with::<syntax::GraphQlHighlighter>((), syntax::graphql_format)
+                    .into()
+            } else {
+                editor.highlight(syntax.token(), self.syntax_theme).into()
+            };
+
+            let content: Element<Message> = if !self.connection_type.sends_http() {
+                column![editor_toolbar, space().height(5), editor].spacing(5).into()
+            } else {
+                match self.body_data.kind {
+                    BodyType::Json => column![
+                        editor_toolbar,
+                        space().height(5),
+                        editor,
+                    ]
+                    .spacing(5)
+                    .into(),
+                    BodyType::Raw => column![
+                        body::raw_type_view(&self.body_data),
+                        editor_toolbar,
+                        space().height(5),
+                        editor,
+                    ]
+                    .spacing(5)
+                    .into(),
+                    BodyType::JsonRpc => column![
+                        jsonrpc::call_view(&self.body_data, &self.jsonrpc),
+                        editor_toolbar,
+                        space().height(5),
+                        editor,
+                    ]
+                    .spacing(5)
+                    .into(),
+                    BodyType::GraphQl => {
+                        let variables_error = graphql::parse_variables(&self.body_data.graphql_variables).err();
+                        column![
+                            editor_toolbar,
+                            space().height(5),
+                            editor,
+                            graphql::completions_view(&self.graphql.completions),
+                            graphql::operation_view(&self.body_data, &self.body_content.text()),
+                            text("Variables (JSON):").size(14),
+                            text_editor(&self.graphql.variables)
+                                .height(80)
+                                .on_action(Message::GraphQlVariablesAction)
+                                .highlight("json", self.syntax_theme)
+                                .wrapping(text::Wrapping::Word),
+                            match variables_error {
+                                Some(error) => Element::from(text(error).size(12).style(text::danger)),
+                                None => space().height(0).into(),
+                            },
+                            match (&self.graphql.schema, &self.graphql.docs) {
+                                (Some(schema), Some(path)) => graphql::docs_view(schema, path),
+                                _ => space().height(0).into(),
+                            },
+                        ]
+                        .spacing(5)
+                        .into()
+                }
+                BodyType::FormUrlEncoded => body::form_view(&self.body_data),
+                BodyType::Multipart => body::multipart_view(&self.body_data),
+                BodyType::Binary => body::binary_view(&self.body_data),
+                }
+            };
+
             container(column![
                 title,
                 space().height(5),
-                theme_selector, // можно убрать, если не нужен
-                space().height(5),
-                json_editor,
+                content,
             ]
             .spacing(5)
             .padding(10))
-            .style(if self.json_valid {
+            .style(if body_valid {
                 container::bordered_box // обычная рамка
             } else {
-                // Красная рамка для невалидного JSON
+                // Красная рамка для невалидного тела
                 |theme: &Theme| container::Style {
                     border: iced::border::Border {
                         color: theme.palette().danger,
@@ -768,19 +2139,96 @@ impl Styling {
                 .width(Fill)
         };
 
+        // Во время запроса рядом кнопка отмены
+        let connect = match self.connection_type {
+            ConnectionType::Http | ConnectionType::Grpc => None,
+            ConnectionType::WebSocket => {
+                Some((self.websocket.session.is_some(), Message::WsDisconnect, Message::WsConnect))
+            }
+            ConnectionType::Sse => {
+                Some((self.sse.session.is_some(), Message::SseDisconnect, Message::SseConnect))
+            }
+        };
+        let send_row: Element<Message> = if let Some((connected, disconnect, connect)) = connect {
+            // WebSocket и SSE: подключение вместо отправки
+            let (label, message, style): (_, _, fn(&Theme, button::Status) -> button::Style) =
+                if connected {
+                    ("Disconnect", disconnect, button::danger)
+                } else {
+                    ("Connect", connect, button::primary)
+                };
+            button(row![text("🔌").size(20), space().width(10), text(label).size(16)].align_y(Center))
+                .on_press(message)
+                .style(style)
+                .padding(15)
+                .width(Fill)
+                .into()
+        } else if self.is_loading {
+            row![
+                send_button,
+                button(
+                    row![
+                text("🛑").size(20),
+                space().width(10),
+                text("Cancel").size(16),
+            ]
+                        .align_y(Center)
+                )
+                    .on_press(Message::CancelRequest)
+                    .style(button::danger)
+                    .padding(15),
+            ]
+            .spacing(10)
+            .into()
+        } else {
+            send_button.into()
+        };
+
         // Секция ответа
         let response_section = {
-            let title = text("Response:").size(16);
+            let title = text(match self.connection_type {
+                ConnectionType::Http | ConnectionType::Grpc => "Response:",
+                ConnectionType::WebSocket => "Messages:",
+                ConnectionType::Sse => "Events:",
+            })
+            .size(16);
 
             // Явно указываем тип Element<Message>
-            let content: Element<Message> = if self.is_loading {
+            let content: Element<Message> = if self.connection_type == ConnectionType::WebSocket {
+                websocket::log_view(&self.websocket)
+            } else if self.connection_type == ConnectionType::Sse {
+                sse::events_view(&self.sse)
+            } else if self.connection_type == ConnectionType::Grpc {
+                grpc::response_view(&self.grpc)
+            } else if self.is_loading {
                 // Показываем индикатор загрузки
                 Element::from(
                     container(
                         column![
                     text("Request in progress...").style(text::secondary),
                     space().height(10),
-                    progress_bar(0.0..=100.0, 50.0),
+                    // Полоса - только когда известен Content-Length
+                    match self.response_progress {
+                        None => Element::from(text("Waiting for response...").size(12)),
+                        Some(response::Progress { received, total: Some(total) }) if total > 0 => column![
+                            progress_bar(0.0..=total as f32, received as f32).length(300),
+                            text(format!(
+                                "{} of {}",
+                                response::format_size(received as usize),
+                                response::format_size(total as usize)
+                            ))
+                            .size(12),
+                        ]
+                        .spacing(5)
+                        .align_x(Center)
+                        .into(),
+                        Some(progress) => text(format!(
+                            "{} received",
+                            response::format_size(progress.received as usize)
+                        ))
+                        .size(12)
+                        .into(),
+                    },
                 ]
                             .align_x(Center)
                     )
@@ -801,8 +2249,9 @@ impl Styling {
                         .padding(15)
                         .style(container::bordered_box)
                 )
-            } else if let Some(status) = self.response_status {
+            } else if let Some(response) = &self.response {
                 // Показываем успешный ответ
+                let status = response.status;
                 let status_style = match status {
                     200..=299 => text::success,
                     400..=499 => text::warning,
@@ -822,11 +2271,41 @@ impl Styling {
                             .size(14),
                     ]
                     .align_y(Center),
-                    space().height(10),
-                    text("Response Body:").size(14),
+                    // Версия, тайминги и размер
+                    row![
+                        text(&response.version).size(12).style(text::secondary),
+                        text(format!("Time: {}", response::format_duration(response.elapsed)))
+                            .size(12),
+                        text(format!("TTFB: {}", response::format_duration(response.ttfb)))
+                            .size(12),
+                        text(format!("Size: {}", response::format_size(response.size)))
+                            .size(12),
+                    ]
+                    .spacing(20),
+                    text(format!("Response Headers ({}):", response.headers.len())).size(14),
+                    response::headers_table(response),
+                    // GraphQL errors - отдельным блоком над data
+                    if self.graphql.response_errors.is_empty() {
+                        Element::from(space().height(0))
+                    } else {
+                        graphql::errors_view(&self.graphql.response_errors)
+                    },
+                    // JSON-RPC: result/error каждого вызова
+                    if self.jsonrpc.replies.is_empty() {
+                        Element::from(space().height(0))
+                    } else {
+                        jsonrpc::replies_view(&self.jsonrpc)
+                    },
+                    text(if self.graphql.response_errors.is_empty() {
+                        "Response Body:"
+                    } else {
+                        "Data:"
+                    })
+                    .size(14),
+                    response::clipped_view(response, &self.response_body, &self.body_file_error),
                     container(
                         scrollable(
-                            text(&self.response_body)
+                            text(response::display_part(&self.response_body))
                                 .size(12)
                                 .font(Font::MONOSPACE)
                         )
@@ -906,12 +2385,16 @@ impl Styling {
         };
 
         let content = column![
-            choose_theme,
+            top_bar,            // ← Тема и окружение
+            environment_editor, // ← Переменные окружения
             choose_http_method, // ← Выбор метода
+            settings_panel,     // ← Таймауты, редиректы, версия HTTP
             url_input, // ← Строка адреса
+            url_preview, // ← Итоговый URL
+            curl_row, // ← Импорт/экспорт cURL
             params_tables, // ← Таблички параметров
             body_section, // ← Редактор Body
-            send_button,      // ← Добавляем кнопку
+            send_row,         // ← Кнопка отправки (и отмены)
             response_section, // ← Добавляем ответ
             rule::horizontal(1),
             text_input,
@@ -933,13 +2416,36 @@ impl Styling {
             .padding(20)
             .max_width(810);
 
-        center_y(scrollable(center_x(content)).spacing(10))
-            .padding(10)
-            .into()
+        // Боковая панель коллекции и истории слева от основного содержимого
+        let sidebar = scrollable(
+            column![
+                collection::view(&self.collection, &self.sidebar),
+                history::view(&self.history, &self.history_panel),
+            ]
+            .spacing(10),
+        );
+
+        row![
+            container(sidebar).padding(10),
+            center_y(scrollable(center_x(content)).spacing(10)).padding(10),
+        ]
+        .into()
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        keyboard::listen().filter_map(|event| {
+        // Пока есть сессия WebSocket, подписка держит соединение открытым
+        let websocket = match &self.websocket.session {
+            Some(session) => {
+                Subscription::run_with(session.clone(), websocket::connect).map(Message::WsEvent)
+            }
+            None => Subscription::none(),
+        };
+        let sse = match &self.sse.session {
+            Some(session) => Subscription::run_with(session.clone(), sse::connect).map(Message::SseEvent),
+            None => Subscription::none(),
+        };
+
+        let keyboard = keyboard::listen().filter_map(|event| {
             let keyboard::Event::KeyPressed {
                 modified_key: keyboard::Key::Named(modified_key),
                 repeat: false,
@@ -959,7 +2465,14 @@ impl Styling {
                 keyboard::key::Named::Space => Some(Message::ClearTheme),
                 _ => None,
             }
-        })
+        });
+
+        Subscription::batch([
+            keyboard,
+            websocket,
+            sse,
+            window::close_requests().map(|_| Message::CloseRequested),
+        ])
     }
 
     fn theme(&self) -> Option<Theme> {
@@ -981,77 +2494,491 @@ impl Styling {
             .collect()
     }
 
+    // Загружаем коллекцию с диска при старте
+    fn with_collection(mut self) -> Self {
+        match Collection::load(collection::COLLECTION_FILE) {
+            Ok(collection) => self.collection = collection,
+            Err(error) => self.sidebar.error = Some(error),
+        }
+        self
+    }
+
+    // Загружаем историю при старте
+    fn with_history(mut self) -> Self {
+        match History::load() {
+            Ok(history) => self.history = history,
+            Err(error) => self.history_panel.error = Some(error),
+        }
+        self
+    }
+
+    // Запрос из истории в редактор, скрытые значения - из текущего запроса.
+    // false - часть секретов взять неоткуда
+    fn restore_history_entry(&mut self, entry: &HistoryEntry) -> bool {
+        let (request, missing) = entry.restore(&self.current_request(String::new()));
+        self.load_request(request);
+        self.history_panel.notice = (!missing.is_empty()).then(|| {
+            format!("Fill in redacted values before sending: {}", missing.join(", "))
+        });
+        missing.is_empty()
+    }
+
+    // Добавляем завершённый запрос в историю, файл пишем в фоне
+    fn record_history(&mut self, result: &Result<HttpResponse, String>) -> Task<Message> {
+        let Some((request, final_url)) = self.pending_request.take() else {
+            return Task::none();
+        };
+        let elapsed_ms = self
+            .request_started
+            .take()
+            .map(|started| started.elapsed().as_millis())
+            .unwrap_or_default();
+
+        self.history
+            .push(HistoryEntry::new(request, final_url, result, elapsed_ms));
+        Task::perform(self.history.save(), Message::HistorySaved)
+    }
+
+    // Загружаем окружения при старте
+    fn with_environments(mut self) -> Self {
+        match Environments::load() {
+            Ok(environments) => self.environments = environments,
+            Err(error) => self.environment_editor.error = Some(error),
+        }
+        self
+    }
+
+    // Загружаем глобальные настройки клиента
+    fn with_settings(mut self) -> Self {
+        match ClientSettings::load() {
+            Ok(settings) => self.client_settings = settings,
+            Err(error) => self.settings_error = Some(error),
+        }
+        self
+    }
+
+    // Загружаем кэш токенов OAuth2
+    fn with_tokens(mut self) -> Self {
+        match TokenCache::load() {
+            Ok(tokens) => self.oauth_tokens = tokens,
+            Err(error) => self.oauth_error = Some(error),
+        }
+        self
+    }
+
+    // Настройки OAuth2 с подставленными переменными и ключ кэша для них.
+    // Токены у каждого окружения свои
+    fn oauth_config(&self) -> (String, OAuth2Config) {
+        let config = self
+            .auth
+            .oauth2
+            .resolve(|value| self.environments.resolve(value));
+        (config.cache_key(self.environments.active.as_deref()), config)
+    }
+
+    // Токен из кэша для текущих настроек OAuth2
+    fn oauth_token(&self) -> Option<&Token> {
+        self.oauth_tokens.tokens.get(&self.oauth_config().0)
+    }
+
+    // Настройки запроса, если заданы, иначе глобальные
+    fn effective_settings(&self) -> &ClientSettings {
+        self.request_settings.as_ref().unwrap_or(&self.client_settings)
+    }
+
+    fn remove_spill_file(&mut self) {
+        if let Some(path) = self.spill_file.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    // Файл окружений пишем, когда правки затихнут
+    fn save_environments(&mut self) -> Task<Message> {
+        Task::perform(self.environments_save.changed(), Message::SaveEnvironments)
+    }
+
+    // Запрос в том виде, в каком он уйдёт: переменные подставлены,
+    // авторизация добавлена, query строка собрана из таблицы в выбранном стиле.
+    // Возвращает URL, заголовки и тело
+    fn prepared_request(&self) -> (String, Vec<HeaderParam>, RequestBody) {
+        let mut headers: Vec<HeaderParam> = self
+            .headers
+            .iter()
+            .map(|h| HeaderParam::new(h.key.clone(), self.environments.resolve(&h.value)))
+            .collect();
+        let mut query_params = self.resolved_query_params();
+
+        self.auth
+            .resolve(|value| self.environments.resolve(value))
+            .apply(&mut headers, &mut query_params, self.oauth_token());
+
+        let url = query::append(
+            &self.environments.resolve(query::strip_query(&self.url_input)),
+            &query_params,
+            self.array_style,
+        );
+        let body = self
+            .body_data
+            .prepare(&self.body_content.text(), self.jsonrpc.next_id, |value| {
+                self.environments.resolve(value)
+            });
+
+        (url, headers, body)
+    }
+
+    // Только итоговый URL (как в prepared_request) - для превью на каждой отрисовке
+    fn resolved_url(&self) -> String {
+        let mut query_params = self.resolved_query_params();
+        query_params.extend(self.auth.query_param().map(|param| {
+            QueryParam::new(
+                self.environments.resolve(&param.key),
+                self.environments.resolve(&param.value),
+            )
+        }));
+        query::append(
+            &self.environments.resolve(query::strip_query(&self.url_input)),
+            &query_params,
+            self.array_style,
+        )
+    }
+
+    // Уходим из WebSocket или SSE - закрываем их сессию, иначе подписка
+    // держит соединение (и переподключается) в фоне
+    fn set_connection_type(&mut self, connection_type: ConnectionType) {
+        if connection_type != ConnectionType::WebSocket && self.websocket.session.is_some() {
+            self.websocket.disconnect();
+        }
+        if connection_type != ConnectionType::Sse && self.sse.session.is_some() {
+            self.sse.disconnect();
+        }
+        self.connection_type = connection_type;
+    }
+
+    // SSE: тот же запрос, что и HTTP, только тело ответа читается потоком.
+    // opened - уже открытый ответ, его дочитываем вместо новой отправки
+    fn connect_sse(&mut self, opened: Option<sse::OpenStream>) {
+        if self.url_input.trim().is_empty() {
+            self.sse.status = sse::Status::Failed("URL is empty".to_string());
+            return;
+        }
+        let method = match self.http_method.to_reqwest(&self.custom_method) {
+            Ok(method) => method,
+            Err(error) => {
+                self.sse.status = sse::Status::Failed(error);
+                return;
+            }
+        };
+        let (url, headers, body) = self.prepared_request();
+        let request = sse::Request {
+            method,
+            url,
+            headers,
+            body,
+            send_body: self.force_body || self.http_method.has_body(),
+            settings: self.effective_settings().clone(),
+            signing: self.auth.resolve(|value| self.environments.resolve(value)),
+        };
+        self.sse.connect(request, opened);
+    }
+
+    // Таблица параметров изменилась - переписываем query в строке адреса
+    fn sync_url_from_params(&mut self) {
+        self.url_input = query::set_query(&self.url_input, &self.query_params);
+    }
+
+    // Query параметры с подставленными переменными
+    fn resolved_query_params(&self) -> Vec<QueryParam> {
+        self.query_params
+            .iter()
+            .map(|p| QueryParam {
+                value: self.environments.resolve(&p.value),
+                ..p.clone()
+            })
+            .collect()
+    }
+
+    // Файл коллекции пишем, когда правки затихнут
+    fn save_collection(&mut self) -> Task<Message> {
+        Task::perform(self.collection_save.changed(), Message::SaveCollection)
+    }
+
+    // Снимок текущего запроса из редактора
+    fn current_request(&self, name: String) -> SavedRequest {
+        SavedRequest {
+            name,
+            method: self.http_method,
+            custom_method: self.custom_method.clone(),
+            force_body: self.force_body,
+            settings: self.request_settings.clone(),
+            auth: self.auth.clone(),
+            url: query::strip_query(&self.url_input).to_string(),
+            query_params: self.query_params.clone(),
+            array_style: self.array_style,
+            headers: self.headers.clone(),
+            body: self.body_content.text(),
+            body_data: self.body_data.clone(),
+            connection: self.connection_type,
+            grpc_method: self.grpc.selected(),
+        }
+    }
+
+    // Переносим сохранённый запрос обратно в редактор
+    fn load_request(&mut self, request: SavedRequest) {
+        self.http_method = request.method;
+        self.custom_method = request.custom_method;
+        self.force_body = request.force_body;
+        self.request_settings = request.settings;
+        self.auth = request.auth;
+        // Query строка в самом URL (например, из старых файлов) идёт перед таблицей
+        let (_, mut query_params) = query::split_url(&request.url);
+        query_params.extend(request.query_params);
+        self.url_input = query::set_query(&request.url, &query_params);
+        self.query_params = query_params;
+        self.array_style = request.array_style;
+        self.headers = request.headers;
+        self.body_content = text_editor::Content::with_text(&request.body);
+        self.body_data = request.body_data;
+        self.set_connection_type(request.connection);
+        self.grpc.select(&request.grpc_method);
+        self.graphql.variables = text_editor::Content::with_text(&self.body_data.graphql_variables);
+        self.validate_body();
+        self.update_graphql_completions();
+    }
+
+    fn validate_body(&mut self) {
+        self.body_error = self.editor_syntax().validate(&self.body_content.text()).err();
+    }
+
+    // В режиме gRPC в редакторе всегда JSON сообщения
+    fn editor_syntax(&self) -> Syntax {
+        match self.connection_type {
+            ConnectionType::Grpc => Syntax::Json,
+            _ => self.body_data.syntax(),
+        }
+    }
+
+    fn call_grpc(&mut self) -> Task<Message> {
+        let method = self.grpc.method_descriptor().and_then(|method| {
+            if method.is_client_streaming() {
+                return Err("Client streaming methods are not supported".to_string());
+            }
+            let json = self.environments.resolve(&self.body_content.text());
+            grpc::parse_message(&method, &json).map(|message| (method, message))
+        });
+        self.grpc.start_call();
+        let (method, message) = match method {
+            Ok(prepared) => prepared,
+            Err(error) => {
+                self.grpc.handle(grpc::Event::Finished(Err(error)));
+                return Task::none();
+            }
+        };
+
+        // Заголовки с авторизацией уходят как metadata
+        let (url, headers, _) = self.prepared_request();
+        let settings = self.effective_settings().clone();
+        self.is_loading = true;
+        self.response_error = None;
+
+        let (task, handle) = Task::stream(
+            grpc::call(url, headers, settings, method, message).map(Message::GrpcEvent),
+        )
+        .abortable();
+        self.request_handle = Some(handle);
+        task
+    }
+
+    // Подсказки по схеме для позиции курсора в query
+    fn update_graphql_completions(&mut self) {
+        self.graphql.completions = match &self.graphql.schema {
+            Some(schema) if self.body_data.kind == BodyType::GraphQl => {
+                let text = self.body_content.text();
+                let cursor = self.body_content.cursor().position;
+                let offset = text
+                    .split('\n')
+                    .take(cursor.line)
+                    .map(|line| line.len() + 1)
+                    .sum::<usize>()
+                    + cursor.column;
+                graphql::completions(schema, &text, offset)
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    // GraphQL ответ с errors: ошибки отдельным блоком, в теле остаётся data.
+    // Ответы JSON-RPC разбираем по вызовам, тело остаётся целиком
+    // Ответы JSON-RPC и GraphQL разбираем только в их режимах:
+    // обычный JSON с ключами data/errors показываем как есть
+    fn show_response_body(&mut self, body: String) {
+        self.jsonrpc.replies = match self.body_data.kind {
+            BodyType::JsonRpc => jsonrpc::parse_response(&body).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let split = match self.body_data.kind {
+            BodyType::GraphQl => graphql::split_response(&body),
+            _ => None,
+        };
+        match split {
+            Some((errors, data)) => {
+                self.graphql.response_errors = errors;
+                self.response_body = data;
+            }
+            None => {
+                self.graphql.response_errors.clear();
+                self.response_body = body;
+            }
+        }
+    }
+
 }
 
 // ДОБАВЛЯЕМ асинхронную функцию (обязательно вне impl, чтоб токио её видел)
 async fn send_http_request(
-    method: HttpMethod,
-    url: String,
-    query_params: Vec<QueryParam>,
+    method: reqwest::Method,
+    url: String, // Уже вместе с query параметрами
     headers: Vec<HeaderParam>,
-    body_text: String,
-) -> Result<(u16, String), String> {
-    // Используем обычный (не blocking) клиент
-    let client = reqwest::Client::new();
-
-    // Создаем запрос в зависимости от метода
-    let mut request = match method {
-        HttpMethod::GET => client.get(&url),
-        HttpMethod::POST => client.post(&url),
-        HttpMethod::PUT => client.put(&url),
-        HttpMethod::DELETE => client.delete(&url),
-        HttpMethod::PATCH => client.patch(&url),
+    body: RequestBody,
+    send_body: bool, // Отправлять ли тело для этого метода
+    settings: ClientSettings,
+    signing: Auth, // Подпись (SigV4) ставится на уже собранный запрос
+) -> Result<HttpResponse, String> {
+    let (response, started) =
+        open_response(method, url, headers, body, send_body, settings, signing).await?;
+    read_response(response, started, |_| {}).await
+}
+
+// Читаем ответ, сообщая, сколько тела уже пришло
+async fn read_response(
+    response: reqwest::Response,
+    started: std::time::Instant,
+    on_progress: impl FnMut(response::Progress),
+) -> Result<HttpResponse, String> {
+    // Заголовки пришли - это и есть время до первого байта
+    let ttfb = started.elapsed();
+    let status = response.status().as_u16();
+    let version = format!("{:?}", response.version());
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(key, value)| {
+            (key.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned())
+        })
+        .collect();
+
+    // Поток событий не кончается - тело не ждём, его читает режим SSE
+    let event_stream = headers.iter().any(|(key, value)| {
+        key.eq_ignore_ascii_case("content-type") && sse::is_event_stream(value)
+    });
+
+    // Тело читаем потоком: большое уходит во временный файл, а не в память.
+    // Поток событий не трогаем - открытый ответ целиком уходит режиму SSE
+    let (bytes, size, body_file, event_stream) = if event_stream {
+        (Vec::new(), 0, None, Some(sse::OpenStream::new(response)))
+    } else {
+        let (bytes, size, body_file) = response::read_body(response, on_progress).await?;
+        (bytes, size, body_file, None)
     };
+    Ok(HttpResponse {
+        status,
+        version,
+        headers,
+        ttfb,
+        elapsed: started.elapsed(),
+        size,
+        // Форматируем здесь, в задаче, а не в потоке интерфейса
+        body: response::pretty_body(String::from_utf8_lossy(&bytes).into_owned()),
+        body_file,
+        event_stream,
+    })
+}
+
+// Собираем, подписываем и отправляем запрос. Тело ответа читает вызывающий,
+// вместе с ответом - момент отправки для замера времени
+async fn open_response(
+    method: reqwest::Method,
+    url: String,
+    headers: Vec<HeaderParam>,
+    body: RequestBody,
+    send_body: bool,
+    settings: ClientSettings,
+    signing: Auth,
+) -> Result<(reqwest::Response, std::time::Instant), String> {
+    // Используем обычный (не blocking) клиент с таймаутами/редиректами из настроек
+    let client = settings.build_client()?;
+
+    // Любой метод, включая WebDAV и произвольные
+    let mut request = client.request(method, &url);
 
     // 1. Сначала проверяем headers ДО их перемещения на наличие "content-type"
     let has_content_type = headers.iter()
         .any(|h| h.key.to_lowercase() == "content-type");
 
-    // 2. Потом перемещаем в map query параметры
-    let params_map: std::collections::HashMap<String, String> = query_params
-        .into_iter()
-        .map(|p| (p.key, p.value))
-        .collect();
-
-    if !params_map.is_empty() {
-        request = request.query(&params_map);
-    }
+    // multipart сам ставит Content-Type с boundary - свой из таблицы не нужен
+    let multipart = send_body && matches!(body, RequestBody::Multipart(_));
 
     // Добавляем заголовки в запрос
     for header in headers {
+        if multipart && header.key.eq_ignore_ascii_case("content-type") {
+            continue;
+        }
         request = request.header(&header.key, &header.value);
     }
 
-    // Добавляем тело если есть и метод не GET
-    // Попытка to JSON, если не получается - отправляем как текст
-    if !body_text.trim().is_empty() && method != HttpMethod::GET {
-        match serde_json::from_str::<serde_json::Value>(&body_text) {
-            Ok(json_value) => {
-                // Это валидный JSON - отправляем как JSON
-                request = request.json(&json_value);
-
-                // Автоматически добавляем Content-Type если его нет
-                if !has_content_type {
-                    request = request.header("Content-Type", "application/json");
-                }
-
-            }
-            Err(_) => {
-                // Не JSON - отправляем как обычный текст
-                request = request.body(body_text);
-            }
-        }
-
+    // Добавляем тело если есть и метод его допускает (или пользователь так решил)
+    if !body.is_empty() && send_body {
+        request = body.apply(request, has_content_type).await?;
     }
 
+    // Собираем запрос целиком, чтобы подписать итоговые URL, заголовки и тело
+    let mut request = request
+        .build()
+        .map_err(|e| format!("Invalid request: {}", e))?;
+    signing.sign(&mut request)?;
+
     // Отправляем запрос АСИНХРОННО (не блокируя UI)
-    match request.send().await {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            // Тоже асинхронно читаем тело
-            let body = response.text().await.unwrap_or_default();
-            Ok((status, body))
+    let started = std::time::Instant::now();
+    let response = execute_with_retry(&client, request, &signing).await?;
+    Ok((response, started))
+}
+
+// Сколько раз всего отправляем запрос (первая попытка + ответ на вызов)
+const MAX_ATTEMPTS: usize = 2;
+
+// Отправка с повтором: авторизация может ответить на вызов сервера
+// (Digest на 401) и отправить тот же запрос ещё раз
+async fn execute_with_retry(
+    client: &reqwest::Client,
+    mut request: reqwest::Request,
+    signing: &Auth,
+) -> Result<reqwest::Response, String> {
+    let mut attempt = 1;
+    loop {
+        // Копию снимаем до отправки (запрос с телом-потоком не копируется)
+        let retry = request.try_clone();
+        let response = client
+            .execute(request)
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if attempt >= MAX_ATTEMPTS {
+            return Ok(response);
+        }
+        let Some(mut next) = retry else {
+            // Ответить на вызов нечем - тело файла или multipart уже ушло потоком
+            if signing.has_challenge(&response) {
+                return Err(
+                    "Digest auth requires a buffered body: file and multipart bodies are streamed \
+                     and can't be re-sent after the 401 challenge"
+                        .to_string(),
+                );
+            }
+            return Ok(response);
+        };
+        if !signing.answer_challenge(&mut next, &response)? {
+            return Ok(response);
         }
-        Err(e) => Err(format!("Request failed: {}", e)),
+        request = next;
+        attempt += 1;
     }
 }
\ No newline at end of file
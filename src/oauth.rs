@@ -0,0 +1,486 @@
+// OAuth 2.0: получение токенов (client credentials, password, refresh token,
+// authorization code + PKCE) и их кэш по окружениям
+use base64::Engine;
+use rand::distr::{Alphanumeric, SampleString};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::query;
+use crate::settings::ClientSettings;
+use crate::storage;
+
+// Токен обновляем заранее, если до истечения осталось меньше этого
+const REFRESH_MARGIN_SECS: u64 = 30;
+// Сколько ждём, пока пользователь залогинится в браузере
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+// Способ получения токена
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GrantType {
+    #[default]
+    ClientCredentials,
+    Password,
+    RefreshToken,
+    AuthorizationCode, // С PKCE и редиректом на локальный порт
+}
+
+impl GrantType {
+    pub const ALL: &'static [GrantType] = &[
+        GrantType::ClientCredentials,
+        GrantType::Password,
+        GrantType::RefreshToken,
+        GrantType::AuthorizationCode,
+    ];
+}
+
+impl std::fmt::Display for GrantType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantType::ClientCredentials => write!(f, "Client credentials"),
+            GrantType::Password => write!(f, "Password"),
+            GrantType::RefreshToken => write!(f, "Refresh token"),
+            GrantType::AuthorizationCode => write!(f, "Authorization code + PKCE"),
+        }
+    }
+}
+
+fn default_redirect_port() -> u16 {
+    8765
+}
+
+// Настройки OAuth2 у запроса
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuth2Config {
+    #[serde(default)]
+    pub grant: GrantType,
+    #[serde(default)]
+    pub token_url: String,
+    #[serde(default)]
+    pub auth_url: String, // Только для authorization code
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default)]
+    pub scope: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub refresh_token: String, // Для гранта refresh token
+    #[serde(default = "default_redirect_port")]
+    pub redirect_port: u16, // http://127.0.0.1:<port>/callback
+}
+
+impl Default for OAuth2Config {
+    fn default() -> Self {
+        Self {
+            grant: GrantType::default(),
+            token_url: String::new(),
+            auth_url: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            scope: String::new(),
+            username: String::new(),
+            password: String::new(),
+            refresh_token: String::new(),
+            redirect_port: default_redirect_port(),
+        }
+    }
+}
+
+impl OAuth2Config {
+    pub fn resolve(&self, resolve: impl Fn(&str) -> String) -> Self {
+        Self {
+            grant: self.grant,
+            token_url: resolve(&self.token_url),
+            auth_url: resolve(&self.auth_url),
+            client_id: resolve(&self.client_id),
+            client_secret: resolve(&self.client_secret),
+            scope: resolve(&self.scope),
+            username: resolve(&self.username),
+            password: resolve(&self.password),
+            refresh_token: resolve(&self.refresh_token),
+            redirect_port: self.redirect_port,
+        }
+    }
+
+    // Ключ кэша: окружение + сервер авторизации + клиент + грант и scope,
+    // для Password ещё и пользователь - чужой токен не подхватываем
+    pub fn cache_key(&self, environment: Option<&str>) -> String {
+        let username = match self.grant {
+            GrantType::Password => self.username.as_str(),
+            _ => "",
+        };
+        format!(
+            "{}|{}|{}|{:?}|{}|{}",
+            environment.unwrap_or_default(),
+            self.token_url,
+            self.client_id,
+            self.grant,
+            self.scope,
+            username
+        )
+    }
+
+    fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}/callback", self.redirect_port)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// Полученный токен
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<u64>, // Unix-время, секунды
+}
+
+impl Token {
+    // Токен ещё годится (с запасом на время запроса)
+    pub fn is_fresh(&self) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| expires_at > now() + REFRESH_MARGIN_SECS)
+    }
+
+    // Значение заголовка Authorization
+    pub fn header_value(&self) -> String {
+        // Некоторые серверы отдают "bearer" строчными, а ждут "Bearer"
+        let token_type = if self.token_type.eq_ignore_ascii_case("bearer") {
+            "Bearer"
+        } else {
+            &self.token_type
+        };
+        format!("{} {}", token_type, self.access_token)
+    }
+
+    // "valid for 53 min" / "expired"
+    pub fn status(&self) -> String {
+        match self.expires_at {
+            None => "Token without expiry".to_string(),
+            Some(expires_at) if expires_at <= now() => "Token expired".to_string(),
+            Some(expires_at) => {
+                let left = expires_at - now();
+                if left >= 120 {
+                    format!("Token valid for {} min", left / 60)
+                } else {
+                    format!("Token valid for {} s", left)
+                }
+            }
+        }
+    }
+}
+
+// Нужно ли получить токен перед отправкой. Только что полученный берём как есть:
+// короткоживущий или уже истёкший токен иначе запрашивали бы по кругу
+pub fn needs_token(cached: Option<&Token>, just_fetched: bool) -> bool {
+    !just_fetched && !cached.is_some_and(Token::is_fresh)
+}
+
+// Ответ token endpoint (RFC 6749, 5.1)
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+// Кэш токенов, переживает перезапуск
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenCache {
+    #[serde(default)]
+    pub tokens: HashMap<String, Token>,
+}
+
+impl TokenCache {
+    // ~/.local/share/rastman/tokens.json
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("rastman").join("tokens.json"))
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    // Снимок берём сейчас, пишем в фоне; файл читает только владелец
+    pub fn save(&self) -> impl Future<Output = Result<(), String>> {
+        let snapshot = self.clone();
+        let write = Self::path().map(|path| {
+            storage::write_private(path, move || {
+                serde_json::to_string_pretty(&snapshot)
+                    .map_err(|e| format!("Failed to serialize tokens: {}", e))
+            })
+        });
+
+        async move {
+            match write {
+                Some(write) => write.await,
+                None => Err("No data directory for tokens".to_string()),
+            }
+        }
+    }
+}
+
+// POST на token endpoint с form-параметрами
+async fn request_token(
+    client: &reqwest::Client,
+    config: &OAuth2Config,
+    mut params: Vec<(&str, String)>,
+) -> Result<Token, String> {
+    if config.token_url.trim().is_empty() {
+        return Err("OAuth2 token URL is empty".to_string());
+    }
+
+    params.push(("client_id", config.client_id.clone()));
+    if !config.client_secret.is_empty() {
+        params.push(("client_secret", config.client_secret.clone()));
+    }
+    if !config.scope.is_empty() {
+        params.push(("scope", config.scope.clone()));
+    }
+
+    let response = client
+        .post(&config.token_url)
+        .header("Accept", "application/json")
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("Token endpoint returned {}: {}", status.as_u16(), body));
+    }
+
+    let token: TokenResponse = serde_json::from_str(&body)
+        .map_err(|e| format!("Invalid token response: {}", e))?;
+
+    Ok(Token {
+        access_token: token.access_token,
+        token_type: token.token_type.unwrap_or_else(|| "Bearer".to_string()),
+        refresh_token: token.refresh_token,
+        expires_at: token.expires_in.map(|seconds| now() + seconds),
+    })
+}
+
+// Authorization code + PKCE: открываем браузер и ждём код на loopback порту
+async fn authorization_code(
+    client: &reqwest::Client,
+    config: &OAuth2Config,
+) -> Result<Token, String> {
+    if config.auth_url.trim().is_empty() {
+        return Err("OAuth2 authorization URL is empty".to_string());
+    }
+
+    // PKCE (RFC 7636): verifier 64 символа, challenge = BASE64URL(SHA256(verifier))
+    let verifier = Alphanumeric.sample_string(&mut rand::rng(), 64);
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(verifier.as_bytes()));
+    let state = Alphanumeric.sample_string(&mut rand::rng(), 16);
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", config.redirect_port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", config.redirect_port, e))?;
+
+    let mut params = vec![
+        crate::QueryParam::new("response_type".to_string(), "code".to_string()),
+        crate::QueryParam::new("client_id".to_string(), config.client_id.clone()),
+        crate::QueryParam::new("redirect_uri".to_string(), config.redirect_uri()),
+        crate::QueryParam::new("code_challenge".to_string(), challenge),
+        crate::QueryParam::new("code_challenge_method".to_string(), "S256".to_string()),
+        crate::QueryParam::new("state".to_string(), state.clone()),
+    ];
+    if !config.scope.is_empty() {
+        params.push(crate::QueryParam::new("scope".to_string(), config.scope.clone()));
+    }
+    let url = query::append(&config.auth_url, &params, query::ArrayStyle::Repeat);
+
+    open::that_detached(&url).map_err(|e| format!("Failed to open browser: {}", e))?;
+
+    let code = tokio::time::timeout(LOGIN_TIMEOUT, wait_for_code(listener, &state))
+        .await
+        .map_err(|_| "Timed out waiting for the OAuth2 redirect".to_string())??;
+
+    request_token(
+        client,
+        config,
+        vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code),
+            ("redirect_uri", config.redirect_uri()),
+            ("code_verifier", verifier),
+        ],
+    )
+    .await
+}
+
+// Принимаем редирект браузера: GET /callback?code=...&state=...
+async fn wait_for_code(listener: tokio::net::TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("Redirect listener failed: {}", e))?;
+
+        let mut buffer = vec![0; 8192];
+        let read = stream.read(&mut buffer).await.unwrap_or_default();
+        let request = String::from_utf8_lossy(&buffer[..read]);
+
+        // Первая строка: "GET /callback?... HTTP/1.1"
+        let target = request.split_whitespace().nth(1).unwrap_or_default();
+        if !target.starts_with("/callback") {
+            // favicon и прочее
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n").await;
+            continue;
+        }
+
+        let params = query::split_url(target).1;
+        let get = |key: &str| params.iter().find(|p| p.key == key).map(|p| p.value.clone());
+
+        let result = match (get("code"), get("state"), get("error")) {
+            (_, _, Some(error)) => Err(format!(
+                "Authorization failed: {} {}",
+                error,
+                get("error_description").unwrap_or_default()
+            )),
+            // Без state или с чужим state код не принимаем (CSRF)
+            (Some(code), Some(received), _) if received == state => Ok(code),
+            (Some(_), Some(_), _) => Err("OAuth2 state mismatch".to_string()),
+            (Some(_), None, _) => Err("No state in OAuth2 redirect".to_string()),
+            _ => Err("No authorization code in redirect".to_string()),
+        };
+
+        let page = match &result {
+            Ok(_) => "Authorization complete. You can close this tab and return to rastman.",
+            Err(_) => "Authorization failed. See rastman for details.",
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            page.len(),
+            page
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+
+        return result;
+    }
+}
+
+// Получаем токен: сначала пробуем refresh token из кэша, потом выбранный грант.
+// Клиент с теми же настройками (таймауты, прокси, TLS), что и у запросов
+pub async fn obtain(
+    config: OAuth2Config,
+    cached: Option<Token>,
+    settings: ClientSettings,
+) -> Result<Token, String> {
+    let client = settings.build_client()?;
+    if let Some(refresh_token) = cached.and_then(|token| token.refresh_token) {
+        let refreshed = request_token(
+            &client,
+            &config,
+            vec![
+                ("grant_type", "refresh_token".to_string()),
+                ("refresh_token", refresh_token.clone()),
+            ],
+        )
+        .await;
+
+        if let Ok(mut token) = refreshed {
+            // Сервер может не прислать новый refresh token - оставляем старый
+            token.refresh_token.get_or_insert(refresh_token);
+            return Ok(token);
+        }
+    }
+
+    match config.grant {
+        GrantType::ClientCredentials => {
+            request_token(&client, &config, vec![("grant_type", "client_credentials".to_string())])
+                .await
+        }
+        GrantType::Password => {
+            request_token(
+                &client,
+                &config,
+                vec![
+                    ("grant_type", "password".to_string()),
+                    ("username", config.username.clone()),
+                    ("password", config.password.clone()),
+                ],
+            )
+            .await
+        }
+        GrantType::RefreshToken => {
+            let mut token = request_token(
+                &client,
+                &config,
+                vec![
+                    ("grant_type", "refresh_token".to_string()),
+                    ("refresh_token", config.refresh_token.clone()),
+                ],
+            )
+            .await?;
+            token.refresh_token.get_or_insert(config.refresh_token.clone());
+            Ok(token)
+        }
+        GrantType::AuthorizationCode => authorization_code(&client, &config).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(expires_in: i64) -> Token {
+        Token {
+            access_token: "t".to_string(),
+            token_type: "Bearer".to_string(),
+            refresh_token: None,
+            expires_at: Some((now() as i64 + expires_in) as u64),
+        }
+    }
+
+    #[test]
+    fn short_lived_token_is_used_right_after_fetch() {
+        // Живёт меньше запаса или уже истёк: перед обычной отправкой получаем новый
+        for token in [token(5), token(0), token(-60)] {
+            assert!(!token.is_fresh());
+            assert!(needs_token(Some(&token), false));
+            // Но полученный для этого запроса не запрашиваем снова
+            assert!(!needs_token(Some(&token), true));
+        }
+
+        assert!(!needs_token(Some(&token(3600)), false));
+        assert!(needs_token(None, false));
+        assert!(!needs_token(None, true));
+    }
+}
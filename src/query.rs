@@ -0,0 +1,248 @@
+// Сборка и разбор query строки: порядок и дубликаты параметров сохраняются
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+use crate::QueryParam;
+
+// Кодируем всё, кроме unreserved символов из RFC 3986
+pub const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// Как передавать несколько значений одного ключа
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ArrayStyle {
+    #[default]
+    Repeat,   // tag=a&tag=b
+    Brackets, // tag[]=a&tag[]=b
+    Comma,    // tag=a,b
+}
+
+impl ArrayStyle {
+    pub const ALL: &'static [ArrayStyle] =
+        &[ArrayStyle::Repeat, ArrayStyle::Brackets, ArrayStyle::Comma];
+}
+
+impl std::fmt::Display for ArrayStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ArrayStyle::Repeat => "tag=a&tag=b",
+            ArrayStyle::Brackets => "tag[]=a&tag[]=b",
+            ArrayStyle::Comma => "tag=a,b",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// Плейсхолдеры {{name}} не кодируем, чтобы их можно было подставить позже
+pub fn encode_component(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let end = start + end + 2;
+        result.extend(utf8_percent_encode(&rest[..start], COMPONENT));
+        result.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+
+    result.extend(utf8_percent_encode(rest, COMPONENT));
+    result
+}
+
+// Декодируем часть query строки (+ - это пробел)
+pub fn decode_component(value: &str) -> String {
+    percent_decode_str(&value.replace('+', " "))
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+fn encode_pair(key: &str, param: &QueryParam) -> String {
+    if param.bare {
+        encode_component(key)
+    } else {
+        format!("{}={}", encode_component(key), encode_component(&param.value))
+    }
+}
+
+// Query строка из параметров в том порядке, в каком они в таблице.
+// Параметры с пустым ключом пропускаем
+pub fn encode(params: &[QueryParam], style: ArrayStyle) -> String {
+    let params: Vec<&QueryParam> = params.iter().filter(|p| !p.key.is_empty()).collect();
+    let count = |key: &str| params.iter().filter(|p| p.key == key).count();
+    let mut pairs: Vec<String> = Vec::with_capacity(params.len());
+
+    for (index, param) in params.iter().enumerate() {
+        let repeated = count(&param.key) > 1;
+
+        let pair = match style {
+            ArrayStyle::Repeat => encode_pair(&param.key, param),
+            ArrayStyle::Brackets if repeated => encode_pair(&format!("{}[]", param.key), param),
+            ArrayStyle::Brackets => encode_pair(&param.key, param),
+            ArrayStyle::Comma if repeated => {
+                // Все значения ключа собираем на месте первого вхождения
+                if params[..index].iter().any(|p| p.key == param.key) {
+                    continue;
+                }
+                let values: Vec<String> = params
+                    .iter()
+                    .filter(|p| p.key == param.key)
+                    .map(|p| encode_component(&p.value))
+                    .collect();
+                format!("{}={}", encode_component(&param.key), values.join(","))
+            }
+            ArrayStyle::Comma => encode_pair(&param.key, param),
+        };
+        pairs.push(pair);
+    }
+
+    pairs.join("&")
+}
+
+// Разбираем query строку (без "?") в параметры
+pub fn decode(query: &str) -> Vec<QueryParam> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => QueryParam::new(decode_component(key), decode_component(value)),
+            None => QueryParam {
+                key: decode_component(pair),
+                value: String::new(),
+                bare: true,
+            },
+        })
+        .collect()
+}
+
+// Отделяем query от URL и раскладываем её в параметры
+pub fn split_url(url: &str) -> (String, Vec<QueryParam>) {
+    // Фрагмент на сервер не уходит
+    let url = url.split('#').next().unwrap_or_default();
+
+    match url.split_once('?') {
+        Some((base, query)) => (base.to_string(), decode(query)),
+        None => (url.to_string(), Vec::new()),
+    }
+}
+
+// URL без query и фрагмента
+pub fn strip_query(url: &str) -> &str {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    &url[..end]
+}
+
+// Переписываем query строку URL по таблице параметров (фрагмент остаётся).
+// В строке адреса параметры всегда один к одному с таблицей,
+// стиль массивов применяется только при отправке
+pub fn set_query(url: &str, params: &[QueryParam]) -> String {
+    let fragment = url.find('#').map(|index| &url[index..]).unwrap_or_default();
+    let query = encode(params, ArrayStyle::Repeat);
+
+    if query.is_empty() {
+        format!("{}{}", strip_query(url), fragment)
+    } else {
+        format!("{}?{}{}", strip_query(url), query, fragment)
+    }
+}
+
+// Добавляем параметры к URL (к уже имеющейся query, до фрагмента)
+pub fn append(url: &str, params: &[QueryParam], style: ArrayStyle) -> String {
+    let query = encode(params, style);
+    if query.is_empty() {
+        return url.to_string();
+    }
+
+    let (base, fragment) = match url.split_once('#') {
+        Some((base, fragment)) => (base, Some(fragment)),
+        None => (url, None),
+    };
+
+    let separator = if !base.contains('?') {
+        "?"
+    } else if base.ends_with('?') || base.ends_with('&') {
+        ""
+    } else {
+        "&"
+    };
+
+    match fragment {
+        Some(fragment) => format!("{}{}{}#{}", base, separator, query, fragment),
+        None => format!("{}{}{}", base, separator, query),
+    }
+}
+
+// Строку адреса правят руками - переносим её query в таблицу, не теряя строк,
+// которых в адресе не видно: с пустым ключом и значения у ключей без "=".
+// Видимые строки сопоставляем с параметрами адреса по порядку
+pub fn merge(rows: &[QueryParam], parsed: Vec<QueryParam>) -> Vec<QueryParam> {
+    let mut parsed = parsed.into_iter();
+    let mut merged = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        if row.key.is_empty() {
+            merged.push(row.clone());
+            continue;
+        }
+        // Параметр убрали из адреса - убираем и строку
+        let Some(param) = parsed.next() else {
+            continue;
+        };
+        let unchanged = row.key == param.key
+            && row.bare == param.bare
+            && (row.bare || row.value == param.value);
+        merged.push(if unchanged { row.clone() } else { param });
+    }
+
+    merged.extend(parsed);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(key: &str, value: &str, bare: bool) -> QueryParam {
+        QueryParam { key: key.to_string(), value: value.to_string(), bare }
+    }
+
+    #[test]
+    fn merge_keeps_rows_hidden_from_url() {
+        let rows = vec![
+            param("a", "1", false),
+            param("", "draft", false),
+            param("debug", "kept", true),
+        ];
+        let (_, parsed) = split_url("https://h/x?a=2&debug&b=3");
+        assert_eq!(
+            merge(&rows, parsed),
+            vec![
+                param("a", "2", false),
+                param("", "draft", false),
+                param("debug", "kept", true),
+                param("b", "3", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_drops_params_removed_from_url() {
+        let rows = vec![param("a", "1", false), param("", "", false), param("b", "2", false)];
+        let (_, parsed) = split_url("https://h/x?a=1");
+        assert_eq!(merge(&rows, parsed), vec![param("a", "1", false), param("", "", false)]);
+    }
+
+    #[test]
+    fn url_round_trips_through_table() {
+        let (base, params) = split_url("https://h/x?tag=a&tag=b&q=%7B%7D+x&flag#top");
+        assert_eq!(base, "https://h/x");
+        assert_eq!(params[2], param("q", "{} x", false));
+        assert!(params[3].bare);
+        assert_eq!(set_query("https://h/x?old=1#top", &params), "https://h/x?tag=a&tag=b&q=%7B%7D%20x&flag#top");
+    }
+}
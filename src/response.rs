@@ -0,0 +1,285 @@
+// Ответ сервера со всеми подробностями: заголовки, версия HTTP, тайминги, размер
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use iced::futures::StreamExt;
+use iced::widget::{button, column, container, row, scrollable, space, text};
+use iced::{Center, Element, Fill, Font};
+use tokio::io::AsyncWriteExt;
+
+use crate::sse::OpenStream;
+use crate::Message;
+
+#[derive(Debug, Clone, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub version: String,               // HTTP/1.1, HTTP/2 ...
+    pub headers: Vec<(String, String)>, // В порядке получения, дубликаты сохраняются
+    pub ttfb: Duration,                // До получения заголовков
+    pub elapsed: Duration,             // До конца тела
+    pub size: usize,                   // Размер тела в байтах
+    pub body: String,                  // JSON уже отформатирован
+    pub body_file: Option<PathBuf>, // Большое тело целиком - во временном файле, в body только начало
+    pub event_stream: Option<OpenStream>, // text/event-stream: тело не читали, его дочитывает SSE
+}
+
+// Больше этого тело в памяти не держим - весь ответ уходит во временный файл
+pub const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+
+// Столько тела показываем в панели ответа - длинный текст подвешивает интерфейс
+pub const DISPLAY_LIMIT: usize = 512 * 1024;
+
+// Чаще прогресс не отправляем
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// Сколько тела прочитано; всего - из Content-Length, если он есть
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Progress {
+    pub received: u64,
+    pub total: Option<u64>,
+}
+
+// Читаем тело потоком: небольшое - в память, большое - во временный файл.
+// Возвращаем байты для показа, полный размер и файл
+pub async fn read_body(
+    response: reqwest::Response,
+    mut on_progress: impl FnMut(Progress),
+) -> Result<(Vec<u8>, usize, Option<PathBuf>), String> {
+    let extension = spill_extension(
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default(),
+    );
+    let mut progress = Progress { received: 0, total: response.content_length() };
+    on_progress(progress);
+
+    let mut stream = response.bytes_stream();
+    let mut bytes = Vec::new();
+    let mut file: Option<(PathBuf, tokio::fs::File)> = None;
+    let mut unfinished = RemoveOnDrop(None);
+    let mut reported = Instant::now();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to read response body: {}", e))?;
+        progress.received += chunk.len() as u64;
+
+        match &mut file {
+            Some((path, file)) => write_chunk(file, path, &chunk).await?,
+            None if bytes.len() + chunk.len() > MEMORY_LIMIT => {
+                let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+                let path = std::env::temp_dir().join(format!("rastman-response-{}.{}", millis, extension));
+                let mut created = tokio::fs::File::create(&path)
+                    .await
+                    .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+                unfinished.0 = Some(path.clone());
+                write_chunk(&mut created, &path, &bytes).await?;
+                write_chunk(&mut created, &path, &chunk).await?;
+                truncate_utf8(&mut bytes, DISPLAY_LIMIT);
+                file = Some((path, created));
+            }
+            None => bytes.extend_from_slice(&chunk),
+        }
+
+        if reported.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(progress);
+            reported = Instant::now();
+        }
+    }
+
+    if let Some((path, file)) = &mut file {
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    unfinished.0 = None;
+    Ok((bytes, progress.received as usize, file.map(|(path, _)| path)))
+}
+
+// Недописанный временный файл (ошибка чтения или отмена запроса) удаляем
+struct RemoveOnDrop(Option<PathBuf>);
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        if let Some(path) = &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+// Обрезаем байты по границе символа UTF-8: продолжение символа (10xxxxxx) не режем.
+// Символ не длиннее 4 байт, дальше 3 байт не отступаем - бинарное тело режем как есть
+fn truncate_utf8(bytes: &mut Vec<u8>, limit: usize) {
+    if bytes.len() <= limit {
+        return;
+    }
+    let mut end = limit;
+    while end > limit.saturating_sub(3) && bytes[end] & 0xC0 == 0x80 {
+        end -= 1;
+    }
+    bytes.truncate(end);
+}
+
+// JSON показываем отформатированным, остальное как есть
+pub fn pretty_body(body: String) -> String {
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(value) => serde_json::to_string_pretty(&value).unwrap_or(body),
+        Err(_) => body,
+    }
+}
+
+async fn write_chunk(file: &mut tokio::fs::File, path: &std::path::Path, bytes: &[u8]) -> Result<(), String> {
+    file.write_all(bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Расширение временного файла, чтобы он открывался подходящей программой
+fn spill_extension(content_type: &str) -> &'static str {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    match mime.as_str() {
+        _ if mime.ends_with("json") => "json",
+        _ if mime.ends_with("xml") => "xml",
+        "text/html" => "html",
+        "text/csv" => "csv",
+        _ if mime.starts_with("text/") => "txt",
+        _ => "bin",
+    }
+}
+
+// Начало тела, которое помещается в панель ответа, по границе символа
+pub fn display_part(body: &str) -> &str {
+    if body.len() <= DISPLAY_LIMIT {
+        return body;
+    }
+    let mut end = DISPLAY_LIMIT;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
+
+// 1.2 KB, 3.4 MB ...
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// 850 ms, 1.24 s
+pub fn format_duration(duration: Duration) -> String {
+    let ms = duration.as_millis();
+    if ms < 1000 {
+        format!("{} ms", ms)
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+// Таблица заголовков ответа
+pub fn headers_table(response: &HttpResponse) -> Element<'_, Message> {
+    if response.headers.is_empty() {
+        return text("No headers").size(12).style(text::secondary).into();
+    }
+
+    let rows: Vec<Element<Message>> = response
+        .headers
+        .iter()
+        .map(|(key, value)| {
+            row![
+                text(key).size(12).font(Font::MONOSPACE).width(220),
+                text(value).size(12).font(Font::MONOSPACE).width(Fill),
+            ]
+            .spacing(10)
+            .into()
+        })
+        .collect();
+
+    container(scrollable(column(rows).spacing(4)).height(160))
+        .padding(10)
+        .style(container::bordered_box)
+        .into()
+}
+
+// Над обрезанным телом: сколько показано и где лежит целиком
+pub fn clipped_view<'a>(
+    response: &'a HttpResponse,
+    shown_body: &str,
+    file_error: &'a Option<String>,
+) -> Element<'a, Message> {
+    if response.body_file.is_none() && shown_body.len() <= DISPLAY_LIMIT {
+        return space().height(0).into();
+    }
+
+    let notice = text(format!(
+        "Showing first {} of {}",
+        format_size(DISPLAY_LIMIT),
+        format_size(response.size)
+    ))
+    .size(12)
+    .style(text::warning);
+
+    let Some(path) = &response.body_file else {
+        return notice.into();
+    };
+    column![
+        row![
+            notice,
+            text(format!("Full body: {}", path.display()))
+                .size(12)
+                .font(Font::MONOSPACE)
+                .width(Fill),
+            button(text("Open file").size(12))
+                .on_press(Message::OpenResponseFile)
+                .padding([3, 8])
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(Center),
+        match file_error {
+            Some(error) => Element::from(text(error).size(12).style(text::danger)),
+            None => space().height(0).into(),
+        },
+    ]
+    .spacing(5)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_backs_off_to_char_boundary() {
+        // "й" - два байта, лимит попадает в его середину
+        let mut bytes = "ab\u{439}".as_bytes().to_vec();
+        truncate_utf8(&mut bytes, 3);
+        assert_eq!(bytes, b"ab");
+
+        // "😀" - четыре байта
+        let mut bytes = "a\u{1F600}".as_bytes().to_vec();
+        truncate_utf8(&mut bytes, 4);
+        assert_eq!(bytes, b"a");
+
+        let mut bytes = b"abc".to_vec();
+        truncate_utf8(&mut bytes, 2);
+        assert_eq!(bytes, b"ab");
+    }
+
+    #[test]
+    fn pretty_body_formats_only_json() {
+        assert_eq!(pretty_body(r#"{"b":1,"a":[]}"#.to_string()), "{\n  \"b\": 1,\n  \"a\": []\n}");
+        assert_eq!(pretty_body("not json".to_string()), "not json");
+    }
+}
@@ -0,0 +1,297 @@
+// Настройки HTTP клиента: таймауты, редиректы, версия HTTP, распаковка.
+// Глобальные хранятся у пользователя, у запроса могут быть свои
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use iced::widget::{
+    checkbox, column, container, pick_list, row, space, text, text_input, toggler,
+};
+use iced::{Center, Element, Fill};
+
+use crate::storage;
+use crate::Message;
+
+// Что делать с 3xx ответами
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RedirectPolicy {
+    #[default]
+    Follow,     // Идём по редиректам (не больше max_redirects)
+    DontFollow, // Показываем сам 3xx ответ
+}
+
+impl RedirectPolicy {
+    pub const ALL: &'static [RedirectPolicy] =
+        &[RedirectPolicy::Follow, RedirectPolicy::DontFollow];
+}
+
+impl std::fmt::Display for RedirectPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedirectPolicy::Follow => write!(f, "Follow redirects"),
+            RedirectPolicy::DontFollow => write!(f, "Don't follow (show 3xx)"),
+        }
+    }
+}
+
+// Версия протокола
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HttpVersion {
+    #[default]
+    Auto,               // Договариваемся через ALPN
+    Http1,              // Только HTTP/1.1
+    Http2PriorKnowledge, // Сразу HTTP/2 без апгрейда (h2c)
+}
+
+impl HttpVersion {
+    pub const ALL: &'static [HttpVersion] = &[
+        HttpVersion::Auto,
+        HttpVersion::Http1,
+        HttpVersion::Http2PriorKnowledge,
+    ];
+}
+
+impl std::fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpVersion::Auto => write!(f, "Auto"),
+            HttpVersion::Http1 => write!(f, "HTTP/1.1 only"),
+            HttpVersion::Http2PriorKnowledge => write!(f, "HTTP/2 prior knowledge"),
+        }
+    }
+}
+
+fn default_max_redirects() -> usize {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientSettings {
+    #[serde(default)]
+    pub connect_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub read_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub total_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub redirect: RedirectPolicy,
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: usize,
+    #[serde(default)]
+    pub http_version: HttpVersion,
+    #[serde(default = "default_true")]
+    pub gzip: bool,
+    #[serde(default = "default_true")]
+    pub brotli: bool,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: None,
+            read_timeout_ms: None,
+            total_timeout_ms: None,
+            redirect: RedirectPolicy::Follow,
+            max_redirects: default_max_redirects(),
+            http_version: HttpVersion::Auto,
+            gzip: true,
+            brotli: true,
+        }
+    }
+}
+
+impl ClientSettings {
+    // ~/.config/rastman/settings.json
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rastman").join("settings.json"))
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    // Снимок берём сейчас, пишем в фоне
+    pub fn save(&self) -> impl Future<Output = Result<(), String>> {
+        let snapshot = self.clone();
+        let write = Self::path().map(|path| {
+            storage::write(path, move || {
+                serde_json::to_string_pretty(&snapshot)
+                    .map_err(|e| format!("Failed to serialize settings: {}", e))
+            })
+        });
+
+        async move {
+            match write {
+                Some(write) => write.await,
+                None => Err("No config directory for settings".to_string()),
+            }
+        }
+    }
+
+    // Клиент reqwest под эти настройки
+    pub fn build_client(&self) -> Result<reqwest::Client, String> {
+        let mut builder = reqwest::Client::builder()
+            .gzip(self.gzip)
+            .brotli(self.brotli)
+            .redirect(match self.redirect {
+                RedirectPolicy::Follow => reqwest::redirect::Policy::limited(self.max_redirects),
+                RedirectPolicy::DontFollow => reqwest::redirect::Policy::none(),
+            });
+
+        if let Some(ms) = self.connect_timeout_ms {
+            builder = builder.connect_timeout(Duration::from_millis(ms));
+        }
+        if let Some(ms) = self.read_timeout_ms {
+            builder = builder.read_timeout(Duration::from_millis(ms));
+        }
+        if let Some(ms) = self.total_timeout_ms {
+            builder = builder.timeout(Duration::from_millis(ms));
+        }
+
+        builder = match self.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        builder
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))
+    }
+}
+
+// Пустое поле - без таймаута, нецифровой ввод игнорируем
+fn parse_ms(input: &str, current: Option<u64>) -> Option<u64> {
+    let input = input.trim();
+    if input.is_empty() {
+        None
+    } else {
+        input.parse().ok().or(current)
+    }
+}
+
+// Поле таймаута в миллисекундах
+fn timeout_input<'a>(
+    label: &'a str,
+    value: Option<u64>,
+    on_change: impl Fn(Option<u64>) -> Message + 'a,
+) -> Element<'a, Message> {
+    let current = value.map(|ms| ms.to_string()).unwrap_or_default();
+
+    column![
+        text(label).size(12),
+        text_input("none", &current)
+            .on_input(move |input| on_change(parse_ms(&input, value)))
+            .width(Fill)
+            .padding(5)
+            .size(12),
+    ]
+    .spacing(3)
+    .into()
+}
+
+// Панель настроек: показывает активные (свои у запроса или глобальные)
+pub fn view<'a>(
+    settings: &'a ClientSettings,
+    per_request: bool,
+    error: Option<&'a String>,
+) -> Element<'a, Message> {
+    let changed = |settings: ClientSettings| Message::ClientSettingsChanged(settings);
+
+    let scope = toggler(per_request)
+        .label(if per_request {
+            "Settings for this request only"
+        } else {
+            "Global settings (all requests)"
+        })
+        .on_toggle(Message::PerRequestSettingsToggled)
+        .text_size(12);
+
+    let timeouts = row![
+        timeout_input("Connect timeout, ms", settings.connect_timeout_ms, move |ms| {
+            changed(ClientSettings { connect_timeout_ms: ms, ..settings.clone() })
+        }),
+        timeout_input("Read timeout, ms", settings.read_timeout_ms, move |ms| {
+            changed(ClientSettings { read_timeout_ms: ms, ..settings.clone() })
+        }),
+        timeout_input("Total timeout, ms", settings.total_timeout_ms, move |ms| {
+            changed(ClientSettings { total_timeout_ms: ms, ..settings.clone() })
+        }),
+    ]
+    .spacing(10);
+
+    let max_redirects = settings.max_redirects.to_string();
+    let redirects = row![
+        pick_list(RedirectPolicy::ALL, Some(settings.redirect), move |redirect| {
+            changed(ClientSettings { redirect, ..settings.clone() })
+        })
+        .text_size(12)
+        .padding(5),
+        text("Max hops:").size(12),
+        text_input("10", &max_redirects)
+            .on_input(move |input| {
+                let max_redirects = input.trim().parse().unwrap_or(settings.max_redirects);
+                changed(ClientSettings { max_redirects, ..settings.clone() })
+            })
+            .width(60)
+            .padding(5)
+            .size(12),
+    ]
+    .spacing(10)
+    .align_y(Center);
+
+    let protocol = row![
+        text("HTTP version:").size(12),
+        pick_list(HttpVersion::ALL, Some(settings.http_version), move |http_version| {
+            changed(ClientSettings { http_version, ..settings.clone() })
+        })
+        .text_size(12)
+        .padding(5),
+        checkbox(settings.gzip)
+            .label("gzip")
+            .on_toggle(move |gzip| changed(ClientSettings { gzip, ..settings.clone() }))
+            .text_size(12),
+        checkbox(settings.brotli)
+            .label("brotli")
+            .on_toggle(move |brotli| changed(ClientSettings { brotli, ..settings.clone() }))
+            .text_size(12),
+    ]
+    .spacing(10)
+    .align_y(Center);
+
+    let error_line: Element<Message> = match error {
+        Some(error) => text(error).size(12).style(text::danger).into(),
+        None => space().height(0).into(),
+    };
+
+    container(
+        column![
+            text("Client settings:").size(16),
+            scope,
+            timeouts,
+            redirects,
+            protocol,
+            error_line,
+        ]
+        .spacing(10)
+        .padding(10),
+    )
+    .width(Fill)
+    .style(container::bordered_box)
+    .into()
+}
@@ -0,0 +1,391 @@
+// Подпись запросов для партнёрских API: HMAC по шаблону из частей запроса
+// и JWT, выпущенный из claims и ключа
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::sigv4::hex;
+
+// Хэш для HMAC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HmacAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    pub const ALL: &'static [HmacAlgorithm] = &[HmacAlgorithm::Sha256, HmacAlgorithm::Sha512];
+}
+
+impl std::fmt::Display for HmacAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HmacAlgorithm::Sha256 => write!(f, "HMAC-SHA256"),
+            HmacAlgorithm::Sha512 => write!(f, "HMAC-SHA512"),
+        }
+    }
+}
+
+// Как записать подпись в заголовок
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+impl SignatureEncoding {
+    pub const ALL: &'static [SignatureEncoding] = &[SignatureEncoding::Hex, SignatureEncoding::Base64];
+}
+
+impl std::fmt::Display for SignatureEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureEncoding::Hex => write!(f, "hex"),
+            SignatureEncoding::Base64 => write!(f, "base64"),
+        }
+    }
+}
+
+// Подставляемые в шаблон части запроса
+pub const TEMPLATE_HINT: &str =
+    "{method} {path} {query} {host} {timestamp} {timestamp_ms} {body} {body_sha256}; \\n for newline";
+
+fn default_signature_header() -> String {
+    "X-Signature".to_string()
+}
+
+fn default_timestamp_header() -> String {
+    "X-Timestamp".to_string()
+}
+
+fn default_template() -> String {
+    "{method}\\n{path}\\n{timestamp}\\n{body_sha256}".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HmacConfig {
+    #[serde(default)]
+    pub algorithm: HmacAlgorithm,
+    #[serde(default)]
+    pub secret: String,
+    #[serde(default = "default_template")]
+    pub template: String,
+    #[serde(default = "default_signature_header")]
+    pub header: String,
+    #[serde(default)]
+    pub prefix: String, // Перед подписью, например "v1="
+    #[serde(default)]
+    pub encoding: SignatureEncoding,
+    #[serde(default = "default_timestamp_header")]
+    pub timestamp_header: String, // Пусто - время не отправляем
+}
+
+impl Default for HmacConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: HmacAlgorithm::default(),
+            secret: String::new(),
+            template: default_template(),
+            header: default_signature_header(),
+            prefix: String::new(),
+            encoding: SignatureEncoding::default(),
+            timestamp_header: default_timestamp_header(),
+        }
+    }
+}
+
+impl HmacConfig {
+    pub fn resolve(&self, resolve: impl Fn(&str) -> String) -> Self {
+        Self {
+            secret: resolve(&self.secret),
+            template: resolve(&self.template),
+            header: resolve(&self.header),
+            prefix: resolve(&self.prefix),
+            timestamp_header: resolve(&self.timestamp_header),
+            ..self.clone()
+        }
+    }
+}
+
+// Алгоритм JWT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum JwtAlgorithm {
+    #[default]
+    HS256,
+    RS256,
+    ES256,
+}
+
+impl JwtAlgorithm {
+    pub const ALL: &'static [JwtAlgorithm] =
+        &[JwtAlgorithm::HS256, JwtAlgorithm::RS256, JwtAlgorithm::ES256];
+
+    // Для HS256 ключ - это секрет, для остальных - PEM файл
+    pub fn uses_key_file(self) -> bool {
+        self != JwtAlgorithm::HS256
+    }
+}
+
+impl std::fmt::Display for JwtAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+fn default_lifetime() -> u64 {
+    300
+}
+
+fn default_claims() -> String {
+    "{}".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JwtConfig {
+    #[serde(default)]
+    pub algorithm: JwtAlgorithm,
+    #[serde(default)]
+    pub secret: String, // HS256
+    #[serde(default)]
+    pub key_file: String, // RS256/ES256: приватный ключ в PEM
+    #[serde(default)]
+    pub key_id: String, // kid в заголовке токена
+    #[serde(default = "default_claims")]
+    pub claims: String, // JSON объект, iat/exp добавляются сами
+    #[serde(default = "default_lifetime")]
+    pub lifetime_secs: u64,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: JwtAlgorithm::default(),
+            secret: String::new(),
+            key_file: String::new(),
+            key_id: String::new(),
+            claims: default_claims(),
+            lifetime_secs: default_lifetime(),
+        }
+    }
+}
+
+impl JwtConfig {
+    pub fn resolve(&self, resolve: impl Fn(&str) -> String) -> Self {
+        Self {
+            secret: resolve(&self.secret),
+            key_file: resolve(&self.key_file),
+            key_id: resolve(&self.key_id),
+            claims: resolve(&self.claims),
+            ..self.clone()
+        }
+    }
+}
+
+fn unix_time(now: SystemTime) -> std::time::Duration {
+    now.duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+fn header_value(value: &str) -> Result<reqwest::header::HeaderValue, String> {
+    reqwest::header::HeaderValue::from_str(value).map_err(|e| format!("Invalid header value: {}", e))
+}
+
+fn header_name(name: &str) -> Result<reqwest::header::HeaderName, String> {
+    reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|_| format!("Invalid header name: {}", name))
+}
+
+// Строка для подписи: подставляем части запроса в шаблон за один проход -
+// фигурные скобки внутри подставленных query и тела не разворачиваются повторно.
+// Тело-поток (файл, multipart) не прочитать до отправки - {body} с ним не подписать
+fn render_template(
+    template: &str,
+    request: &reqwest::Request,
+    now: SystemTime,
+) -> Result<String, String> {
+    let url = request.url();
+    let body = match request.body() {
+        None => Some(&[][..]),
+        Some(body) => body.as_bytes(),
+    };
+    let time = unix_time(now);
+
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '\\']) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("\\n") {
+            output.push('\n');
+            rest = after;
+            continue;
+        }
+        let name = rest
+            .strip_prefix('{')
+            .and_then(|after| after.find('}').map(|end| &after[..end]));
+        let value = match name {
+            Some("method") => request.method().as_str().to_string(),
+            Some("path") => url.path().to_string(),
+            Some("query") => url.query().unwrap_or_default().to_string(),
+            Some("host") => url.host_str().unwrap_or_default().to_string(),
+            Some("timestamp_ms") => time.as_millis().to_string(),
+            Some("timestamp") => time.as_secs().to_string(),
+            Some(name @ ("body" | "body_sha256")) => {
+                let body = body.ok_or_else(|| {
+                    format!("{{{}}} needs a buffered body: file and multipart bodies are streamed", name)
+                })?;
+                if name == "body" {
+                    String::from_utf8_lossy(body).into_owned()
+                } else {
+                    hex(&Sha256::digest(body))
+                }
+            }
+            // Не плейсхолдер - символ как есть
+            _ => {
+                let c = rest.chars().next().unwrap_or_default();
+                output.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        };
+        output.push_str(&value);
+        rest = &rest[name.map_or(0, str::len) + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+// HMAC по шаблону в заголовок (и время запроса в свой заголовок)
+pub fn sign_hmac(
+    config: &HmacConfig,
+    request: &mut reqwest::Request,
+    now: SystemTime,
+) -> Result<(), String> {
+    if config.header.trim().is_empty() {
+        return Err("Signature header name is empty".to_string());
+    }
+
+    let message = render_template(&config.template, request, now)?;
+    let signature = match config.algorithm {
+        HmacAlgorithm::Sha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(config.secret.as_bytes())
+                .expect("HMAC accepts any key length");
+            mac.update(message.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+        HmacAlgorithm::Sha512 => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(config.secret.as_bytes())
+                .expect("HMAC accepts any key length");
+            mac.update(message.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+    };
+    let signature = match config.encoding {
+        SignatureEncoding::Hex => hex(&signature),
+        SignatureEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(signature),
+    };
+
+    let headers = request.headers_mut();
+    if !config.timestamp_header.trim().is_empty() {
+        headers.insert(
+            header_name(&config.timestamp_header)?,
+            header_value(&unix_time(now).as_secs().to_string())?,
+        );
+    }
+    headers.insert(
+        header_name(&config.header)?,
+        header_value(&format!("{}{}", config.prefix, signature))?,
+    );
+    Ok(())
+}
+
+// Выпускаем JWT и кладём в Authorization: Bearer
+pub fn sign_jwt(config: &JwtConfig, request: &mut reqwest::Request, now: SystemTime) -> Result<(), String> {
+    use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+    let mut claims: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(if config.claims.trim().is_empty() { "{}" } else { &config.claims })
+            .map_err(|e| format!("JWT claims must be a JSON object: {}", e))?;
+
+    // Свои iat/exp из claims не трогаем
+    let issued_at = unix_time(now).as_secs();
+    claims.entry("iat").or_insert(issued_at.into());
+    claims
+        .entry("exp")
+        .or_insert((issued_at + config.lifetime_secs).into());
+
+    let read_key = || {
+        std::fs::read(config.key_file.trim())
+            .map_err(|e| format!("Failed to read key file {}: {}", config.key_file, e))
+    };
+    let (algorithm, key) = match config.algorithm {
+        JwtAlgorithm::HS256 => (Algorithm::HS256, EncodingKey::from_secret(config.secret.as_bytes())),
+        JwtAlgorithm::RS256 => (
+            Algorithm::RS256,
+            EncodingKey::from_rsa_pem(&read_key()?).map_err(|e| format!("Invalid RSA key: {}", e))?,
+        ),
+        JwtAlgorithm::ES256 => (
+            Algorithm::ES256,
+            EncodingKey::from_ec_pem(&read_key()?).map_err(|e| format!("Invalid EC key: {}", e))?,
+        ),
+    };
+
+    let mut header = Header::new(algorithm);
+    if !config.key_id.is_empty() {
+        header.kid = Some(config.key_id.clone());
+    }
+
+    let token = jsonwebtoken::encode(&header, &claims, &key)
+        .map_err(|e| format!("Failed to sign JWT: {}", e))?;
+    request.headers_mut().insert(
+        reqwest::header::AUTHORIZATION,
+        header_value(&format!("Bearer {}", token))?,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str, body: reqwest::Body) -> reqwest::Request {
+        reqwest::Client::new().post(url).body(body).build().unwrap()
+    }
+
+    #[test]
+    fn template_is_rendered_in_one_pass() {
+        let request = request("https://api.example.com/v1/items?q={body}", reqwest::Body::from("{method}"));
+        let now = UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_000_123);
+        let rendered = render_template(
+            "{method}\\n{host}{path}\\n{query}\\n{timestamp}.{timestamp_ms}\\n{body}\\n{unknown}{",
+            &request,
+            now,
+        );
+        assert_eq!(
+            rendered,
+            Ok("POST\napi.example.com/v1/items\nq={body}\n1700000000.1700000000123\n{method}\n{unknown}{"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn body_hash_of_buffered_body() {
+        let request = request("https://example.com/", reqwest::Body::from(""));
+        assert_eq!(
+            render_template("{body_sha256}", &request, UNIX_EPOCH),
+            Ok("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string())
+        );
+    }
+
+    #[test]
+    fn streamed_body_cannot_be_signed() {
+        let stream = iced::futures::stream::iter([Ok::<_, std::io::Error>("chunk")]);
+        let request = request("https://example.com/", reqwest::Body::wrap_stream(stream));
+        assert!(render_template("{method} {path}", &request, UNIX_EPOCH).is_ok());
+        let error = render_template("{body_sha256}", &request, UNIX_EPOCH).unwrap_err();
+        assert!(error.starts_with("{body_sha256} needs a buffered body"), "{}", error);
+    }
+}
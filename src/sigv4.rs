@@ -0,0 +1,375 @@
+// AWS Signature Version 4: подпись готового запроса перед отправкой
+// (S3 и совместимые хранилища, API Gateway и прочие сервисы AWS)
+use hmac::{Hmac, Mac};
+use percent_encoding::{percent_decode_str, utf8_percent_encode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::query::COMPONENT;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+// Ключи и область подписи
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SigV4Config {
+    #[serde(default)]
+    pub access_key: String,
+    #[serde(default)]
+    pub secret_key: String,
+    #[serde(default)]
+    pub session_token: String, // Для временных ключей (STS)
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub service: String, // s3, execute-api, ...
+}
+
+impl SigV4Config {
+    pub fn resolve(&self, resolve: impl Fn(&str) -> String) -> Self {
+        Self {
+            access_key: resolve(&self.access_key),
+            secret_key: resolve(&self.secret_key),
+            session_token: resolve(&self.session_token),
+            region: resolve(&self.region),
+            service: resolve(&self.service),
+        }
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+// Unix-время -> ("20240131T235959Z", "20240131") в UTC
+fn amz_date(time: SystemTime) -> (String, String) {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    let (days, rem) = (secs / 86400, secs % 86400);
+
+    // Дни с 1970-01-01 -> дата (алгоритм civil_from_days Говарда Хиннанта)
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{:04}{:02}{:02}", year, month, day);
+    let timestamp = format!(
+        "{}T{:02}{:02}{:02}Z",
+        date,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    );
+    (timestamp, date)
+}
+
+// Путь: каждый сегмент кодируем заново по RFC 3986.
+// Все сервисы, кроме S3, ждут двойное кодирование
+fn canonical_uri(path: &str, service: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+
+    path.split('/')
+        .map(|segment| {
+            let decoded = percent_decode_str(segment).decode_utf8_lossy();
+            let encoded = utf8_percent_encode(&decoded, COMPONENT).to_string();
+            if service == "s3" {
+                encoded
+            } else {
+                utf8_percent_encode(&encoded, COMPONENT).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Query: пары кодируем и сортируем по ключу, затем по значению
+fn canonical_query(url: &reqwest::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            (
+                utf8_percent_encode(&key, COMPONENT).to_string(),
+                utf8_percent_encode(&value, COMPONENT).to_string(),
+            )
+        })
+        .collect();
+    pairs.sort();
+
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Подписываем запрос: добавляем x-amz-* заголовки и Authorization.
+// Подписываются host и все заголовки, которые уже есть в запросе
+pub fn sign(config: &SigV4Config, request: &mut reqwest::Request, now: SystemTime) -> Result<(), String> {
+    if config.access_key.is_empty() || config.secret_key.is_empty() {
+        return Err("AWS access key and secret key are required".to_string());
+    }
+    if config.region.is_empty() || config.service.is_empty() {
+        return Err("AWS region and service are required".to_string());
+    }
+
+    let (timestamp, date) = amz_date(now);
+
+    // Тело-поток заранее не прочитать - такие запросы подписываем без хэша тела,
+    // но UNSIGNED-PAYLOAD понимает только S3, остальные сервисы ответят 403
+    let payload_hash = match request.body() {
+        None => hex(&Sha256::digest(b"")),
+        Some(body) => match body.as_bytes() {
+            Some(bytes) => hex(&Sha256::digest(bytes)),
+            None if config.service == "s3" => "UNSIGNED-PAYLOAD".to_string(),
+            None => {
+                return Err(format!(
+                    "AWS service {} needs a signed body: file and multipart bodies are streamed \
+                     (only s3 accepts UNSIGNED-PAYLOAD)",
+                    config.service
+                ))
+            }
+        },
+    };
+
+    let headers = request.headers_mut();
+    let mut set = |name: &'static str, value: &str| -> Result<(), String> {
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|e| format!("Invalid {} header: {}", name, e))?;
+        headers.insert(name, value);
+        Ok(())
+    };
+    set("x-amz-date", &timestamp)?;
+    set("x-amz-content-sha256", &payload_hash)?;
+    if !config.session_token.is_empty() {
+        set("x-amz-security-token", &config.session_token)?;
+    }
+    // Заголовок из таблицы мог остаться от прошлой подписи
+    request.headers_mut().remove(reqwest::header::AUTHORIZATION);
+
+    let (scope, signed_headers, signature) = signature(
+        config,
+        request.method(),
+        request.url(),
+        request.headers(),
+        &payload_hash,
+        &timestamp,
+        &date,
+    )?;
+    let authorization = format!(
+        "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        ALGORITHM, config.access_key, scope, signed_headers, signature
+    );
+    let authorization = reqwest::header::HeaderValue::from_str(&authorization)
+        .map_err(|e| format!("Invalid Authorization header: {}", e))?;
+    request
+        .headers_mut()
+        .insert(reqwest::header::AUTHORIZATION, authorization);
+
+    Ok(())
+}
+
+// Канонический запрос -> строка для подписи -> подпись ключом, выведенным из секрета.
+// Возвращаем область, список подписанных заголовков и подпись
+fn signature(
+    config: &SigV4Config,
+    method: &reqwest::Method,
+    url: &reqwest::Url,
+    headers: &reqwest::header::HeaderMap,
+    payload_hash: &str,
+    timestamp: &str,
+    date: &str,
+) -> Result<(String, String, String), String> {
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err("URL has no host".to_string()),
+    };
+
+    // Имена в нижнем регистре, пробелы в значениях схлопываем, сортируем по имени
+    let mut canonical_headers: Vec<(String, String)> = vec![("host".to_string(), host)];
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        match canonical_headers.iter_mut().find(|(n, _)| n == name.as_str()) {
+            // Повторяющиеся заголовки склеиваются через запятую
+            Some((_, existing)) => {
+                existing.push(',');
+                existing.push_str(&value);
+            }
+            None => canonical_headers.push((name.as_str().to_string(), value)),
+        }
+    }
+    canonical_headers.sort();
+
+    let signed_headers = canonical_headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let headers_block: String = canonical_headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method,
+        canonical_uri(url.path(), &config.service),
+        canonical_query(url),
+        headers_block,
+        signed_headers,
+        payload_hash
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, config.region, config.service);
+    let string_to_sign = format!(
+        "{}\n{}\n{}\n{}",
+        ALGORITHM,
+        timestamp,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = hmac_sha256(format!("AWS4{}", config.secret_key).as_bytes(), date);
+    let key = hmac_sha256(&key, &config.region);
+    let key = hmac_sha256(&key, &config.service);
+    let key = hmac_sha256(&key, "aws4_request");
+    let signature = hex(&hmac_sha256(&key, &string_to_sign));
+
+    Ok((scope, signed_headers, signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // Общие данные тестового набора AWS SigV4 (aws-sig-v4-test-suite)
+    fn config() -> SigV4Config {
+        SigV4Config {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: String::new(),
+            region: "us-east-1".to_string(),
+            service: "service".to_string(),
+        }
+    }
+
+    const NOW: Duration = Duration::from_secs(1440938160); // 2015-08-30T12:36:00Z
+
+    // Подпись как в наборе: только заголовки из примера, без x-amz-content-sha256
+    fn suite(
+        method: &str,
+        url: &str,
+        headers: &[(&'static str, &str)],
+        body: &str,
+    ) -> (String, String) {
+        let mut map = reqwest::header::HeaderMap::new();
+        for (name, value) in headers {
+            map.append(
+                reqwest::header::HeaderName::from_static(name),
+                reqwest::header::HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        map.insert("x-amz-date", "20150830T123600Z".parse().unwrap());
+        let (_, signed_headers, signature) = signature(
+            &config(),
+            &method.parse().unwrap(),
+            &url.parse().unwrap(),
+            &map,
+            &hex(&Sha256::digest(body.as_bytes())),
+            "20150830T123600Z",
+            "20150830",
+        )
+        .unwrap();
+        (signed_headers, signature)
+    }
+
+    #[test]
+    fn amz_date_is_utc() {
+        assert_eq!(
+            amz_date(UNIX_EPOCH + NOW),
+            ("20150830T123600Z".to_string(), "20150830".to_string())
+        );
+        assert_eq!(amz_date(UNIX_EPOCH).0, "19700101T000000Z");
+        assert_eq!(amz_date(UNIX_EPOCH + Duration::from_secs(951_782_400)).1, "20000229");
+    }
+
+    #[test]
+    fn get_vanilla() {
+        let (signed, signature) = suite("GET", "https://example.amazonaws.com/", &[], "");
+        assert_eq!(signed, "host;x-amz-date");
+        assert_eq!(signature, "5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31");
+    }
+
+    #[test]
+    fn get_vanilla_query_order() {
+        let (_, signature) =
+            suite("GET", "https://example.amazonaws.com/?Param2=value2&Param1=value1", &[], "");
+        assert_eq!(signature, "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500");
+    }
+
+    #[test]
+    fn get_vanilla_empty_query_key() {
+        let (_, signature) = suite("GET", "https://example.amazonaws.com/?Param1=value1", &[], "");
+        assert_eq!(signature, "a67d582fa61cc504c4bae71f336f98b97f1ea3c7a6bfe1b6e45aec72011b9aeb");
+    }
+
+    #[test]
+    fn post_vanilla() {
+        let (_, signature) = suite("POST", "https://example.amazonaws.com/", &[], "");
+        assert_eq!(signature, "5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b");
+    }
+
+    #[test]
+    fn post_vanilla_query() {
+        let (_, signature) = suite("POST", "https://example.amazonaws.com/?Param1=value1", &[], "");
+        assert_eq!(signature, "28038455d6de14eafc1f9222cf5aa6f1a96197d7deb8263271d420d138af7f11");
+    }
+
+    #[test]
+    fn post_x_www_form_urlencoded() {
+        let (signed, signature) = suite(
+            "POST",
+            "https://example.amazonaws.com/",
+            &[("content-type", "application/x-www-form-urlencoded")],
+            "Param1=value1",
+        );
+        assert_eq!(signed, "content-type;host;x-amz-date");
+        assert_eq!(signature, "ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a");
+    }
+
+    #[test]
+    fn streamed_body_is_unsigned_only_for_s3() {
+        let stream = || iced::futures::stream::iter([Ok::<_, std::io::Error>("chunk")]);
+        let build = || {
+            reqwest::Client::new()
+                .put("https://bucket.s3.amazonaws.com/key")
+                .body(reqwest::Body::wrap_stream(stream()))
+                .build()
+                .unwrap()
+        };
+
+        let mut request = build();
+        let s3 = SigV4Config { service: "s3".to_string(), ..config() };
+        sign(&s3, &mut request, UNIX_EPOCH + NOW).unwrap();
+        assert_eq!(request.headers()["x-amz-content-sha256"], "UNSIGNED-PAYLOAD");
+
+        let mut request = build();
+        let error = sign(&config(), &mut request, UNIX_EPOCH + NOW).unwrap_err();
+        assert!(error.contains("only s3 accepts UNSIGNED-PAYLOAD"), "{}", error);
+    }
+}
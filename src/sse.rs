@@ -0,0 +1,526 @@
+// Server-Sent Events: запрос уходит как обычный HTTP, а тело ответа читается потоком
+// в iced Subscription и разбирается на события по мере прихода
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::widget::{button, checkbox, column, container, row, scrollable, space, text, text_input};
+use iced::{Center, Element, Fill, Font};
+
+use crate::auth::Auth;
+use crate::body::RequestBody;
+use crate::settings::ClientSettings;
+use crate::websocket::timestamp;
+use crate::{HeaderParam, Message};
+
+// Больше событий таблица не держит - старые уходят
+const EVENT_LIMIT: usize = 1000;
+
+// Пауза перед переподключением, пока сервер не прислал свою (retry:)
+const DEFAULT_RETRY_MS: u64 = 3000;
+
+// Поток событий узнаём по Content-Type ответа
+pub fn is_event_stream(content_type: &str) -> bool {
+    content_type
+        .trim_start()
+        .to_ascii_lowercase()
+        .starts_with("text/event-stream")
+}
+
+// Запрос, открывающий поток - уже с переменными и авторизацией
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: reqwest::Method,
+    pub url: String,
+    pub headers: Vec<HeaderParam>,
+    pub body: RequestBody,
+    pub send_body: bool,
+    pub settings: ClientSettings,
+    pub signing: Auth,
+}
+
+// Ответ text/event-stream, уже открытый обычным запросом: режим SSE дочитывает его,
+// а не отправляет запрос второй раз. Забрать можно только однажды
+#[derive(Debug, Clone, Default)]
+pub struct OpenStream(Arc<Mutex<Option<reqwest::Response>>>);
+
+impl OpenStream {
+    pub fn new(response: reqwest::Response) -> Self {
+        Self(Arc::new(Mutex::new(Some(response))))
+    }
+
+    fn take(&self) -> Option<reqwest::Response> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+// Параметры подключения. Новый id - новое соединение
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: u64,
+    pub request: Request,
+    pub opened: Option<OpenStream>, // Первое подключение - этот ответ вместо запроса
+    pub last_event_id: String, // Уходит в Last-Event-ID, если не пустой
+    pub reconnect: bool,
+}
+
+// Подписку различаем только по id
+impl Hash for Session {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SseEvent {
+    pub time: SystemTime,
+    pub event: String,
+    pub id: String,
+    pub data: String,
+}
+
+// Что даёт разбор очередного куска потока
+#[derive(Debug, Clone)]
+enum Parsed {
+    Event(SseEvent),
+    Retry(u64),
+}
+
+// Разбор text/event-stream: строки копятся до пустой, которая и отправляет событие
+#[derive(Debug, Default)]
+struct Parser {
+    line: Vec<u8>,
+    skip_lf: bool, // Кусок кончился на \r - \n в начале следующего ту же строку не закрывает
+    event: String,
+    data: String,
+    last_event_id: String,
+}
+
+impl Parser {
+    fn new(last_event_id: String) -> Self {
+        Self { last_event_id, ..Self::default() }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) -> Vec<Parsed> {
+        let mut parsed = Vec::new();
+        for &byte in bytes {
+            if std::mem::take(&mut self.skip_lf) && byte == b'\n' {
+                continue;
+            }
+            match byte {
+                b'\r' | b'\n' => {
+                    self.skip_lf = byte == b'\r';
+                    let line = std::mem::take(&mut self.line);
+                    parsed.extend(self.process_line(&String::from_utf8_lossy(&line)));
+                }
+                _ => self.line.push(byte),
+            }
+        }
+        parsed
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<Parsed> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        // ":" в начале - комментарий (часто так держат соединение живым)
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            "retry" if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) => {
+                return value.parse().ok().map(Parsed::Retry);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    // Без data событие не отправляется, но id остаётся
+    fn dispatch(&mut self) -> Option<Parsed> {
+        let event = std::mem::take(&mut self.event);
+        let mut data = std::mem::take(&mut self.data);
+        if data.is_empty() {
+            return None;
+        }
+        data.pop();
+        Some(Parsed::Event(SseEvent {
+            time: SystemTime::now(),
+            event: if event.is_empty() { "message".to_string() } else { event },
+            id: self.last_event_id.clone(),
+            data,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Opened(u16),
+    Received(SseEvent),
+    Retry(u64),
+    Reconnecting(String), // Почему оборвалось и когда пробуем снова
+    Closed(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum Status {
+    #[default]
+    Idle,
+    Connecting,
+    Open(u16),
+    Reconnecting(String),
+    Closed(String),
+    Failed(String),
+}
+
+// Состояние режима SSE
+#[derive(Debug)]
+pub struct Panel {
+    pub session: Option<Session>, // Есть - подписка держит поток
+    pub status: Status,
+    pub events: Vec<SseEvent>,
+    pub last_event_id: String,
+    pub auto_reconnect: bool, // Применяется к следующему подключению
+    pub retry_ms: u64,
+    next_id: u64,
+}
+
+impl Default for Panel {
+    fn default() -> Self {
+        Self {
+            session: None,
+            status: Status::Idle,
+            events: Vec::new(),
+            last_event_id: String::new(),
+            auto_reconnect: true,
+            retry_ms: DEFAULT_RETRY_MS,
+            next_id: 0,
+        }
+    }
+}
+
+impl Panel {
+    // Новый поток; прежний закрывается вместе с подпиской
+    pub fn connect(&mut self, request: Request, opened: Option<OpenStream>) {
+        self.next_id += 1;
+        self.session = Some(Session {
+            id: self.next_id,
+            request,
+            opened,
+            last_event_id: self.last_event_id.trim().to_string(),
+            reconnect: self.auto_reconnect,
+        });
+        self.status = Status::Connecting;
+    }
+
+    pub fn disconnect(&mut self) {
+        self.session = None;
+        self.status = Status::Closed("Disconnected".to_string());
+    }
+
+    pub fn handle(&mut self, event: Event) {
+        match event {
+            Event::Opened(status) => self.status = Status::Open(status),
+            Event::Received(event) => {
+                self.last_event_id = event.id.clone();
+                if self.events.len() >= EVENT_LIMIT {
+                    self.events.remove(0);
+                }
+                self.events.push(event);
+            }
+            Event::Retry(ms) => self.retry_ms = ms,
+            Event::Reconnecting(reason) => self.status = Status::Reconnecting(reason),
+            Event::Closed(reason) => {
+                self.session = None;
+                self.status = Status::Closed(reason);
+            }
+            Event::Failed(error) => {
+                self.session = None;
+                self.status = Status::Failed(error);
+            }
+        }
+    }
+}
+
+// Отправляем запрос с Accept и Last-Event-ID
+async fn open(request: &Request, last_event_id: &str) -> Result<reqwest::Response, String> {
+    let mut headers = request.headers.clone();
+    if !headers.iter().any(|header| header.key.eq_ignore_ascii_case("accept")) {
+        headers.push(HeaderParam::new("Accept".to_string(), "text/event-stream".to_string()));
+    }
+    if !last_event_id.is_empty() {
+        headers.retain(|header| !header.key.eq_ignore_ascii_case("last-event-id"));
+        headers.push(HeaderParam::new("Last-Event-ID".to_string(), last_event_id.to_string()));
+    }
+
+    // Поток идёт сколько угодно - общий таймаут к нему не применяем
+    let settings = ClientSettings { total_timeout_ms: None, ..request.settings.clone() };
+    let (response, _) = crate::open_response(
+        request.method.clone(),
+        request.url.clone(),
+        headers,
+        request.body.clone(),
+        request.send_body,
+        settings,
+        request.signing.clone(),
+    )
+    .await?;
+    Ok(response)
+}
+
+// Поток событий для Subscription::run_with. Обрыв - переподключение через retry
+// с последним id; ответ не 200 или не text/event-stream - конец
+pub fn connect(session: &Session) -> impl Stream<Item = Event> {
+    let session = session.clone();
+
+    iced::stream::channel(100, async move |mut output: mpsc::Sender<Event>| {
+        let mut last_event_id = session.last_event_id.clone();
+        let mut retry_ms = DEFAULT_RETRY_MS;
+        let mut opened = false; // После первого открытия сетевые ошибки тоже переживаем
+
+        loop {
+            // Ответ обычного запроса живёт под его общим таймаутом: оборвётся по нему -
+            // переподключимся уже своим запросом без таймаута
+            let response = match session.opened.as_ref().and_then(OpenStream::take) {
+                Some(response) => Ok(response),
+                None => open(&session.request, &last_event_id).await,
+            };
+            let reason = match response {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    let content_type = response
+                        .headers()
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                    if status != 200 {
+                        let _ = output.send(Event::Failed(format!("Server answered {}", status))).await;
+                        return;
+                    }
+                    if !is_event_stream(&content_type) {
+                        let error = format!("Response is not text/event-stream: {}", content_type);
+                        let _ = output.send(Event::Failed(error)).await;
+                        return;
+                    }
+                    opened = true;
+                    let _ = output.send(Event::Opened(status)).await;
+
+                    let mut parser = Parser::new(last_event_id.clone());
+                    let mut body = response.bytes_stream();
+                    let reason = loop {
+                        match body.next().await {
+                            Some(Ok(chunk)) => {
+                                for parsed in parser.feed(&chunk) {
+                                    let event = match parsed {
+                                        Parsed::Event(event) => Event::Received(event),
+                                        Parsed::Retry(ms) => {
+                                            retry_ms = ms;
+                                            Event::Retry(ms)
+                                        }
+                                    };
+                                    let _ = output.send(event).await;
+                                }
+                            }
+                            Some(Err(e)) => break format!("Stream failed: {}", e),
+                            None => break "Stream ended".to_string(),
+                        }
+                    };
+                    last_event_id = parser.last_event_id;
+                    reason
+                }
+                Err(error) if opened => error,
+                Err(error) => {
+                    let _ = output.send(Event::Failed(error)).await;
+                    return;
+                }
+            };
+
+            if !session.reconnect {
+                let _ = output.send(Event::Closed(reason)).await;
+                return;
+            }
+            let reason = format!("{}, reconnecting in {} ms", reason, retry_ms);
+            let _ = output.send(Event::Reconnecting(reason)).await;
+            tokio::time::sleep(Duration::from_millis(retry_ms)).await;
+        }
+    })
+}
+
+// Таблица событий вместо панели ответа
+pub fn events_view(panel: &Panel) -> Element<'_, Message> {
+    let status = match &panel.status {
+        Status::Idle => text("● Disconnected").style(text::secondary),
+        Status::Connecting => text("● Connecting...").style(text::secondary),
+        Status::Open(status) => text(format!("● Open ({})", status)).style(text::success),
+        Status::Reconnecting(reason) => text(format!("● {}", reason)).style(text::warning),
+        Status::Closed(reason) => text(format!("● {}", reason)).style(text::secondary),
+        Status::Failed(error) => text(format!("● {}", error)).style(text::danger),
+    }
+    .size(14);
+
+    let header = row![
+        text("Time").size(12).width(90),
+        text("Event").size(12).width(120),
+        text("ID").size(12).width(100),
+        text("Data").size(12).width(Fill),
+    ]
+    .spacing(8);
+
+    let rows: Vec<Element<Message>> = panel
+        .events
+        .iter()
+        .map(|event| {
+            row![
+                text(timestamp(event.time))
+                    .size(12)
+                    .font(Font::MONOSPACE)
+                    .style(text::secondary)
+                    .width(90),
+                text(&event.event).size(12).style(text::primary).width(120),
+                text(&event.id).size(12).font(Font::MONOSPACE).width(100),
+                text(&event.data).size(12).font(Font::MONOSPACE).width(Fill),
+            ]
+            .spacing(8)
+            .into()
+        })
+        .collect();
+
+    column![
+        row![
+            status,
+            space().width(Fill),
+            text(format!("{} events, retry {} ms", panel.events.len(), panel.retry_ms))
+                .size(11)
+                .style(text::secondary),
+            button(text("Clear").size(12))
+                .on_press(Message::SseClear)
+                .padding([3, 8])
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(Center),
+        row![
+            text("Last-Event-ID:").size(12),
+            text_input("none", &panel.last_event_id)
+                .on_input(Message::SseLastEventIdChanged)
+                .size(12)
+                .width(200)
+                .padding(3),
+            checkbox(panel.auto_reconnect)
+                .label("Auto-reconnect")
+                .on_toggle(Message::SseAutoReconnectToggled)
+                .text_size(12),
+            space().width(Fill),
+            text("Times in UTC").size(11).style(text::secondary),
+        ]
+        .spacing(10)
+        .align_y(Center),
+        container(
+            column![
+                header,
+                scrollable(column(rows).spacing(4))
+                    .height(300)
+                    .width(Fill)
+                    .anchor_bottom(),
+            ]
+            .spacing(6)
+        )
+        .padding(10)
+        .style(container::bordered_box),
+    ]
+    .spacing(10)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (event, id, data) или retry - без времени получения
+    fn feed(parser: &mut Parser, chunks: &[&str]) -> Vec<String> {
+        chunks
+            .iter()
+            .flat_map(|chunk| parser.feed(chunk.as_bytes()))
+            .map(|parsed| match parsed {
+                Parsed::Event(event) => format!("{}|{}|{}", event.event, event.id, event.data),
+                Parsed::Retry(ms) => format!("retry {}", ms),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn multi_line_data_and_event_name() {
+        let mut parser = Parser::new(String::new());
+        let parsed = feed(&mut parser, &["data: first\ndata:second\ndata:  third\n\nevent: tick\ndata: x\n\n"]);
+        assert_eq!(parsed, ["message||first\nsecond\n third", "tick||x"]);
+    }
+
+    #[test]
+    fn id_is_kept_between_events() {
+        let mut parser = Parser::new("5".to_string());
+        let parsed = feed(
+            &mut parser,
+            &["data: a\n\n", "id: 7\ndata: b\n\n", "data: c\n\n", "id: bad\0id\ndata: d\n\n"],
+        );
+        assert_eq!(parsed, ["message|5|a", "message|7|b", "message|7|c", "message|7|d"]);
+
+        // id без data события не даёт, но Last-Event-ID меняет
+        assert!(feed(&mut parser, &["id: 9\n\n"]).is_empty());
+        assert_eq!(parser.last_event_id, "9");
+        assert!(feed(&mut parser, &["id\n\n"]).is_empty());
+        assert_eq!(parser.last_event_id, "");
+    }
+
+    #[test]
+    fn retry_accepts_only_digits() {
+        let mut parser = Parser::new(String::new());
+        let parsed = feed(&mut parser, &["retry: 1500\nretry: 2s\nretry:\nretry: -1\n\n"]);
+        assert_eq!(parsed, ["retry 1500"]);
+    }
+
+    #[test]
+    fn comments_and_unknown_fields_are_ignored() {
+        let mut parser = Parser::new(String::new());
+        let parsed = feed(&mut parser, &[": keep-alive\n\nfoo: bar\ndata\ndata: x\n\n"]);
+        // "data" без двоеточия - пустая строка данных
+        assert_eq!(parsed, ["message||\nx"]);
+    }
+
+    #[test]
+    fn crlf_and_cr_line_endings() {
+        let mut parser = Parser::new(String::new());
+        assert_eq!(feed(&mut parser, &["data: a\r\ndata: b\r\n\r\n"]), ["message||a\nb"]);
+        assert_eq!(feed(&mut parser, &["data: c\rdata: d\r\r"]), ["message||c\nd"]);
+    }
+
+    #[test]
+    fn chunks_split_mid_line() {
+        let mut parser = Parser::new(String::new());
+        assert!(feed(&mut parser, &["da", "ta: hel", "lo\n"]).is_empty());
+        assert_eq!(feed(&mut parser, &["\n"]), ["message||hello"]);
+
+        // \r в конце куска, \n в начале следующего - один перевод строки, не два
+        assert!(feed(&mut parser, &["data: a\r", "\ndata: b\r", "\n"]).is_empty());
+        assert_eq!(feed(&mut parser, &["\r", "\n"]), ["message||a\nb"]);
+    }
+
+    #[test]
+    fn event_without_blank_line_is_not_dispatched() {
+        let mut parser = Parser::new(String::new());
+        assert!(feed(&mut parser, &["data: partial\n"]).is_empty());
+    }
+}
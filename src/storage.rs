@@ -0,0 +1,110 @@
+// Запись файлов в фоне: сериализация и запись не блокируют интерфейс.
+// Снимки нумеруются при создании задачи, устаревший снимок поверх нового не пишем
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+// Пауза после последней правки, после которой файл пишем
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+static NEXT_SNAPSHOT: AtomicU64 = AtomicU64::new(1);
+// Последний записанный снимок каждого файла
+static WRITTEN: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+pub fn write(
+    path: PathBuf,
+    serialize: impl FnOnce() -> Result<String, String> + Send + 'static,
+) -> impl Future<Output = Result<(), String>> {
+    schedule(path, false, serialize)
+}
+
+// Файл с секретами (токены): на unix доступен только владельцу (0600)
+pub fn write_private(
+    path: PathBuf,
+    serialize: impl FnOnce() -> Result<String, String> + Send + 'static,
+) -> impl Future<Output = Result<(), String>> {
+    schedule(path, true, serialize)
+}
+
+// Номер берём сразу, а не при первом poll - порядок задач не важен
+fn schedule(
+    path: PathBuf,
+    private: bool,
+    serialize: impl FnOnce() -> Result<String, String> + Send + 'static,
+) -> impl Future<Output = Result<(), String>> {
+    let snapshot = NEXT_SNAPSHOT.fetch_add(1, Ordering::Relaxed);
+
+    async move {
+        tokio::task::spawn_blocking(move || write_now(&path, snapshot, private, serialize()?))
+            .await
+            .map_err(|e| format!("Save task failed: {}", e))?
+    }
+}
+
+fn write_now(path: &PathBuf, snapshot: u64, private: bool, data: String) -> Result<(), String> {
+    let mut written = WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+    if written.get(path).is_some_and(|&last| last > snapshot) {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let failed = |e: std::io::Error| format!("Failed to write {}: {}", path.display(), e);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(failed)?;
+    // mode действует только при создании - старому файлу права меняем сами
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600)).map_err(failed)?;
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    file.write_all(data.as_bytes()).map_err(failed)?;
+    written.insert(path.clone(), snapshot);
+    Ok(())
+}
+
+// Отложенное сохранение: правки при наборе копятся и пишутся,
+// когда ввод затих на SAVE_DELAY (или при выходе)
+#[derive(Debug, Default)]
+pub struct Debounce {
+    revision: u64, // Номер последней правки
+    saved: u64,    // Номер правки, которую уже отправили на запись
+}
+
+impl Debounce {
+    // Отмечаем правку, future вернёт её номер после паузы
+    pub fn changed(&mut self) -> impl Future<Output = u64> {
+        self.revision += 1;
+        let revision = self.revision;
+        async move {
+            tokio::time::sleep(SAVE_DELAY).await;
+            revision
+        }
+    }
+
+    // Пора писать: после этой правки новых не было
+    pub fn is_due(&mut self, revision: u64) -> bool {
+        revision == self.revision && self.flush()
+    }
+
+    // Есть ли несохранённые правки (при выходе), дальше считаем их сохранёнными
+    pub fn flush(&mut self) -> bool {
+        let pending = self.saved != self.revision;
+        self.saved = self.revision;
+        pending
+    }
+}
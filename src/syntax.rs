@@ -0,0 +1,1058 @@
+// Синтаксис текста в редакторе тела: подсветка, проверка и prettify/minify
+// для JSON, XML, HTML, YAML и GraphQL
+use std::ops::Range;
+
+use iced::advanced::text::highlighter::{self, Highlighter};
+use iced::{Color, Font, Theme};
+use quick_xml::events::{BytesText, Event};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Json,
+    Xml,
+    Html,
+    Yaml,
+    GraphQl,
+    Plain, // Без проверки и форматирования
+}
+
+impl Syntax {
+    // Токен для iced::highlighter (syntect). GraphQL в syntect нет - у него свой
+    pub fn token(self) -> &'static str {
+        match self {
+            Syntax::Json => "json",
+            Syntax::Xml => "xml",
+            Syntax::Html => "html",
+            Syntax::Yaml => "yaml",
+            Syntax::GraphQl | Syntax::Plain => "txt",
+        }
+    }
+
+    pub fn is_plain(self) -> bool {
+        self == Syntax::Plain
+    }
+
+    // Пустой текст считается валидным
+    pub fn validate(self, text: &str) -> Result<(), String> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        match self {
+            Syntax::Json => serde_json::from_str::<serde_json::Value>(text)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Syntax::Xml => xml_events(text).map(|_| ()),
+            Syntax::Html => html_check(&html_tokens(text)?),
+            Syntax::Yaml => yaml_documents(text).map(|_| ()),
+            Syntax::GraphQl => graphql_check(&graphql_tokens(text)?),
+            Syntax::Plain => Ok(()),
+        }
+    }
+
+    pub fn prettify(self, text: &str) -> Result<String, String> {
+        match self {
+            Syntax::Json => {
+                let value: serde_json::Value =
+                    serde_json::from_str(text).map_err(|e| e.to_string())?;
+                serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+            }
+            Syntax::Xml => xml_write(text, true),
+            Syntax::Html => Ok(html_pretty(&html_tokens(text)?)),
+            Syntax::Yaml => {
+                yaml_check_lossless(text)?;
+                let documents = yaml_documents(text)?
+                    .iter()
+                    .map(serde_yaml::to_string)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?;
+                Ok(documents.join("---\n"))
+            }
+            Syntax::GraphQl => Ok(graphql_pretty(&graphql_tokens(text)?)),
+            Syntax::Plain => Ok(text.to_string()),
+        }
+    }
+
+    pub fn minify(self, text: &str) -> Result<String, String> {
+        match self {
+            Syntax::Json => {
+                let value: serde_json::Value =
+                    serde_json::from_str(text).map_err(|e| e.to_string())?;
+                serde_json::to_string(&value).map_err(|e| e.to_string())
+            }
+            Syntax::Xml => xml_write(text, false),
+            Syntax::Html => Ok(html_minify(&html_tokens(text)?)),
+            // JSON - валидный YAML, короче записи не бывает
+            Syntax::Yaml => {
+                yaml_check_lossless(text)?;
+                let documents = yaml_documents(text)?
+                    .iter()
+                    .map(serde_json::to_string)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?;
+                Ok(documents.join("\n---\n"))
+            }
+            Syntax::GraphQl => Ok(graphql_minify(&graphql_tokens(text)?)),
+            Syntax::Plain => Ok(text.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Syntax {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Syntax::Json => write!(f, "JSON"),
+            Syntax::Xml => write!(f, "XML"),
+            Syntax::Html => write!(f, "HTML"),
+            Syntax::Yaml => write!(f, "YAML"),
+            Syntax::GraphQl => write!(f, "GraphQL"),
+            Syntax::Plain => write!(f, "text"),
+        }
+    }
+}
+
+// ---------- XML ----------
+
+// Все события документа; теги проверяем на парность и единственный корень
+fn xml_events(text: &str) -> Result<Vec<Event<'_>>, String> {
+    let mut reader = quick_xml::Reader::from_str(text);
+
+    let mut events = Vec::new();
+    let mut depth = 0usize;
+    let mut roots = 0usize;
+    loop {
+        let position = reader.buffer_position();
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("{} at byte {}", e, reader.error_position()))?;
+        match &event {
+            Event::Start(_) | Event::Empty(_) => {
+                if depth == 0 {
+                    roots += 1;
+                    if roots > 1 {
+                        return Err(format!("Second root element at byte {}", position));
+                    }
+                }
+                if matches!(event, Event::Start(_)) {
+                    depth += 1;
+                }
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            // Переводы строк вокруг корня - можно
+            Event::Text(text) if depth == 0 && text.iter().all(u8::is_ascii_whitespace) => {}
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) if depth == 0 => {
+                return Err(format!("Text outside the root element at byte {}", position));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        events.push(event);
+    }
+
+    if depth > 0 {
+        return Err("Unexpected end of document: unclosed element".to_string());
+    }
+    if roots == 0 {
+        return Err("No root element".to_string());
+    }
+    Ok(events)
+}
+
+// Отступы меняем только между элементами, сам текст не трогаем
+fn xml_write(text: &str, pretty: bool) -> Result<String, String> {
+    // Текст со ссылками (a &amp; b) reader отдаёт кусками - склеиваем обратно,
+    // иначе writer вставит отступ посреди текста
+    let mut events: Vec<Event> = Vec::new();
+    for event in xml_events(text)? {
+        let piece = match &event {
+            Event::Text(text) => Some(String::from_utf8_lossy(text).into_owned()),
+            Event::GeneralRef(name) => Some(format!("&{};", String::from_utf8_lossy(name))),
+            _ => None,
+        };
+        match (piece, events.last_mut()) {
+            (Some(piece), Some(Event::Text(last))) => {
+                let joined = format!("{}{}", String::from_utf8_lossy(last), piece);
+                *last = BytesText::from_escaped(joined);
+            }
+            (Some(piece), _) => events.push(Event::Text(BytesText::from_escaped(piece))),
+            (None, _) => events.push(event),
+        }
+    }
+
+    let mut writer = if pretty {
+        quick_xml::Writer::new_with_indent(Vec::new(), b' ', 2)
+    } else {
+        quick_xml::Writer::new(Vec::new())
+    };
+    let blank = |event: &Event| {
+        matches!(event, Event::Text(text) if text.iter().all(u8::is_ascii_whitespace))
+    };
+    let mut events = events.into_iter().peekable();
+    let mut after_start = false;
+    while let Some(event) = events.next() {
+        // Пробелы между элементами - прежние отступы, а <a> </a> - содержимое
+        let keep = !blank(&event) || (after_start && matches!(events.peek(), Some(Event::End(_))));
+        after_start = matches!(event, Event::Start(_));
+        if keep {
+            writer.write_event(event).map_err(|e| e.to_string())?;
+        }
+    }
+    String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+}
+
+// ---------- YAML ----------
+
+// Документы через "---" разбираем по отдельности
+fn yaml_documents(text: &str) -> Result<Vec<serde_yaml::Value>, String> {
+    serde_yaml::Deserializer::from_str(text)
+        .map(|document| serde_yaml::Value::deserialize(document).map_err(|e| e.to_string()))
+        .collect()
+}
+
+// Форматируем через serde: комментарии пропадут, якоря &name и ссылки *name
+// развернутся в копии. Такой YAML не трогаем, а говорим почему
+fn yaml_check_lossless(text: &str) -> Result<(), String> {
+    for (number, line) in text.lines().enumerate() {
+        let mut quote = None;
+        let mut previous = ' ';
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            let at_token = previous.is_whitespace() || matches!(previous, '[' | '{' | ',');
+            match (quote, c) {
+                (Some('"'), '\\') => {
+                    chars.next();
+                }
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') if at_token => quote = Some(c),
+                (None, '#') if at_token => {
+                    return Err(format!(
+                        "Comment on line {} would be lost, format YAML without comments",
+                        number + 1
+                    ));
+                }
+                (None, '&' | '*')
+                    if at_token
+                        && chars.peek().is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '-')) =>
+                {
+                    return Err(format!(
+                        "Anchor or alias on line {} would be expanded, format YAML without them",
+                        number + 1
+                    ));
+                }
+                _ => {}
+            }
+            previous = c;
+        }
+    }
+    Ok(())
+}
+
+// ---------- HTML ----------
+
+// HTML не XML: теги без пары, необязательные закрывающие, script/style с сырым текстом
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+    "track", "wbr",
+];
+const OPTIONAL_END: &[&str] = &[
+    "html", "head", "body", "p", "li", "dt", "dd", "tr", "td", "th", "thead", "tbody", "tfoot",
+    "option", "optgroup", "colgroup", "caption", "rt", "rp",
+];
+// Содержимое выводим как есть
+const RAW_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+
+#[derive(Debug)]
+enum HtmlToken<'a> {
+    Open { name: String, source: &'a str, closed: bool }, // closed: <br/> или void элемент
+    Close { name: String, source: &'a str },
+    Text(&'a str),
+    Other(&'a str), // Комментарий, <!DOCTYPE>, <?...?>
+}
+
+fn html_tokens(text: &str) -> Result<Vec<HtmlToken<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while position < text.len() {
+        let rest = &text[position..];
+        let Some(start) = rest.find('<') else {
+            tokens.push(HtmlToken::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(HtmlToken::Text(&rest[..start]));
+        }
+        let tag = &rest[start..];
+        let unterminated = || format!("Unterminated tag at byte {}", position + start);
+
+        if tag.starts_with("<!--") {
+            let end = tag.find("-->").ok_or_else(unterminated)? + 3;
+            tokens.push(HtmlToken::Other(&tag[..end]));
+            position += start + end;
+            continue;
+        }
+        if tag.starts_with("<!") || tag.starts_with("<?") {
+            let end = tag.find('>').ok_or_else(unterminated)? + 1;
+            tokens.push(HtmlToken::Other(&tag[..end]));
+            position += start + end;
+            continue;
+        }
+
+        let closing = tag.starts_with("</");
+        let name: String = tag[if closing { 2 } else { 1 }..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == ':')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if name.is_empty() {
+            // Одиночный "<" в тексте
+            tokens.push(HtmlToken::Text(&tag[..1]));
+            position += start + 1;
+            continue;
+        }
+
+        // Конец тега: ">" вне кавычек атрибутов
+        let mut quote = None;
+        let end = tag
+            .char_indices()
+            .find(|&(_, c)| match quote {
+                Some(q) if c == q => {
+                    quote = None;
+                    false
+                }
+                Some(_) => false,
+                None if c == '"' || c == '\'' => {
+                    quote = Some(c);
+                    false
+                }
+                None => c == '>',
+            })
+            .map(|(index, _)| index + 1)
+            .ok_or_else(unterminated)?;
+        let source = &tag[..end];
+        position += start + end;
+
+        if closing {
+            tokens.push(HtmlToken::Close { name, source });
+            continue;
+        }
+
+        let closed = source.ends_with("/>") || VOID_ELEMENTS.contains(&name.as_str());
+        let raw = RAW_ELEMENTS.contains(&name.as_str()) && !closed;
+        tokens.push(HtmlToken::Open { name: name.clone(), source, closed });
+
+        // Содержимое script/style/pre/textarea - до закрывающего тега
+        if raw {
+            let rest = &text[position..];
+            let end = rest
+                .to_ascii_lowercase()
+                .find(&format!("</{}", name))
+                .ok_or_else(|| format!("Unclosed <{}>", name))?;
+            if end > 0 {
+                tokens.push(HtmlToken::Text(&rest[..end]));
+            }
+            position += end;
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Каждому закрывающему тегу - открытый; незакрытыми могут остаться только
+// элементы с необязательным концом
+fn html_check(tokens: &[HtmlToken]) -> Result<(), String> {
+    let mut stack: Vec<&str> = Vec::new();
+    for token in tokens {
+        match token {
+            HtmlToken::Open { name, closed: false, .. } => stack.push(name),
+            HtmlToken::Close { name, .. } => {
+                if VOID_ELEMENTS.contains(&name.as_str()) {
+                    continue;
+                }
+                let Some(index) = stack.iter().rposition(|open| open == name) else {
+                    return Err(format!("Unexpected </{}>", name));
+                };
+                if let Some(open) = stack[index + 1..]
+                    .iter()
+                    .find(|open| !OPTIONAL_END.contains(open))
+                {
+                    return Err(format!("</{}> closes unclosed <{}>", name, open));
+                }
+                stack.truncate(index);
+            }
+            _ => {}
+        }
+    }
+
+    match stack.iter().find(|open| !OPTIONAL_END.contains(open)) {
+        Some(open) => Err(format!("Unclosed <{}>", open)),
+        None => Ok(()),
+    }
+}
+
+// Элементы, которые закрываются следующим таким же (<li>one<li>two)
+const SIBLING_CLOSED: &[&str] = &["p", "li", "dt", "dd", "tr", "td", "th", "option"];
+
+// Каждый тег и кусок текста с новой строки, отступ по вложенности.
+// Тег с коротким текстом внутри (<b>text</b>) остаётся одной строкой
+fn html_pretty(tokens: &[HtmlToken]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut raw = false;
+    let indent = |depth: usize| "  ".repeat(depth);
+
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            HtmlToken::Open { name, source, closed } => {
+                if SIBLING_CLOSED.contains(&name.as_str()) && stack.last() == Some(&name.as_str()) {
+                    stack.pop();
+                }
+                if let (
+                    false,
+                    Some(HtmlToken::Text(text)),
+                    Some(HtmlToken::Close { name: close, source: end }),
+                ) = (closed, tokens.get(index + 1), tokens.get(index + 2))
+                {
+                    if close == name && !text.contains('\n') {
+                        lines.push(format!("{}{}{}{}", indent(stack.len()), source, text.trim(), end));
+                        index += 3;
+                        continue;
+                    }
+                }
+                lines.push(format!("{}{}", indent(stack.len()), source));
+                if !closed {
+                    stack.push(name);
+                    raw = RAW_ELEMENTS.contains(&name.as_str());
+                }
+            }
+            // Закрывающий тег закрывает и всё, что осталось открытым внутри
+            HtmlToken::Close { name, source } => {
+                if let Some(open) = stack.iter().rposition(|open| open == name) {
+                    stack.truncate(open);
+                }
+                raw = false;
+                lines.push(format!("{}{}", indent(stack.len()), source));
+            }
+            // Сырое содержимое не трогаем
+            HtmlToken::Text(text) if raw => lines.push(text.trim_matches('\n').to_string()),
+            HtmlToken::Text(text) => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !text.is_empty() {
+                    lines.push(format!("{}{}", indent(stack.len()), text));
+                }
+            }
+            HtmlToken::Other(source) => lines.push(format!("{}{}", indent(stack.len()), source)),
+        }
+        index += 1;
+    }
+
+    lines.join("\n")
+}
+
+// Пробелы в тексте схлопываем, пустой текст между тегами убираем
+fn html_minify(tokens: &[HtmlToken]) -> String {
+    let mut output = String::new();
+    let mut raw = false;
+
+    for token in tokens {
+        match token {
+            HtmlToken::Open { name, source, closed } => {
+                output.push_str(source);
+                raw = !closed && RAW_ELEMENTS.contains(&name.as_str());
+            }
+            HtmlToken::Close { source, .. } => {
+                output.push_str(source);
+                raw = false;
+            }
+            HtmlToken::Text(text) if raw => output.push_str(text),
+            HtmlToken::Text(text) => {
+                if !text.trim().is_empty() {
+                    if text.starts_with(char::is_whitespace) {
+                        output.push(' ');
+                    }
+                    output.push_str(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+                    if text.ends_with(char::is_whitespace) {
+                        output.push(' ');
+                    }
+                }
+            }
+            HtmlToken::Other(source) => output.push_str(source),
+        }
+    }
+
+    output
+}
+
+// ---------- GraphQL ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphQlKind {
+    Name,
+    Variable,  // $id
+    Directive, // @include
+    Number,
+    String,
+    Comment,
+    Spread, // ...
+    Punct,  // { } ( ) [ ] : = ! | & ,
+}
+
+#[derive(Debug)]
+pub struct GraphQlToken<'a> {
+    pub kind: GraphQlKind,
+    pub text: &'a str,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+// Длина строки, начинающейся с " или """; None - строка не закрыта
+fn graphql_string_len(text: &str) -> Option<usize> {
+    if let Some(rest) = text.strip_prefix("\"\"\"") {
+        let mut index = 0;
+        while index < rest.len() {
+            if rest[index..].starts_with("\\\"\"\"") {
+                index += 4;
+            } else if rest[index..].starts_with("\"\"\"") {
+                return Some(3 + index + 3);
+            } else {
+                index += rest[index..].chars().next()?.len_utf8();
+            }
+        }
+        return None;
+    }
+
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(index + 1),
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn graphql_tokens(text: &str) -> Result<Vec<GraphQlToken<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while let Some(c) = text[position..].chars().next() {
+        let rest = &text[position..];
+        let (kind, len) = match c {
+            c if c.is_whitespace() || c == '\u{feff}' => {
+                position += c.len_utf8();
+                continue;
+            }
+            '#' => (GraphQlKind::Comment, rest.find('\n').unwrap_or(rest.len())),
+            '"' => (
+                GraphQlKind::String,
+                graphql_string_len(rest)
+                    .ok_or_else(|| format!("Unterminated string at byte {}", position))?,
+            ),
+            '.' if rest.starts_with("...") => (GraphQlKind::Spread, 3),
+            '$' | '@' => {
+                let len = rest[1..].find(|c| !is_name_char(c)).unwrap_or(rest.len() - 1);
+                if len == 0 {
+                    return Err(format!("Expected a name after '{}' at byte {}", c, position));
+                }
+                let kind = if c == '$' { GraphQlKind::Variable } else { GraphQlKind::Directive };
+                (kind, len + 1)
+            }
+            '-' | '0'..='9' => (
+                GraphQlKind::Number,
+                1 + rest[1..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')))
+                    .unwrap_or(rest.len() - 1),
+            ),
+            c if c.is_ascii_alphabetic() || c == '_' => (
+                GraphQlKind::Name,
+                rest.find(|c| !is_name_char(c)).unwrap_or(rest.len()),
+            ),
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '=' | '!' | '|' | '&' | ',' => {
+                (GraphQlKind::Punct, 1)
+            }
+            c => return Err(format!("Unexpected character '{}' at byte {}", c, position)),
+        };
+        tokens.push(GraphQlToken { kind, text: &rest[..len] });
+        position += len;
+    }
+
+    Ok(tokens)
+}
+
+// Скобки парные, документ не пустой
+fn graphql_check(tokens: &[GraphQlToken]) -> Result<(), String> {
+    let mut stack = Vec::new();
+    for token in tokens.iter().filter(|token| token.kind == GraphQlKind::Punct) {
+        match token.text {
+            "{" | "(" | "[" => stack.push(token.text),
+            close @ ("}" | ")" | "]") => {
+                let open = match close {
+                    "}" => "{",
+                    ")" => "(",
+                    _ => "[",
+                };
+                if stack.pop() != Some(open) {
+                    return Err(format!("Unexpected '{}'", close));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        return Err(format!("Unclosed '{}'", open));
+    }
+    if tokens.iter().all(|token| token.kind == GraphQlKind::Comment) {
+        return Err("Document has no definitions".to_string());
+    }
+    Ok(())
+}
+
+// Слова и значения, между которыми нужен пробел
+fn graphql_is_word(token: &GraphQlToken) -> bool {
+    matches!(
+        token.kind,
+        GraphQlKind::Name
+            | GraphQlKind::Variable
+            | GraphQlKind::Directive
+            | GraphQlKind::Number
+            | GraphQlKind::String
+    )
+}
+
+// Поля выборки - по одному на строку, аргументы и значения - в строку
+fn graphql_pretty(tokens: &[GraphQlToken]) -> String {
+    let mut output = String::new();
+    let mut depth = 0usize; // Вложенность выборок { }
+    let mut inline = 0usize; // Внутри ( ) или [ ], там { } - объект-значение
+    let mut previous: Option<&GraphQlToken> = None;
+
+    let newline = |output: &mut String, depth: usize| {
+        while output.ends_with(' ') {
+            output.pop();
+        }
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(&"  ".repeat(depth));
+    };
+    let at_line_start = |output: &String| output.is_empty() || output.trim_end_matches(' ').ends_with('\n');
+
+    for token in tokens {
+        match (token.kind, token.text) {
+            (GraphQlKind::Comment, text) => {
+                if !at_line_start(&output) {
+                    output.push(' ');
+                }
+                output.push_str(text);
+                newline(&mut output, depth);
+            }
+            (GraphQlKind::Punct, "{") if inline == 0 => {
+                if !at_line_start(&output) {
+                    output.push(' ');
+                }
+                output.push('{');
+                depth += 1;
+                newline(&mut output, depth);
+            }
+            (GraphQlKind::Punct, "}") if inline == 0 => {
+                depth = depth.saturating_sub(1);
+                newline(&mut output, depth);
+                output.push('}');
+                // Между определениями - пустая строка
+                if depth == 0 {
+                    output.push_str("\n\n");
+                } else {
+                    newline(&mut output, depth);
+                }
+            }
+            (GraphQlKind::Punct, open @ ("(" | "[")) => {
+                inline += 1;
+                output.push_str(open);
+            }
+            (GraphQlKind::Punct, close @ (")" | "]")) => {
+                inline = inline.saturating_sub(1);
+                output.push_str(close);
+            }
+            (GraphQlKind::Punct, "{") => output.push('{'),
+            (GraphQlKind::Punct, "}") => output.push('}'),
+            (GraphQlKind::Punct, ",") if inline == 0 => newline(&mut output, depth),
+            (GraphQlKind::Punct, ",") => output.push_str(", "),
+            (GraphQlKind::Punct, ":") => output.push_str(": "),
+            (GraphQlKind::Punct, "!") => output.push('!'),
+            (GraphQlKind::Punct, text) => {
+                if !at_line_start(&output) && !output.ends_with(' ') {
+                    output.push(' ');
+                }
+                output.push_str(text);
+                output.push(' ');
+            }
+            _ => {
+                // Новое поле выборки: имя после законченного поля
+                let new_field = inline == 0
+                    && depth > 0
+                    && matches!(token.kind, GraphQlKind::Name | GraphQlKind::Spread)
+                    && previous.is_some_and(|previous| {
+                        matches!(previous.text, ")" | "!")
+                            || (graphql_is_word(previous)
+                                && previous.kind != GraphQlKind::Directive
+                                && previous.text != "on")
+                            || (previous.kind == GraphQlKind::Directive
+                                && token.kind == GraphQlKind::Name)
+                    });
+                if new_field {
+                    newline(&mut output, depth);
+                } else if !at_line_start(&output)
+                    && !output.ends_with(' ')
+                    && previous.is_some_and(|previous| {
+                        graphql_is_word(previous)
+                            || matches!(previous.text, ")" | "]" | "}" | "!")
+                            || token.kind == GraphQlKind::Directive
+                            || (previous.kind == GraphQlKind::Spread && token.text == "on")
+                    })
+                {
+                    output.push(' ');
+                }
+                output.push_str(token.text);
+            }
+        }
+        if token.kind != GraphQlKind::Comment {
+            previous = Some(token);
+        }
+    }
+
+    output.trim_end().to_string()
+}
+
+// Без комментариев и запятых, пробел только между двумя словами
+fn graphql_minify(tokens: &[GraphQlToken]) -> String {
+    let mut output = String::new();
+    let mut previous: Option<&GraphQlToken> = None;
+
+    for token in tokens {
+        if token.kind == GraphQlKind::Comment || token.text == "," {
+            continue;
+        }
+        if previous.is_some_and(|previous| {
+            graphql_is_word(previous) && graphql_is_word(token) && token.kind != GraphQlKind::Directive
+        }) {
+            output.push(' ');
+        }
+        output.push_str(token.text);
+        previous = Some(token);
+    }
+
+    output
+}
+
+// Подсветка GraphQL: syntect его не знает, размечаем сами по токенам.
+// Многострочные """строки""" переносят состояние на следующие строки
+const GRAPHQL_KEYWORDS: &[&str] = &[
+    "query", "mutation", "subscription", "fragment", "on", "true", "false", "null", "type",
+    "input", "enum", "interface", "union", "scalar", "schema", "directive", "extend",
+    "implements", "repeatable",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphQlHighlight {
+    Keyword,
+    Type,
+    Variable,
+    Directive,
+    Number,
+    String,
+    Comment,
+}
+
+pub struct GraphQlHighlighter {
+    // Для каждой строки: начинается ли она внутри """строки"""
+    in_block_string: Vec<bool>,
+    current_line: usize,
+}
+
+impl Highlighter for GraphQlHighlighter {
+    type Settings = ();
+    type Highlight = GraphQlHighlight;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, GraphQlHighlight)>;
+
+    fn new(_settings: &Self::Settings) -> Self {
+        Self {
+            in_block_string: vec![false],
+            current_line: 0,
+        }
+    }
+
+    fn update(&mut self, _new_settings: &Self::Settings) {
+        self.change_line(0);
+    }
+
+    fn change_line(&mut self, line: usize) {
+        let line = line.min(self.current_line);
+        self.in_block_string.truncate(line + 1);
+        self.current_line = line;
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        let mut spans = Vec::new();
+        let mut position = 0;
+        let mut in_block_string = self
+            .in_block_string
+            .get(self.current_line)
+            .copied()
+            .unwrap_or_default();
+
+        while position < line.len() {
+            let rest = &line[position..];
+            if in_block_string {
+                let end = match rest.find("\"\"\"") {
+                    Some(end) => {
+                        in_block_string = false;
+                        end + 3
+                    }
+                    None => rest.len(),
+                };
+                spans.push((position..position + end, GraphQlHighlight::String));
+                position += end;
+                continue;
+            }
+
+            let Some(c) = rest.chars().next() else {
+                break;
+            };
+            let (len, highlight) = match c {
+                '#' => (rest.len(), Some(GraphQlHighlight::Comment)),
+                '"' if rest.starts_with("\"\"\"") => {
+                    match rest[3..].find("\"\"\"") {
+                        Some(end) => (end + 6, Some(GraphQlHighlight::String)),
+                        None => {
+                            in_block_string = true;
+                            (rest.len(), Some(GraphQlHighlight::String))
+                        }
+                    }
+                }
+                '"' => (
+                    graphql_string_len(rest).unwrap_or(rest.len()),
+                    Some(GraphQlHighlight::String),
+                ),
+                '$' | '@' => {
+                    let len = 1 + rest[1..].find(|c| !is_name_char(c)).unwrap_or(rest.len() - 1);
+                    let highlight = if c == '$' {
+                        GraphQlHighlight::Variable
+                    } else {
+                        GraphQlHighlight::Directive
+                    };
+                    (len, Some(highlight))
+                }
+                '-' | '0'..='9' => (
+                    1 + rest[1..]
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                        .unwrap_or(rest.len() - 1),
+                    Some(GraphQlHighlight::Number),
+                ),
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+                    let word = &rest[..len];
+                    let highlight = if GRAPHQL_KEYWORDS.contains(&word) {
+                        Some(GraphQlHighlight::Keyword)
+                    } else if word.starts_with(|c: char| c.is_ascii_uppercase()) {
+                        Some(GraphQlHighlight::Type)
+                    } else {
+                        None
+                    };
+                    (len, highlight)
+                }
+                c => (c.len_utf8(), None),
+            };
+            if let Some(highlight) = highlight {
+                spans.push((position..position + len, highlight));
+            }
+            position += len;
+        }
+
+        self.current_line += 1;
+        self.in_block_string.push(in_block_string);
+        spans.into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current_line
+    }
+}
+
+// Цвета берём из палитры темы приложения
+pub fn graphql_format(highlight: &GraphQlHighlight, theme: &Theme) -> highlighter::Format<Font> {
+    let palette = theme.extended_palette();
+    let color = match highlight {
+        GraphQlHighlight::Keyword => palette.primary.strong.color,
+        GraphQlHighlight::Type => palette.secondary.strong.color,
+        GraphQlHighlight::Variable | GraphQlHighlight::Number => palette.warning.base.color,
+        GraphQlHighlight::Directive => palette.danger.base.color,
+        GraphQlHighlight::String => palette.success.base.color,
+        GraphQlHighlight::Comment => Color {
+            a: 0.5,
+            ..palette.background.base.text
+        },
+    };
+    highlighter::Format {
+        color: Some(color),
+        font: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_formatting() {
+        let pretty = Syntax::Json.prettify(r#"{"b":1,"a":[true,null]}"#).unwrap();
+        assert_eq!(pretty, "{\n  \"b\": 1,\n  \"a\": [\n    true,\n    null\n  ]\n}");
+        assert_eq!(Syntax::Json.minify(&pretty).unwrap(), r#"{"b":1,"a":[true,null]}"#);
+        assert!(Syntax::Json.validate("{\"a\":").is_err());
+        assert!(Syntax::Json.validate("  ").is_ok());
+    }
+
+    #[test]
+    fn xml_keeps_text_and_reindents_between_elements() {
+        let text = "<?xml version=\"1.0\"?>\n<root>\n      <p>Hello <b>big</b> world</p>\n  <q>a &amp; b</q>\n<s> </s>\n</root>\n";
+        assert_eq!(
+            Syntax::Xml.prettify(text).unwrap(),
+            "<?xml version=\"1.0\"?>\n<root>\n  <p>Hello <b>big</b> world</p>\n  <q>a &amp; b</q>\n  <s> </s>\n</root>"
+        );
+        assert_eq!(
+            Syntax::Xml.minify(text).unwrap(),
+            "<?xml version=\"1.0\"?><root><p>Hello <b>big</b> world</p><q>a &amp; b</q><s> </s></root>"
+        );
+    }
+
+    #[test]
+    fn xml_errors() {
+        assert!(Syntax::Xml.validate("<a><b></a>").is_err());
+        assert_eq!(Syntax::Xml.validate("<a/><b/>").unwrap_err(), "Second root element at byte 4");
+        assert!(Syntax::Xml.validate("text<a/>").is_err());
+        assert_eq!(Syntax::Xml.validate("<a>").unwrap_err(), "Unexpected end of document: unclosed element");
+    }
+
+    #[test]
+    fn yaml_formatting() {
+        let text = "a:   1\nb: [x, 'y # z']\n---\nc: \"d\"\n";
+        assert_eq!(Syntax::Yaml.prettify(text).unwrap(), "a: 1\nb:\n- x\n- 'y # z'\n---\nc: d\n");
+        assert_eq!(Syntax::Yaml.minify(text).unwrap(), "{\"a\":1,\"b\":[\"x\",\"y # z\"]}\n---\n{\"c\":\"d\"}");
+        assert!(Syntax::Yaml.validate("a: [1").is_err());
+    }
+
+    #[test]
+    fn yaml_with_comments_or_anchors_is_not_formatted() {
+        assert_eq!(
+            Syntax::Yaml.prettify("a: 1\nb: 2 # two\n").unwrap_err(),
+            "Comment on line 2 would be lost, format YAML without comments"
+        );
+        assert!(Syntax::Yaml.minify("# header\na: 1").is_err());
+        assert!(Syntax::Yaml.prettify("base: &base {x: 1}\nother: *base").is_err());
+        // Решётка и амперсанд внутри значений - не комментарий и не якорь
+        assert!(Syntax::Yaml.prettify("url: http://x/#top\nq: \"# no\"\nname: it's\nop: a&b").is_ok());
+    }
+
+    #[test]
+    fn html_tokens_split_tags_text_and_raw_content() {
+        let tokens = html_tokens("<!DOCTYPE html><p class=\"a>b\">x<br><script>if (a<b) {}</script>").unwrap();
+        let kinds: Vec<String> = tokens
+            .iter()
+            .map(|token| match token {
+                HtmlToken::Open { name, closed, .. } => format!("open {} {}", name, closed),
+                HtmlToken::Close { name, .. } => format!("close {}", name),
+                HtmlToken::Text(text) => format!("text {}", text),
+                HtmlToken::Other(source) => format!("other {}", source),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "other <!DOCTYPE html>",
+                "open p false",
+                "text x",
+                "open br true",
+                "open script false",
+                "text if (a<b) {}",
+                "close script",
+            ]
+        );
+        // Одиночный "<" - текст
+        assert_eq!(html_tokens("<p>1 < 2</p>").unwrap().len(), 5);
+        assert_eq!(html_tokens("<a href='x").unwrap_err(), "Unterminated tag at byte 0");
+        assert_eq!(html_tokens("<script>x").unwrap_err(), "Unclosed <script>");
+    }
+
+    #[test]
+    fn html_check_and_formatting() {
+        assert!(Syntax::Html.validate("<ul><li>one<li>two</ul>").is_ok());
+        assert_eq!(Syntax::Html.validate("<div><span></div>").unwrap_err(), "</div> closes unclosed <span>");
+        assert_eq!(Syntax::Html.validate("</b>").unwrap_err(), "Unexpected </b>");
+
+        let text = "<div>\n<p>Hello   <b>you</b></p><ul><li>one<li>two</ul><pre>  a\n b</pre></div>";
+        assert_eq!(
+            Syntax::Html.prettify(text).unwrap(),
+            "<div>\n  <p>\n    Hello\n    <b>you</b>\n  </p>\n  <ul>\n    <li>\n      one\n    <li>\n      two\n  </ul>\n  <pre>\n  a\n b\n  </pre>\n</div>"
+        );
+        assert_eq!(
+            Syntax::Html.minify(text).unwrap(),
+            "<div><p>Hello <b>you</b></p><ul><li>one<li>two</ul><pre>  a\n b</pre></div>"
+        );
+    }
+
+    #[test]
+    fn graphql_tokens_kinds() {
+        let tokens = graphql_tokens("query Q($id: ID! = 1) @live { ...F # note\n \"\"\"doc \"quoted\" \"\"\" }").unwrap();
+        let kinds: Vec<(GraphQlKind, &str)> = tokens.iter().map(|token| (token.kind, token.text)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (GraphQlKind::Name, "query"),
+                (GraphQlKind::Name, "Q"),
+                (GraphQlKind::Punct, "("),
+                (GraphQlKind::Variable, "$id"),
+                (GraphQlKind::Punct, ":"),
+                (GraphQlKind::Name, "ID"),
+                (GraphQlKind::Punct, "!"),
+                (GraphQlKind::Punct, "="),
+                (GraphQlKind::Number, "1"),
+                (GraphQlKind::Punct, ")"),
+                (GraphQlKind::Directive, "@live"),
+                (GraphQlKind::Punct, "{"),
+                (GraphQlKind::Spread, "..."),
+                (GraphQlKind::Name, "F"),
+                (GraphQlKind::Comment, "# note"),
+                (GraphQlKind::String, "\"\"\"doc \"quoted\" \"\"\""),
+                (GraphQlKind::Punct, "}"),
+            ]
+        );
+        assert_eq!(graphql_tokens("{ a(b: \"x) }").unwrap_err(), "Unterminated string at byte 7");
+        assert_eq!(graphql_tokens("{ $ }").unwrap_err(), "Expected a name after '$' at byte 2");
+        assert_eq!(graphql_tokens("{ a; }").unwrap_err(), "Unexpected character ';' at byte 3");
+    }
+
+    #[test]
+    fn graphql_check_and_formatting() {
+        assert_eq!(Syntax::GraphQl.validate("{ a(b: [1) }").unwrap_err(), "Unexpected ')'");
+        assert_eq!(Syntax::GraphQl.validate("{ a { b }").unwrap_err(), "Unclosed '{'");
+        assert_eq!(Syntax::GraphQl.validate("# only").unwrap_err(), "Document has no definitions");
+
+        let text = "query Q($id: ID!) { user(id: $id, opts: {a: 1}) { id, name @include(if: true) ...F } }\nfragment F on User { email }";
+        assert_eq!(
+            Syntax::GraphQl.prettify(text).unwrap(),
+            "query Q($id: ID!) {\n  user(id: $id, opts: {a: 1}) {\n    id\n    name @include(if: true)\n    ...F\n  }\n}\n\nfragment F on User {\n  email\n}"
+        );
+        assert_eq!(
+            Syntax::GraphQl.minify(text).unwrap(),
+            "query Q($id:ID!){user(id:$id opts:{a:1}){id name@include(if:true)...F}}fragment F on User{email}"
+        );
+    }
+}
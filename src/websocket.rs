@@ -0,0 +1,402 @@
+// WebSocket: соединение живёт в iced Subscription, исходящие кадры попадают
+// туда через канал, входящие и служебные события копятся в журнале
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use iced::futures::channel::mpsc;
+use iced::futures::future::{self, Either};
+use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::widget::{button, column, container, pick_list, row, scrollable, space, text};
+use iced::{Center, Element, Fill, Font};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::Message as Frame;
+
+use crate::Message;
+
+// Больше записей журнал не держит - старые уходят
+const LOG_LIMIT: usize = 1000;
+
+// Как отправлять текст редактора
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameKind {
+    #[default]
+    Text,
+    Binary, // Текст редактора - hex байты: "01 ff 0a"
+}
+
+impl FrameKind {
+    pub const ALL: &'static [FrameKind] = &[FrameKind::Text, FrameKind::Binary];
+}
+
+impl std::fmt::Display for FrameKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameKind::Text => write!(f, "Text"),
+            FrameKind::Binary => write!(f, "Binary (hex)"),
+        }
+    }
+}
+
+// Параметры подключения. Новый id - новое соединение
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Session {
+    pub id: u64,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+// Что уходит в соединение из update
+#[derive(Debug, Clone)]
+enum Outgoing {
+    Frame(Frame),
+    Close,
+}
+
+// Канал в открытое соединение
+#[derive(Debug, Clone)]
+pub struct Connection(mpsc::Sender<Outgoing>);
+
+impl Connection {
+    fn send(&mut self, outgoing: Outgoing) -> Result<(), String> {
+        self.0
+            .try_send(outgoing)
+            .map_err(|_| "Connection is closed".to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Connected(Connection, u16), // Канал и статус ответа на upgrade (101)
+    Received(FrameKind, String),
+    Closed(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    pub direction: Direction,
+    pub text: String,
+}
+
+// Состояние режима WebSocket
+#[derive(Debug, Default)]
+pub struct Panel {
+    pub session: Option<Session>, // Есть - подписка держит соединение
+    pub connection: Option<Connection>,
+    pub frame_kind: FrameKind,
+    pub log: Vec<LogEntry>,
+    next_id: u64,
+}
+
+impl Panel {
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    pub fn push(&mut self, direction: Direction, text: String) {
+        if self.log.len() >= LOG_LIMIT {
+            self.log.remove(0);
+        }
+        self.log.push(LogEntry {
+            time: SystemTime::now(),
+            direction,
+            text,
+        });
+    }
+
+    // ws:// и wss:// - новое соединение, прежнее закрывается вместе с подпиской
+    pub fn connect(&mut self, url: String, headers: Vec<(String, String)>) -> Result<(), String> {
+        if !(url.starts_with("ws://") || url.starts_with("wss://")) {
+            return Err("WebSocket URL must start with ws:// or wss://".to_string());
+        }
+        self.next_id += 1;
+        self.push(Direction::Info, format!("Connecting to {}", url));
+        self.session = Some(Session { id: self.next_id, url, headers });
+        self.connection = None;
+        Ok(())
+    }
+
+    // Закрываем с Close кадром; если соединения ещё нет - просто снимаем подписку
+    pub fn disconnect(&mut self) {
+        match self.connection.as_mut().map(|connection| connection.send(Outgoing::Close)) {
+            Some(Ok(())) => {}
+            _ => {
+                self.session = None;
+                self.connection = None;
+                self.push(Direction::Info, "Disconnected".to_string());
+            }
+        }
+    }
+
+    pub fn send(&mut self, text: &str) -> Result<(), String> {
+        let connection = self.connection.as_mut().ok_or("Not connected")?;
+        let (frame, logged) = match self.frame_kind {
+            FrameKind::Text => (Frame::text(text), text.to_string()),
+            FrameKind::Binary => {
+                let bytes = parse_hex(text)?;
+                let logged = format!("[{} bytes] {}", bytes.len(), hex_dump(&bytes));
+                (Frame::binary(bytes), logged)
+            }
+        };
+        connection.send(Outgoing::Frame(frame))?;
+        self.push(Direction::Sent, logged);
+        Ok(())
+    }
+
+    pub fn handle(&mut self, event: Event) {
+        match event {
+            Event::Connected(connection, status) => {
+                self.connection = Some(connection);
+                self.push(Direction::Info, format!("Connected ({})", status));
+            }
+            Event::Received(FrameKind::Text, text) => self.push(Direction::Received, text),
+            Event::Received(FrameKind::Binary, dump) => self.push(Direction::Received, dump),
+            Event::Closed(reason) => {
+                self.session = None;
+                self.connection = None;
+                self.push(Direction::Info, reason);
+            }
+            Event::Failed(error) => {
+                self.session = None;
+                self.connection = None;
+                self.push(Direction::Error, error);
+            }
+        }
+    }
+}
+
+// "01 ff 0a", "01ff0a", "0x01 0xff" -> байты
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text
+        .split_whitespace()
+        .map(|chunk| chunk.trim_start_matches("0x"))
+        .collect();
+    // Дальше режем по байтам - не-hex символ (в том числе не ASCII) отсекаем сразу
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hex digit: {}", c));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex payload has an odd number of digits".to_string());
+    }
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).unwrap_or_default();
+            u8::from_str_radix(pair, 16).map_err(|_| format!("Invalid hex byte: {}", pair))
+        })
+        .collect()
+}
+
+fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn build_request(
+    session: &Session,
+) -> Result<tokio_tungstenite::tungstenite::handshake::client::Request, String> {
+    let mut request = session
+        .url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid WebSocket URL: {}", e))?;
+    for (key, value) in &session.headers {
+        let name = HeaderName::from_bytes(key.trim().as_bytes())
+            .map_err(|_| format!("Invalid header name: {}", key))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| format!("Invalid value for header {}: {}", key, e))?;
+        request.headers_mut().append(name, value);
+    }
+    Ok(request)
+}
+
+// Поток событий соединения для Subscription::run_with
+pub fn connect(session: &Session) -> impl Stream<Item = Event> {
+    let session = session.clone();
+
+    iced::stream::channel(100, async move |mut output: mpsc::Sender<Event>| {
+        let result = async {
+            let request = build_request(&session)?;
+            tokio_tungstenite::connect_async(request)
+                .await
+                .map_err(|e| format!("Connection failed: {}", e))
+        };
+        let (stream, response) = match result.await {
+            Ok(connected) => connected,
+            Err(error) => {
+                let _ = output.send(Event::Failed(error)).await;
+                return;
+            }
+        };
+
+        let (sender, mut outgoing) = mpsc::channel(100);
+        let _ = output
+            .send(Event::Connected(Connection(sender), response.status().as_u16()))
+            .await;
+
+        let (mut write, mut read) = stream.split();
+        let event = loop {
+            match future::select(read.next(), outgoing.next()).await {
+                Either::Left((Some(Ok(frame)), _)) => {
+                    let event = match frame {
+                        Frame::Text(text) => Event::Received(FrameKind::Text, text.to_string()),
+                        Frame::Binary(bytes) => Event::Received(
+                            FrameKind::Binary,
+                            format!("[{} bytes] {}", bytes.len(), hex_dump(&bytes)),
+                        ),
+                        Frame::Close(frame) => {
+                            break Event::Closed(match frame {
+                                Some(frame) if !frame.reason.is_empty() => {
+                                    format!("Closed by server: {} {}", frame.code, frame.reason)
+                                }
+                                Some(frame) => format!("Closed by server: {}", frame.code),
+                                None => "Closed by server".to_string(),
+                            });
+                        }
+                        // Ping/Pong отвечает сама библиотека
+                        _ => continue,
+                    };
+                    let _ = output.send(event).await;
+                }
+                Either::Left((Some(Err(e)), _)) => break Event::Failed(e.to_string()),
+                Either::Left((None, _)) => break Event::Closed("Connection closed".to_string()),
+                Either::Right((Some(Outgoing::Frame(frame)), _)) => {
+                    if let Err(e) = write.send(frame).await {
+                        break Event::Failed(format!("Send failed: {}", e));
+                    }
+                }
+                Either::Right((Some(Outgoing::Close), _)) | Either::Right((None, _)) => {
+                    let _ = write.send(Frame::Close(None)).await;
+                    break Event::Closed("Disconnected".to_string());
+                }
+            }
+        };
+        let _ = output.send(event).await;
+    })
+}
+
+// 12:34:56.789 по UTC
+pub fn timestamp(time: SystemTime) -> String {
+    let millis = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let secs = (millis / 1000) % 86400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        millis % 1000
+    )
+}
+
+// Журнал соединения вместо панели ответа
+pub fn log_view(panel: &Panel) -> Element<'_, Message> {
+    let status = match (&panel.session, panel.is_connected()) {
+        (Some(_), true) => text("● Connected").size(14).style(text::success),
+        (Some(_), false) => text("● Connecting...").size(14).style(text::secondary),
+        (None, _) => text("● Disconnected").size(14).style(text::secondary),
+    };
+
+    let entries: Vec<Element<Message>> = panel
+        .log
+        .iter()
+        .map(|entry| {
+            let (arrow, style): (&str, fn(&iced::Theme) -> text::Style) = match entry.direction {
+                Direction::Sent => ("→", text::primary),
+                Direction::Received => ("←", text::success),
+                Direction::Info => ("•", text::secondary),
+                Direction::Error => ("✖", text::danger),
+            };
+            row![
+                text(timestamp(entry.time)).size(12).font(Font::MONOSPACE).style(text::secondary),
+                text(arrow).size(12).style(style),
+                text(&entry.text).size(12).font(Font::MONOSPACE).width(Fill),
+            ]
+            .spacing(8)
+            .into()
+        })
+        .collect();
+
+    column![
+        row![
+            status,
+            space().width(Fill),
+            text("Times in UTC").size(11).style(text::secondary),
+            button(text("Clear").size(12))
+                .on_press(Message::WsClearLog)
+                .padding([3, 8])
+                .style(button::secondary),
+        ]
+        .spacing(10)
+        .align_y(Center),
+        container(
+            scrollable(column(entries).spacing(4))
+                .height(300)
+                .width(Fill)
+                .anchor_bottom()
+        )
+        .padding(10)
+        .style(container::bordered_box),
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Над редактором: тип кадра и отправка
+pub fn composer_view(panel: &Panel) -> Element<'_, Message> {
+    row![
+        text("Message:").size(16),
+        pick_list(FrameKind::ALL, Some(panel.frame_kind), Message::WsFrameKindChanged)
+            .text_size(12)
+            .padding(3),
+        space().width(Fill),
+        button(text("Send frame").size(12))
+            .on_press_maybe(panel.is_connected().then_some(Message::WsSend))
+            .padding([3, 8])
+            .style(button::primary),
+    ]
+    .spacing(10)
+    .align_y(Center)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_accepts_spaces_and_prefixes() {
+        assert_eq!(parse_hex("01 ff 0a"), Ok(vec![0x01, 0xff, 0x0a]));
+        assert_eq!(parse_hex("01ff0A"), Ok(vec![0x01, 0xff, 0x0a]));
+        assert_eq!(parse_hex("0x01 0xff\n\t0x0a"), Ok(vec![0x01, 0xff, 0x0a]));
+        assert_eq!(parse_hex("  "), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_hex_rejects_odd_length() {
+        assert_eq!(
+            parse_hex("01 f"),
+            Err("Hex payload has an odd number of digits".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_hex_rejects_non_hex_and_non_ascii() {
+        assert_eq!(parse_hex("zz"), Err("Invalid hex digit: z".to_string()));
+        // 4 байта UTF-8 - раньше паниковало на границе символа
+        assert_eq!(parse_hex("aé1"), Err("Invalid hex digit: é".to_string()));
+        assert_eq!(parse_hex("0xé"), Err("Invalid hex digit: é".to_string()));
+    }
+}